@@ -7,7 +7,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tauri_plugin_dialog::DialogExt;
 
 // ─── Core Domain Types ────────────────────────────────────────────────────────
@@ -30,6 +30,251 @@ pub struct FieldConfig {
     pub env: Vec<EnvVar>,
     /// Absolute path to the project root
     pub project_path: String,
+    /// Where sensitive env vars go: "sops" | "external-secrets" | "sealed-secrets".
+    /// None → project default (`.endfield-config` secrets.backend), then "sops".
+    #[serde(default)]
+    pub secret_backend: Option<String>,
+    /// Probes, securityContext, command/args, graceful shutdown
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    /// Volume claim template of stateful fields
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Extra volumes mounted into the container
+    #[serde(default)]
+    pub volumes: Vec<VolumeConfig>,
+    /// Generate an HPA; `replicas` is then left to the autoscaler
+    #[serde(default)]
+    pub autoscaling: Option<AutoscalingConfig>,
+    /// PDB and spreading of replicas; only applied when more than one replica runs
+    #[serde(default)]
+    pub availability: AvailabilityConfig,
+    /// "deployment" | "statefulset" | "daemonset" | "job" | "cronjob".
+    /// None → StatefulSet for stateful images, Deployment otherwise.
+    #[serde(default)]
+    pub workload_kind: Option<String>,
+    /// Job / CronJob settings
+    #[serde(default)]
+    pub batch: BatchConfig,
+    /// nodeSelector, tolerations, hostNetwork
+    #[serde(default)]
+    pub placement: PlacementConfig,
+    /// Run to completion before the main container starts (wait-for-db, migrations)
+    #[serde(default)]
+    pub init_containers: Vec<ExtraContainer>,
+    /// Run next to the main container for the pod's lifetime (proxy, log agent)
+    #[serde(default)]
+    pub sidecars: Vec<ExtraContainer>,
+    /// Own ServiceAccount with a Role/ClusterRole, for pods calling the Kubernetes API
+    #[serde(default)]
+    pub rbac: Option<RbacConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RbacConfig {
+    /// Namespaced rules → Role + RoleBinding in the field's namespace
+    #[serde(default)]
+    pub rules: Vec<RbacRule>,
+    /// Rules on cluster-scoped resources (nodes, namespaces, …) →
+    /// ClusterRole + ClusterRoleBinding
+    #[serde(default)]
+    pub cluster_rules: Vec<RbacRule>,
+    /// Mount the API token into the pods; off for a ServiceAccount that only
+    /// carries imagePullSecrets or identity
+    #[serde(default = "default_automount_token")]
+    pub automount_token: bool,
+}
+
+fn default_automount_token() -> bool { true }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RbacRule {
+    /// "" (or none) for the core group, e.g. "apps", "batch"
+    #[serde(default)]
+    pub api_groups: Vec<String>,
+    pub resources: Vec<String>,
+    pub verbs: Vec<String>,
+    /// Restrict the rule to these objects
+    #[serde(default)]
+    pub resource_names: Vec<String>,
+}
+
+/// Init container or sidecar. Shares the pod's volumes with the main
+/// container by mounting them by name.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtraContainer {
+    pub name: String,
+    pub image: String,
+    #[serde(default)]
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Sensitive keys go through the workload's Secret, like the main container's
+    #[serde(default)]
+    pub env: Vec<EnvVar>,
+    /// Keys of the workload's existing Secret to expose as env vars
+    #[serde(default)]
+    pub secret_env: Vec<String>,
+    #[serde(default)]
+    pub ports: Vec<u32>,
+    #[serde(default)]
+    pub volume_mounts: Vec<ContainerMount>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerMount {
+    /// Name of a pod volume (`volumes`, or "data" on StatefulSets)
+    pub name: String,
+    pub mount_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Which nodes the pods may run on. Mostly for DaemonSets (agents that
+/// must also run on tainted control-plane nodes), but applies to every kind.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlacementConfig {
+    #[serde(default)]
+    pub node_selector: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub tolerations: Vec<TolerationConfig>,
+    /// Tolerate every taint (`operator: Exists`) — typical for node agents
+    #[serde(default)]
+    pub tolerate_all: bool,
+    /// Use the node's network namespace (opt-in; e.g. node exporters)
+    #[serde(default)]
+    pub host_network: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TolerationConfig {
+    #[serde(default)]
+    pub key: Option<String>,
+    /// "Equal" (default when a value is set) | "Exists"
+    #[serde(default)]
+    pub operator: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+    /// "NoSchedule" | "PreferNoSchedule" | "NoExecute"; None = all effects
+    #[serde(default)]
+    pub effect: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BatchConfig {
+    /// Cron schedule, required for CronJobs, e.g. "0 3 * * *"
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// IANA time zone for the schedule, e.g. "Europe/Berlin"
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// "Allow" | "Forbid" (default) | "Replace"
+    #[serde(default)]
+    pub concurrency_policy: Option<String>,
+    /// Retries before the Job is marked failed, default 3
+    #[serde(default)]
+    pub backoff_limit: Option<u32>,
+    /// Delete finished Jobs after this many seconds, default 86400
+    #[serde(default)]
+    pub ttl_seconds_after_finished: Option<u32>,
+    /// "OnFailure" (default) | "Never"
+    #[serde(default)]
+    pub restart_policy: Option<String>,
+    /// Kill the Job after this many seconds
+    #[serde(default)]
+    pub active_deadline_seconds: Option<u32>,
+    #[serde(default)]
+    pub successful_jobs_history_limit: Option<u32>,
+    #[serde(default)]
+    pub failed_jobs_history_limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AvailabilityConfig {
+    /// Generate a PodDisruptionBudget
+    #[serde(default)]
+    pub pdb: bool,
+    /// PDB minAvailable, count or percentage ("2", "50%")
+    #[serde(default)]
+    pub pdb_min_available: Option<String>,
+    /// PDB maxUnavailable; the default ("1") when neither bound is set
+    #[serde(default)]
+    pub pdb_max_unavailable: Option<String>,
+    /// topologySpreadConstraints across "zone" | "hostname"
+    #[serde(default)]
+    pub spread: Option<String>,
+    /// Pod anti-affinity between replicas on one node: "preferred" | "required"
+    #[serde(default)]
+    pub anti_affinity: Option<String>,
+}
+
+/// `autoscaling/v2` HorizontalPodAutoscaler settings.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutoscalingConfig {
+    pub min_replicas: u32,
+    pub max_replicas: u32,
+    /// Target average CPU utilization, % of requests
+    #[serde(default)]
+    pub cpu_utilization: Option<u32>,
+    /// Target average memory utilization, % of requests
+    #[serde(default)]
+    pub memory_utilization: Option<u32>,
+    #[serde(default)]
+    pub custom_metrics: Vec<CustomMetricTarget>,
+    /// behavior.scaleDown.stabilizationWindowSeconds
+    #[serde(default)]
+    pub scale_down_stabilization_seconds: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomMetricTarget {
+    /// "pods" (per-pod metric, averaged) | "external"
+    pub kind: String,
+    pub name: String,
+    /// Quantity, e.g. "100" or "500m"
+    pub average_value: String,
+    /// Optional matchLabels for the metric selector
+    #[serde(default)]
+    pub selector: std::collections::HashMap<String, String>,
+}
+
+/// PersistentVolumeClaim template for StatefulSet fields.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StorageConfig {
+    /// e.g. "10Gi" (default)
+    #[serde(default)]
+    pub size: Option<String>,
+    /// StorageClass name; None/empty → cluster default class
+    #[serde(default)]
+    pub storage_class: Option<String>,
+    /// "ReadWriteOnce" (default) | "ReadWriteOncePod" | "ReadWriteMany" | "ReadOnlyMany"
+    #[serde(default)]
+    pub access_mode: Option<String>,
+    /// Where the data volume is mounted, default /var/lib/<id>
+    #[serde(default)]
+    pub mount_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VolumeConfig {
+    /// Volume name, unique within the pod
+    pub name: String,
+    /// "configMap" | "secret" | "emptyDir" | "pvc"
+    pub kind: String,
+    /// ConfigMap / Secret / PersistentVolumeClaim name (unused for emptyDir)
+    #[serde(default)]
+    pub source: Option<String>,
+    pub mount_path: String,
+    #[serde(default)]
+    pub sub_path: Option<String>,
+    #[serde(default)]
+    pub read_only: bool,
+    /// emptyDir sizeLimit, e.g. "1Gi"
+    #[serde(default)]
+    pub size_limit: Option<String>,
+    /// emptyDir medium: "Memory" for tmpfs
+    #[serde(default)]
+    pub medium: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -38,6 +283,80 @@ pub struct EnvVar {
     pub value: String,
 }
 
+/// Probes, lifecycle and security settings of the main container.
+/// Anything left unset falls back to defaults for the image's type_id
+/// (see `default_container_runtime`).
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ContainerRuntime {
+    #[serde(default)]
+    pub liveness_probe: Option<ProbeConfig>,
+    #[serde(default)]
+    pub readiness_probe: Option<ProbeConfig>,
+    #[serde(default)]
+    pub startup_probe: Option<ProbeConfig>,
+    #[serde(default)]
+    pub security_context: Option<SecurityContextConfig>,
+    /// Overrides the image ENTRYPOINT
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Overrides the image CMD
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Pod terminationGracePeriodSeconds
+    #[serde(default)]
+    pub termination_grace_period_seconds: Option<u32>,
+    /// `preStop` sleep so endpoints drain before SIGTERM; 0 disables it.
+    /// Uses the kubelet's native sleep action (Kubernetes 1.29+), so it
+    /// works on images without a shell.
+    #[serde(default)]
+    pub pre_stop_sleep_seconds: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProbeConfig {
+    /// "http" | "tcp" | "exec" | "none" ("none" disables the default probe)
+    pub kind: String,
+    /// HTTP path, default "/"
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Defaults to the container port
+    #[serde(default)]
+    pub port: Option<u32>,
+    /// Command for "exec" probes
+    #[serde(default)]
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub initial_delay_seconds: Option<u32>,
+    #[serde(default)]
+    pub period_seconds: Option<u32>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u32>,
+    #[serde(default)]
+    pub failure_threshold: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SecurityContextConfig {
+    #[serde(default)]
+    pub run_as_non_root: Option<bool>,
+    #[serde(default)]
+    pub run_as_user: Option<u64>,
+    #[serde(default)]
+    pub run_as_group: Option<u64>,
+    /// Pod-level: group ownership applied to mounted volumes
+    #[serde(default)]
+    pub fs_group: Option<u64>,
+    #[serde(default)]
+    pub read_only_root_filesystem: Option<bool>,
+    #[serde(default)]
+    pub allow_privilege_escalation: Option<bool>,
+    /// Capabilities to drop, e.g. ["ALL"]
+    #[serde(default)]
+    pub drop_capabilities: Vec<String>,
+    #[serde(default)]
+    pub add_capabilities: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InfraConfig {
     /// Unique id / Helm release name
@@ -83,6 +402,9 @@ pub struct DeployResult {
     pub success: bool,
     /// Shell commands that were actually executed
     pub commands_run: Vec<String>,
+    /// Outcome of the post-deploy wait, when one was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readiness: Option<ReadinessReport>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,6 +415,24 @@ pub struct DiffResult {
     pub error: Option<String>,
 }
 
+/// Outcome of a server-side dry-run (`kubectl apply --dry-run=server`).
+/// Objects are reported as `kind.group/name`, exactly as kubectl prints them.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DryRunResult {
+    pub created: Vec<String>,
+    pub configured: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Validation errors and admission webhook denials, one per message
+    pub rejected: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Shell commands that were actually executed
+    pub commands_run: Vec<String>,
+    /// False when anything was rejected or a command failed
+    pub success: bool,
+    /// Failure before the server got to see the objects (paths, helm, spawn)
+    pub error: Option<String>,
+}
+
 // ─── Existing Types (unchanged) ───────────────────────────────────────────────
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -122,6 +462,48 @@ pub struct YamlNode {
     pub y: f64,
     pub group_x: Option<f64>,
     pub group_y: Option<f64>,
+    /// Secrets the workload references (env, envFrom, volumes, pull secrets)
+    #[serde(default)]
+    pub secrets: Vec<LinkedSecret>,
+    /// HPA in the project that scales this workload — replicas are then
+    /// owned by the autoscaler, not the manifest
+    #[serde(default)]
+    pub hpa: Option<HpaInfo>,
+    /// Name of the PodDisruptionBudget in the project selecting this workload's pods
+    #[serde(default)]
+    pub pdb: Option<String>,
+    /// Every container of the pod template, init containers included
+    #[serde(default)]
+    pub containers: Vec<ContainerInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerInfo {
+    pub name: String,
+    pub image: String,
+    /// "main" | "sidecar" | "init"
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HpaInfo {
+    pub name: String,
+    pub namespace: String,
+    pub target_kind: String,
+    pub target_name: String,
+    pub min_replicas: u32,
+    pub max_replicas: u32,
+    pub file_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinkedSecret {
+    /// Secret name as seen by the workload
+    pub name: String,
+    /// "Secret" | "ExternalSecret" | "SealedSecret", or empty if no project
+    /// file produces it (created out of band, or missing)
+    pub kind: String,
+    pub file_path: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -129,16 +511,56 @@ pub struct ScanResult {
     pub nodes: Vec<YamlNode>,
     pub project_path: String,
     pub errors: Vec<String>,
+    /// Findings that aren't parse errors (e.g. multi-replica workload without a PDB)
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PodInfo {
     pub name: String,
     pub namespace: String,
+    /// Pod phase: Pending | Running | Succeeded | Failed | Unknown
     pub phase: String,
+    /// What `kubectl get pods` shows as STATUS: the phase, or why the pod
+    /// isn't healthy (CrashLoopBackOff, ImagePullBackOff, Init:0/1, Terminating, …)
+    #[serde(default)]
+    pub status: String,
     pub ready: u32,
     pub total: u32,
     pub restarts: u32,
+    /// Node the pod is scheduled on
+    #[serde(default)]
+    pub node: Option<String>,
+    #[serde(default)]
+    pub pod_ip: Option<String>,
+    #[serde(default)]
+    pub start_time: Option<String>,
+    /// Init containers first, then the regular ones
+    #[serde(default)]
+    pub containers: Vec<ContainerStatusInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerStatusInfo {
+    pub name: String,
+    #[serde(default)]
+    pub init: bool,
+    /// Image the kubelet actually runs, and its resolved digest
+    pub image: String,
+    pub image_id: Option<String>,
+    pub ready: bool,
+    pub restarts: u32,
+    /// "running" | "waiting" | "terminated"
+    pub state: String,
+    /// Waiting / terminated reason, e.g. CrashLoopBackOff, OOMKilled, Completed
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub exit_code: Option<i64>,
+    pub started_at: Option<String>,
+    /// How the previous instance ended, e.g. after a crash
+    pub last_termination_reason: Option<String>,
+    pub last_exit_code: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -150,6 +572,25 @@ pub struct FieldStatus {
     pub available: u32,
     pub status: String,
     pub pods: Vec<PodInfo>,
+    /// "Deployment" | "StatefulSet" | "DaemonSet"
+    #[serde(default)]
+    pub kind: String,
+    /// DaemonSets: nodes currently running a pod (desired = nodes that should)
+    #[serde(default)]
+    pub current: Option<u32>,
+    /// Live HPA state when the workload is autoscaled
+    #[serde(default)]
+    pub autoscaler: Option<AutoscalerStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutoscalerStatus {
+    pub name: String,
+    pub min_replicas: u32,
+    pub max_replicas: u32,
+    pub current_replicas: u32,
+    /// What the HPA last decided to scale to
+    pub desired_replicas: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -166,6 +607,9 @@ pub struct DeleteResult {
     pub file_errors: Vec<String>,
     pub kubectl_output: Option<String>,
     pub kubectl_error: Option<String>,
+    /// kubectl invocations and file removals that were actually executed
+    #[serde(default)]
+    pub commands_run: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -224,6 +668,90 @@ fn run_helm_output(args: &[&str], cwd: &Path) -> (String, String, bool) {
     }
 }
 
+/// Run kubectl with `input` piped to stdin (for `-f -`).
+fn run_kubectl_stdin(args: &[&str], input: &str) -> (String, String, bool) {
+    use std::io::Write;
+    let child = Command::new("kubectl")
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => return (String::new(), format!("kubectl not found: {}", e), false),
+    };
+    if let Some(stdin) = child.stdin.as_mut() {
+        if let Err(e) = stdin.write_all(input.as_bytes()) {
+            return (String::new(), format!("stdin write error: {}", e), false);
+        }
+    }
+    match child.wait_with_output() {
+        Ok(out) => (
+            String::from_utf8_lossy(&out.stdout).to_string(),
+            String::from_utf8_lossy(&out.stderr).to_string(),
+            out.status.success(),
+        ),
+        Err(e) => (String::new(), format!("kubectl wait error: {}", e), false),
+    }
+}
+
+/// Run helm with `input` piped to stdin (for `--values -`).
+fn run_helm_stdin(args: &[&str], cwd: &Path, input: &str) -> (String, String, bool) {
+    use std::io::Write;
+    let child = Command::new("helm")
+        .args(args)
+        .current_dir(cwd)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => return (String::new(), format!("helm not found: {}", e), false),
+    };
+    if let Some(stdin) = child.stdin.as_mut() {
+        if let Err(e) = stdin.write_all(input.as_bytes()) {
+            return (String::new(), format!("stdin write error: {}", e), false);
+        }
+    }
+    match child.wait_with_output() {
+        Ok(out) => (
+            String::from_utf8_lossy(&out.stdout).to_string(),
+            String::from_utf8_lossy(&out.stderr).to_string(),
+            out.status.success(),
+        ),
+        Err(e) => (String::new(), format!("helm wait error: {}", e), false),
+    }
+}
+
+/// Sort `kubectl apply --dry-run=server` output into the result buckets.
+/// stdout lines look like `deployment.apps/api configured (server dry run)`;
+/// rejections arrive on stderr as `Error from server (...): ...`.
+fn collect_dry_run_output(stdout: &str, stderr: &str, result: &mut DryRunResult) {
+    for line in stdout.lines() {
+        let line = line.trim().trim_end_matches("(server dry run)").trim();
+        let Some((object, verb)) = line.rsplit_once(' ') else { continue };
+        match verb {
+            "created" => result.created.push(object.to_string()),
+            "configured" | "serverside-applied" => result.configured.push(object.to_string()),
+            "unchanged" => result.unchanged.push(object.to_string()),
+            _ => {}
+        }
+    }
+    for line in stderr.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(w) = line.strip_prefix("Warning:") {
+            result.warnings.push(w.trim().to_string());
+        } else {
+            result.rejected.push(line.to_string());
+        }
+    }
+}
+
 /// Ensure namespace exists in the cluster. Returns true if it had to be created.
 fn ensure_namespace(namespace: &str) -> Result<bool, String> {
     // Check if namespace already exists
@@ -245,6 +773,77 @@ fn parse_ready(s: &str) -> (u32, u32) {
     }
 }
 
+/// One item of `kubectl get pods -o json`, with its owner (kind, name).
+fn parse_pod(item: &serde_json::Value) -> (PodInfo, Option<(String, String)>) {
+    let text = |v: &serde_json::Value| v.as_str().map(String::from);
+    let status = &item["status"];
+    let container = |c: &serde_json::Value, init: bool| {
+        let (state, detail) = match c["state"].as_object().and_then(|s| s.iter().next()) {
+            Some((state, detail)) => (state.clone(), detail.clone()),
+            None => ("waiting".to_string(), serde_json::Value::Null),
+        };
+        let last = c["lastState"]["terminated"].clone();
+        ContainerStatusInfo {
+            name: text(&c["name"]).unwrap_or_default(),
+            init,
+            image: text(&c["image"]).unwrap_or_default(),
+            image_id: text(&c["imageID"]).filter(|s| !s.is_empty()),
+            ready: c["ready"].as_bool().unwrap_or(false),
+            restarts: c["restartCount"].as_u64().unwrap_or(0) as u32,
+            state,
+            reason: text(&detail["reason"]),
+            message: text(&detail["message"]),
+            exit_code: detail["exitCode"].as_i64(),
+            started_at: text(&detail["startedAt"]),
+            last_termination_reason: text(&last["reason"]),
+            last_exit_code: last["exitCode"].as_i64(),
+        }
+    };
+    let statuses = |key: &str, init: bool| -> Vec<ContainerStatusInfo> {
+        status[key].as_array().map(|a| a.iter().map(|c| container(c, init)).collect()).unwrap_or_default()
+    };
+    let init = statuses("initContainerStatuses", true);
+    let regular = statuses("containerStatuses", false);
+
+    let phase = text(&status["phase"]).unwrap_or_else(|| "Unknown".to_string());
+    let unfinished_init = init.iter().position(|c| !(c.state == "terminated" && c.exit_code == Some(0)));
+    let display = if !item["metadata"]["deletionTimestamp"].is_null() {
+        "Terminating".to_string()
+    } else if let Some(i) = unfinished_init.filter(|_| phase == "Pending") {
+        match init[i].reason.as_deref() {
+            Some(reason) if reason != "PodInitializing" => format!("Init:{}", reason),
+            _ => format!("Init:{}/{}", i, init.len()),
+        }
+    } else {
+        regular
+            .iter()
+            .find_map(|c| c.reason.clone().filter(|_| c.state != "running"))
+            .or_else(|| text(&status["reason"]))
+            .unwrap_or_else(|| phase.clone())
+    };
+    let owner = item["metadata"]["ownerReferences"][0].as_object().map(|o| {
+        (
+            o.get("kind").and_then(|k| k.as_str()).unwrap_or_default().to_string(),
+            o.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+        )
+    });
+
+    let pod = PodInfo {
+        name: text(&item["metadata"]["name"]).unwrap_or_default(),
+        namespace: text(&item["metadata"]["namespace"]).unwrap_or_default(),
+        phase,
+        status: display,
+        ready: regular.iter().filter(|c| c.ready).count() as u32,
+        total: item["spec"]["containers"].as_array().map_or(regular.len(), |c| c.len()) as u32,
+        restarts: regular.iter().map(|c| c.restarts).sum(),
+        node: text(&item["spec"]["nodeName"]),
+        pod_ip: text(&status["podIP"]),
+        start_time: text(&status["startTime"]),
+        containers: init.into_iter().chain(regular).collect(),
+    };
+    (pod, owner)
+}
+
 fn compute_status(ready: u32, desired: u32) -> &'static str {
     if desired == 0 {
         return "gray";
@@ -258,6 +857,154 @@ fn compute_status(ready: u32, desired: u32) -> &'static str {
     "green"
 }
 
+// ─── Project root guard ───────────────────────────────────────────────────────
+
+/// Canonical root of the project currently open in the IDE.
+/// Registered when the project is scanned/watched; every file-touching
+/// command resolves its paths against it and refuses anything outside.
+/// Once a root is active, only a folder picked in the native dialog can
+/// replace it — a path typed by the webview cannot widen the confinement.
+#[derive(Default)]
+pub struct ProjectRootState {
+    root: Mutex<Option<PathBuf>>,
+    /// Canonical folder last returned by `open_folder_dialog`
+    picked: Mutex<Option<PathBuf>>,
+}
+
+#[derive(Debug)]
+pub enum PathGuardError {
+    /// No project has been opened yet
+    NoProject,
+    /// Path (after resolving symlinks) lies outside the project root
+    OutsideProject { path: String, root: String },
+    /// Path is the project root itself, where a sub-path is required
+    IsProjectRoot { path: String },
+    /// Path is relative, contains `..` past existing dirs, or cannot be resolved
+    Unresolvable { path: String, reason: String },
+    /// Another project is open and this folder was not picked in the dialog
+    NotPicked { path: String },
+}
+
+impl std::fmt::Display for PathGuardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathGuardError::NoProject => write!(f, "No project is open"),
+            PathGuardError::OutsideProject { path, root } => {
+                write!(f, "Path {} is outside the project {}", path, root)
+            }
+            PathGuardError::IsProjectRoot { path } => {
+                write!(f, "Refusing to operate on the project root {}", path)
+            }
+            PathGuardError::Unresolvable { path, reason } => {
+                write!(f, "Cannot resolve path {}: {}", path, reason)
+            }
+            PathGuardError::NotPicked { path } => {
+                write!(f, "Another project is open; pick {} in the folder dialog to switch to it", path)
+            }
+        }
+    }
+}
+
+impl From<PathGuardError> for String {
+    fn from(e: PathGuardError) -> String {
+        e.to_string()
+    }
+}
+
+/// Canonicalize `path`, following symlinks. Paths that do not exist yet
+/// (e.g. a file about to be created) are resolved via their nearest existing
+/// ancestor; the missing tail may not contain `..`.
+fn resolve_path(path: &Path) -> Result<PathBuf, PathGuardError> {
+    let unresolvable = |reason: &str| PathGuardError::Unresolvable {
+        path: path.display().to_string(),
+        reason: reason.to_string(),
+    };
+    if !path.is_absolute() {
+        return Err(unresolvable("path must be absolute"));
+    }
+
+    let mut existing = path.to_path_buf();
+    let mut tail: Vec<std::ffi::OsString> = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(mut resolved) => {
+                for part in tail.iter().rev() {
+                    resolved.push(part);
+                }
+                return Ok(resolved);
+            }
+            Err(e) => {
+                if existing.components().next_back() == Some(std::path::Component::ParentDir) {
+                    return Err(unresolvable("`..` in a part of the path that does not exist"));
+                }
+                let Some(name) = existing.file_name() else {
+                    return Err(unresolvable(&e.to_string()));
+                };
+                tail.push(name.to_os_string());
+                if !existing.pop() {
+                    return Err(unresolvable(&e.to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// Store `project_path` as the active project root. Returns the canonical root.
+/// The first project of a session may come from anywhere (recent projects);
+/// replacing an open one requires the folder picked last in the dialog.
+fn register_project_root(
+    state: &ProjectRootState,
+    project_path: &str,
+) -> Result<PathBuf, PathGuardError> {
+    let root = Path::new(project_path)
+        .canonicalize()
+        .map_err(|e| PathGuardError::Unresolvable {
+            path: project_path.to_string(),
+            reason: e.to_string(),
+        })?;
+    let mut current = state.root.lock().unwrap();
+    if current.as_ref().is_some_and(|c| *c != root) {
+        let mut picked = state.picked.lock().unwrap();
+        if picked.as_ref() != Some(&root) {
+            return Err(PathGuardError::NotPicked {
+                path: root.display().to_string(),
+            });
+        }
+        *picked = None;
+    }
+    *current = Some(root.clone());
+    Ok(root)
+}
+
+fn project_root(state: &ProjectRootState) -> Result<PathBuf, PathGuardError> {
+    state.root.lock().unwrap().clone().ok_or(PathGuardError::NoProject)
+}
+
+/// Resolve `path` and ensure it lies inside the active project (root included).
+fn confine_path(state: &ProjectRootState, path: &str) -> Result<PathBuf, PathGuardError> {
+    let root = project_root(state)?;
+    let resolved = resolve_path(Path::new(path))?;
+    if !resolved.starts_with(&root) {
+        return Err(PathGuardError::OutsideProject {
+            path: resolved.display().to_string(),
+            root: root.display().to_string(),
+        });
+    }
+    Ok(resolved)
+}
+
+/// Like `confine_path`, but also rejects the project root itself.
+/// Used for destructive operations on sub-trees (delete, apply dir).
+fn confine_subpath(state: &ProjectRootState, path: &str) -> Result<PathBuf, PathGuardError> {
+    let resolved = confine_path(state, path)?;
+    if resolved == project_root(state)? {
+        return Err(PathGuardError::IsProjectRoot {
+            path: resolved.display().to_string(),
+        });
+    }
+    Ok(resolved)
+}
+
 // ─── image → type_id ─────────────────────────────────────────────────────────
 
 fn image_to_type_id(image: &str) -> &'static str {
@@ -379,6 +1126,69 @@ fn extract_metadata_field<'a>(content: &'a str, key: &str) -> Option<&'a str> {
     None
 }
 
+/// First `field:` value inside the first `block:` mapping, at any depth
+/// (e.g. `scaleTargetRef:` → `name`).
+fn extract_block_field(content: &str, block: &str, field: &str) -> Option<String> {
+    let block_key = format!("{}:", block);
+    let field_key = format!("{}:", field);
+    let mut block_indent: Option<usize> = None;
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let t = line.trim();
+        match block_indent {
+            None if t == block_key => block_indent = Some(indent),
+            Some(bi) if !t.is_empty() && indent <= bi => return None,
+            Some(_) => {
+                if let Some(value) = t.trim_start_matches("- ").strip_prefix(&field_key) {
+                    let value = value.trim().trim_matches('"').trim_matches('\'');
+                    if !value.is_empty() {
+                        return Some(value.to_string());
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/// Label-style key/value pairs, in manifest order.
+type Labels = Vec<(String, String)>;
+
+/// Entries of the first `map_key:` mapping inside the first `block:` mapping
+/// (e.g. `template:` → `labels:`).
+fn extract_block_map(content: &str, block: &str, map_key: &str) -> Labels {
+    let block_key = format!("{}:", block);
+    let map_key = format!("{}:", map_key);
+    let mut block_indent: Option<usize> = None;
+    let mut map_indent: Option<usize> = None;
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let t = line.trim();
+        if t.is_empty() || t.starts_with('#') {
+            continue;
+        }
+        match (block_indent, map_indent) {
+            (None, _) if t == block_key => block_indent = Some(indent),
+            (None, _) => {}
+            (Some(bi), None) if indent <= bi => break,
+            (Some(_), None) if t == map_key => map_indent = Some(indent),
+            (Some(_), None) => {}
+            (Some(_), Some(mi)) if indent <= mi => break,
+            (Some(_), Some(_)) => {
+                if let Some((k, v)) = t.split_once(':') {
+                    entries.push((
+                        k.trim().to_string(),
+                        v.trim().trim_matches('"').trim_matches('\'').to_string(),
+                    ));
+                }
+            }
+        }
+    }
+    entries
+}
+
 fn extract_images(content: &str) -> Vec<String> {
     content
         .lines()
@@ -395,6 +1205,29 @@ fn extract_images(content: &str) -> Vec<String> {
         .collect()
 }
 
+/// Containers of the pod template. The first `containers` entry is the main
+/// container, later ones are sidecars; init containers with
+/// `restartPolicy: Always` are native sidecars.
+fn extract_containers(doc: &str) -> Vec<ContainerInfo> {
+    let info = |item: &str, role: &str| {
+        Some(ContainerInfo {
+            name: list_item_field(item, "name")?,
+            image: list_item_field(item, "image").unwrap_or_default(),
+            role: role.to_string(),
+        })
+    };
+    let mut containers: Vec<ContainerInfo> = block_list_items(doc, "containers")
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| info(item, if i == 0 { "main" } else { "sidecar" }))
+        .collect();
+    containers.extend(block_list_items(doc, "initContainers").iter().filter_map(|item| {
+        let native = list_item_field(item, "restartPolicy").as_deref() == Some("Always");
+        info(item, if native { "sidecar" } else { "init" })
+    }));
+    containers
+}
+
 fn extract_replicas(content: &str) -> Option<u32> {
     content.lines().find_map(|line| {
         line.trim()
@@ -502,6 +1335,10 @@ fn try_parse_helm_node(component_dir: &Path) -> Option<YamlNode> {
         y: 0.0,
         group_x: None,
         group_y: None,
+        secrets: vec![],
+        hpa: None,
+        pdb: None,
+        containers: vec![],
     })
 }
 
@@ -532,7 +1369,12 @@ fn parse_yaml_doc(doc: &str, path: &Path, idx: usize) -> Option<YamlNode> {
         .unwrap_or("default")
         .to_string();
     let replicas = extract_replicas(doc);
-    let images = extract_images(doc);
+    let containers = extract_containers(doc);
+    // the main container decides the type — not an init container listed first
+    let images: Vec<String> = containers.iter()
+        .find(|c| c.role == "main")
+        .map(|c| vec![c.image.clone()])
+        .unwrap_or_else(|| extract_images(doc));
 
     let (image, type_id) = if let Some(img) = images.first() {
         (img.clone(), image_to_type_id(img).to_string())
@@ -570,6 +1412,13 @@ fn parse_yaml_doc(doc: &str, path: &Path, idx: usize) -> Option<YamlNode> {
         y: 0.0,
         group_x: None,
         group_y: None,
+        secrets: extract_secret_refs(doc)
+            .into_iter()
+            .map(|name| LinkedSecret { name, kind: String::new(), file_path: String::new() })
+            .collect(),
+        hpa: None,
+        pdb: None,
+        containers,
     })
 }
 
@@ -719,67 +1568,589 @@ fn split_rendered_manifests(raw: &str) -> Vec<(String, String)> {
 
 // ─── YAML code generators ─────────────────────────────────────────────────────
 
-fn generate_deployment_yaml(cfg: &FieldConfig) -> String {
-    let secret_keys = ["PASSWORD", "SECRET", "KEY", "TOKEN", "PASS"];
-    let has_secret = cfg.env.iter()
-        .any(|e| secret_keys.iter().any(|k| e.key.to_uppercase().contains(k)));
-    let secret_name = format!("{}-secret", cfg.id);
+/// Env keys containing any of these are treated as secrets.
+const SECRET_KEY_HINTS: [&str; 5] = ["PASSWORD", "SECRET", "KEY", "TOKEN", "PASS"];
 
-    let env_block = if cfg.env.is_empty() {
-        String::new()
-    } else {
-        let vars: String = cfg.env.iter().map(|e| {
-            let is_sensitive = secret_keys.iter().any(|k| e.key.to_uppercase().contains(k));
-            if is_sensitive && has_secret {
-                format!(
-                    "            - name: {key}\n              valueFrom:\n                secretKeyRef:\n                  name: {secret}\n                  key: {key}\n",
-                    key = e.key, secret = secret_name,
-                )
-            } else {
-                format!("            - name: {}\n              value: \"{}\"\n", e.key, e.value)
-            }
-        }).collect();
-        format!("          env:\n{}\n", vars)
-    };
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SECRET_KEY_HINTS.iter().any(|k| key.contains(k))
+}
+
+/// Container `env:` block for a field. Sensitive keys are always referenced
+/// from `<id>-secret` — their values never appear in the workload manifest.
+fn field_env_block(cfg: &FieldConfig) -> String {
+    if cfg.env.is_empty() {
+        return String::new();
+    }
+    format!("          env:\n{}\n", field_env_lines(&cfg.env, &format!("{}-secret", cfg.id)))
+}
+
+/// Container `env` entries; sensitive keys reference the field's Secret.
+fn field_env_lines(vars: &[EnvVar], secret_name: &str) -> String {
+    vars.iter().map(|e| {
+        if is_sensitive_key(&e.key) {
+            secret_env_line(&e.key, secret_name)
+        } else {
+            format!("            - name: {}\n              value: \"{}\"\n", e.key, e.value)
+        }
+    }).collect()
+}
 
+fn secret_env_line(key: &str, secret_name: &str) -> String {
     format!(
-        r#"apiVersion: apps/v1
-kind: Deployment
-metadata:
-  name: {name}
-  namespace: {ns}
-  labels:
-    app: {name}
-    managed-by: endfield
-spec:
-  replicas: {replicas}
-  selector:
-    matchLabels:
-      app: {name}
-  template:
-    metadata:
-      labels:
-        app: {name}
-    spec:
-      containers:
-        - name: {name}
-          image: {image}
-          ports:
-            - containerPort: {port}
-{env}          resources:
-            requests:
-              cpu: "100m"
+        "            - name: {key}\n              valueFrom:\n                secretKeyRef:\n                  name: {secret}\n                  key: {key}\n",
+        key = key, secret = secret_name,
+    )
+}
+
+/// Sensitive env vars of every container in the field — these make up the
+/// field's Secret. The first value wins when containers share a key.
+fn field_sensitive_env(cfg: &FieldConfig) -> Vec<&EnvVar> {
+    let mut seen = std::collections::HashSet::new();
+    cfg.env.iter()
+        .chain(cfg.init_containers.iter().flat_map(|c| c.env.iter()))
+        .chain(cfg.sidecars.iter().flat_map(|c| c.env.iter()))
+        .filter(|e| is_sensitive_key(&e.key) && seen.insert(e.key.as_str()))
+        .collect()
+}
+
+fn check_extra_container(c: &ExtraContainer) -> Result<(), String> {
+    if c.name.is_empty() || c.image.is_empty() {
+        return Err(format!("Container '{}' needs both a name and an image — skipped", c.name));
+    }
+    Ok(())
+}
+
+/// Problems with the extra containers of a workload: missing fields,
+/// names clashing with the main container or each other, mounts of volumes
+/// the pod doesn't have and keys the workload's Secret doesn't define.
+fn extra_container_warnings(
+    main: &str,
+    init: &[ExtraContainer],
+    sidecars: &[ExtraContainer],
+    volumes: &[String],
+    secret_keys: &[&str],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut names = vec![main.to_string()];
+    for c in init.iter().chain(sidecars) {
+        if let Err(e) = check_extra_container(c) {
+            warnings.push(e);
+            continue;
+        }
+        if names.contains(&c.name) {
+            warnings.push(format!("Container name '{}' is used twice in the pod", c.name));
+        }
+        names.push(c.name.clone());
+        for m in c.volume_mounts.iter().filter(|m| !volumes.contains(&m.name)) {
+            warnings.push(format!(
+                "Container {} mounts unknown volume '{}' — mount skipped",
+                c.name, m.name
+            ));
+        }
+        for key in c.secret_env.iter().filter(|k| !secret_keys.contains(&k.as_str())) {
+            warnings.push(format!(
+                "Container {} reads secret key {} which the workload's Secret doesn't define",
+                c.name, key
+            ));
+        }
+    }
+    warnings
+}
+
+/// One `containers` / `initContainers` entry. `env` holds the rendered
+/// entries of `c.env`; `restart_always` turns an init container into a
+/// native sidecar (Kubernetes 1.29+).
+fn extra_container_item(
+    c: &ExtraContainer,
+    env: &str,
+    secret_name: &str,
+    volumes: &[String],
+    restart_always: bool,
+) -> String {
+    let mut out = format!("        - name: {}\n          image: {}\n", c.name, c.image);
+    if restart_always {
+        out.push_str("          restartPolicy: Always\n");
+    }
+    if !c.command.is_empty() {
+        out.push_str(&format!("          command: {}\n", yaml_flow_list(&c.command)));
+    }
+    if !c.args.is_empty() {
+        out.push_str(&format!("          args: {}\n", yaml_flow_list(&c.args)));
+    }
+    if !c.ports.is_empty() {
+        out.push_str("          ports:\n");
+        for p in &c.ports {
+            out.push_str(&format!("            - containerPort: {}\n", p));
+        }
+    }
+    let secret_refs: String = c.secret_env.iter().map(|k| secret_env_line(k, secret_name)).collect();
+    if !env.is_empty() || !secret_refs.is_empty() {
+        out.push_str(&format!("          env:\n{}{}", env, secret_refs));
+    }
+    out.push_str(
+        "          resources:\n            requests:\n              cpu: \"50m\"\n              memory: \"64Mi\"\n            limits:\n              cpu: \"200m\"\n              memory: \"256Mi\"\n",
+    );
+    let mounts: String = c.volume_mounts.iter()
+        .filter(|m| volumes.contains(&m.name))
+        .map(|m| {
+            let mut line = format!("            - name: {}\n              mountPath: {}\n", m.name, m.mount_path);
+            if m.read_only {
+                line.push_str("              readOnly: true\n");
+            }
+            line
+        })
+        .collect();
+    if !mounts.is_empty() {
+        out.push_str(&format!("          volumeMounts:\n{}", mounts));
+    }
+    out
+}
+
+/// Pod-level `initContainers:` block and the sidecar entries appended to
+/// `containers:`. With `native_sidecars` (batch pods) sidecars become init
+/// containers with `restartPolicy: Always`, so they don't keep a Job from
+/// completing.
+fn extra_containers_blocks(
+    init: &[ExtraContainer],
+    sidecars: &[ExtraContainer],
+    env_of: impl Fn(&[EnvVar]) -> String,
+    secret_name: &str,
+    volumes: &[String],
+    native_sidecars: bool,
+) -> (String, String) {
+    let item = |c: &ExtraContainer, native: bool| {
+        extra_container_item(c, &env_of(&c.env), secret_name, volumes, native)
+    };
+    let valid = |c: &&ExtraContainer| check_extra_container(c).is_ok();
+    let mut init_items: String = init.iter().filter(valid).map(|c| item(c, false)).collect();
+    let mut sidecar_items = String::new();
+    for c in sidecars.iter().filter(valid) {
+        if native_sidecars {
+            init_items.push_str(&item(c, true));
+        } else {
+            sidecar_items.push_str(&item(c, false));
+        }
+    }
+    let init_block = if init_items.is_empty() {
+        String::new()
+    } else {
+        format!("      initContainers:\n{}", init_items)
+    };
+    (init_block, sidecar_items)
+}
+
+/// Names of the valid pod volumes extra containers may mount.
+fn pod_volume_names(volumes: &[VolumeConfig]) -> Vec<String> {
+    volumes.iter().filter(|v| check_volume(v).is_ok()).map(|v| v.name.clone()).collect()
+}
+
+/// `extra_containers_blocks` for a field: env goes through the field's Secret.
+fn field_extra_containers(cfg: &FieldConfig, volumes: &[String], native_sidecars: bool) -> (String, String) {
+    let secret_name = format!("{}-secret", cfg.id);
+    extra_containers_blocks(
+        &cfg.init_containers,
+        &cfg.sidecars,
+        |env| field_env_lines(env, &secret_name),
+        &secret_name,
+        volumes,
+        native_sidecars,
+    )
+}
+
+fn tcp_probe(initial_delay: u32, period: u32, failure_threshold: u32) -> ProbeConfig {
+    ProbeConfig {
+        kind: "tcp".to_string(),
+        path: None,
+        port: None,
+        command: vec![],
+        initial_delay_seconds: (initial_delay > 0).then_some(initial_delay),
+        period_seconds: Some(period),
+        timeout_seconds: None,
+        failure_threshold: Some(failure_threshold),
+    }
+}
+
+/// Defaults by type_id. Probes are TCP on the declared port — the one check
+/// that works for any image without knowing its health endpoint. Stateful
+/// types get a startup probe (slow recovery on boot) and a longer grace
+/// period to flush; stateless ones a short preStop sleep so endpoints drain
+/// before SIGTERM.
+fn default_container_runtime(type_id: &str) -> ContainerRuntime {
+    let stateful = matches!(type_id, "database" | "queue");
+    ContainerRuntime {
+        liveness_probe: Some(tcp_probe(if stateful { 0 } else { 10 }, 10, 6)),
+        readiness_probe: Some(tcp_probe(0, 5, 3)),
+        startup_probe: stateful.then(|| tcp_probe(0, 10, 30)),
+        security_context: Some(SecurityContextConfig {
+            allow_privilege_escalation: Some(false),
+            ..Default::default()
+        }),
+        command: vec![],
+        args: vec![],
+        termination_grace_period_seconds: Some(if stateful { 60 } else { 30 }),
+        pre_stop_sleep_seconds: Some(match type_id {
+            "database" | "queue" | "cache" => 0,
+            _ => 5,
+        }),
+    }
+}
+
+/// Fill whatever the request left unset from the type_id defaults.
+fn resolve_container_runtime(requested: &ContainerRuntime, type_id: &str) -> ContainerRuntime {
+    let defaults = default_container_runtime(type_id);
+    ContainerRuntime {
+        liveness_probe: requested.liveness_probe.clone().or(defaults.liveness_probe),
+        readiness_probe: requested.readiness_probe.clone().or(defaults.readiness_probe),
+        startup_probe: requested.startup_probe.clone().or(defaults.startup_probe),
+        security_context: requested.security_context.clone().or(defaults.security_context),
+        command: requested.command.clone(),
+        args: requested.args.clone(),
+        termination_grace_period_seconds: requested
+            .termination_grace_period_seconds
+            .or(defaults.termination_grace_period_seconds),
+        pre_stop_sleep_seconds: requested.pre_stop_sleep_seconds.or(defaults.pre_stop_sleep_seconds),
+    }
+}
+
+/// YAML flow sequence, e.g. `["sh", "-c", "echo hi"]`.
+fn yaml_flow_list(items: &[String]) -> String {
+    serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// One probe at container indentation. Empty for "none", or when a
+/// network probe has no port to target.
+fn render_probe(key: &str, probe: &ProbeConfig, container_port: Option<u32>) -> String {
+    let handler = match probe.kind.as_str() {
+        "http" => match probe.port.or(container_port) {
+            Some(port) => format!(
+                "            httpGet:\n              path: {}\n              port: {}\n",
+                probe.path.as_deref().unwrap_or("/"),
+                port
+            ),
+            None => return String::new(),
+        },
+        "tcp" => match probe.port.or(container_port) {
+            Some(port) => format!("            tcpSocket:\n              port: {}\n", port),
+            None => return String::new(),
+        },
+        "exec" if !probe.command.is_empty() => format!(
+            "            exec:\n              command: {}\n",
+            yaml_flow_list(&probe.command)
+        ),
+        _ => return String::new(),
+    };
+    let timings: String = [
+        ("initialDelaySeconds", probe.initial_delay_seconds),
+        ("periodSeconds", probe.period_seconds),
+        ("timeoutSeconds", probe.timeout_seconds),
+        ("failureThreshold", probe.failure_threshold),
+    ]
+    .iter()
+    .filter_map(|(k, v)| v.map(|v| format!("            {}: {}\n", k, v)))
+    .collect();
+    format!("          {}:\n{}{}", key, handler, timings)
+}
+
+/// Container-level part: command/args, probes, preStop, securityContext.
+fn container_runtime_block(rt: &ContainerRuntime, container_port: Option<u32>) -> String {
+    let mut out = String::new();
+    if !rt.command.is_empty() {
+        out.push_str(&format!("          command: {}\n", yaml_flow_list(&rt.command)));
+    }
+    if !rt.args.is_empty() {
+        out.push_str(&format!("          args: {}\n", yaml_flow_list(&rt.args)));
+    }
+    for (key, probe) in [
+        ("livenessProbe", &rt.liveness_probe),
+        ("readinessProbe", &rt.readiness_probe),
+        ("startupProbe", &rt.startup_probe),
+    ] {
+        if let Some(probe) = probe {
+            out.push_str(&render_probe(key, probe, container_port));
+        }
+    }
+    if let Some(secs) = rt.pre_stop_sleep_seconds.filter(|s| *s > 0) {
+        out.push_str(&format!(
+            "          lifecycle:\n            preStop:\n              sleep:\n                seconds: {}\n",
+            secs
+        ));
+    }
+    if let Some(sc) = &rt.security_context {
+        let mut lines = String::new();
+        let bools = [
+            ("runAsNonRoot", sc.run_as_non_root),
+            ("readOnlyRootFilesystem", sc.read_only_root_filesystem),
+            ("allowPrivilegeEscalation", sc.allow_privilege_escalation),
+        ];
+        let ids = [("runAsUser", sc.run_as_user), ("runAsGroup", sc.run_as_group)];
+        for (k, v) in ids {
+            if let Some(v) = v {
+                lines.push_str(&format!("            {}: {}\n", k, v));
+            }
+        }
+        for (k, v) in bools {
+            if let Some(v) = v {
+                lines.push_str(&format!("            {}: {}\n", k, v));
+            }
+        }
+        if !sc.drop_capabilities.is_empty() || !sc.add_capabilities.is_empty() {
+            lines.push_str("            capabilities:\n");
+            if !sc.add_capabilities.is_empty() {
+                lines.push_str(&format!("              add: {}\n", yaml_flow_list(&sc.add_capabilities)));
+            }
+            if !sc.drop_capabilities.is_empty() {
+                lines.push_str(&format!("              drop: {}\n", yaml_flow_list(&sc.drop_capabilities)));
+            }
+        }
+        if !lines.is_empty() {
+            out.push_str("          securityContext:\n");
+            out.push_str(&lines);
+        }
+    }
+    out
+}
+
+/// Pod-level part: terminationGracePeriodSeconds and fsGroup.
+fn pod_runtime_block(rt: &ContainerRuntime) -> String {
+    let mut out = String::new();
+    if let Some(secs) = rt.termination_grace_period_seconds {
+        out.push_str(&format!("      terminationGracePeriodSeconds: {}\n", secs));
+    }
+    if let Some(fs_group) = rt.security_context.as_ref().and_then(|sc| sc.fs_group) {
+        out.push_str(&format!("      securityContext:\n        fsGroup: {}\n", fs_group));
+    }
+    out
+}
+
+/// Why an extra volume can't be rendered, if it can't.
+fn check_volume(v: &VolumeConfig) -> Result<(), String> {
+    let has_source = v.source.as_deref().is_some_and(|s| !s.is_empty());
+    match v.kind.as_str() {
+        "emptyDir" => Ok(()),
+        "configMap" | "secret" | "pvc" if has_source => Ok(()),
+        "configMap" | "secret" | "pvc" => Err(format!("Volume {} ({}) has no source name — skipped", v.name, v.kind)),
+        other => Err(format!(
+            "Volume {}: unknown kind '{}' (expected configMap, secret, emptyDir or pvc) — skipped",
+            v.name, other
+        )),
+    }
+}
+
+/// Container `volumeMounts` entries (without the key) for valid extra volumes.
+fn volume_mount_lines(volumes: &[VolumeConfig]) -> String {
+    volumes.iter().filter(|v| check_volume(v).is_ok()).map(|v| {
+        let mut out = format!(
+            "            - name: {}\n              mountPath: {}\n",
+            v.name, v.mount_path
+        );
+        if let Some(sub) = v.sub_path.as_deref().filter(|s| !s.is_empty()) {
+            out.push_str(&format!("              subPath: {}\n", sub));
+        }
+        if v.read_only {
+            out.push_str("              readOnly: true\n");
+        }
+        out
+    }).collect()
+}
+
+/// Pod `volumes:` block for valid extra volumes.
+fn pod_volumes_block(volumes: &[VolumeConfig]) -> String {
+    let lines: String = volumes.iter().filter(|v| check_volume(v).is_ok()).map(|v| {
+        let source = v.source.as_deref().unwrap_or_default();
+        let body = match v.kind.as_str() {
+            "configMap" => format!("          configMap:\n            name: {}\n", source),
+            "secret" => format!("          secret:\n            secretName: {}\n", source),
+            "pvc" => format!(
+                "          persistentVolumeClaim:\n            claimName: {}\n{}",
+                source,
+                if v.read_only { "            readOnly: true\n" } else { "" }
+            ),
+            _ => {
+                let mut opts = String::new();
+                if let Some(medium) = v.medium.as_deref().filter(|s| !s.is_empty()) {
+                    opts.push_str(&format!("            medium: {}\n", medium));
+                }
+                if let Some(limit) = v.size_limit.as_deref().filter(|s| !s.is_empty()) {
+                    opts.push_str(&format!("            sizeLimit: {}\n", limit));
+                }
+                if opts.is_empty() {
+                    "          emptyDir: {}\n".to_string()
+                } else {
+                    format!("          emptyDir:\n{}", opts)
+                }
+            }
+        };
+        format!("        - name: {}\n{}", v.name, body)
+    }).collect();
+    if lines.is_empty() { String::new() } else { format!("      volumes:\n{}", lines) }
+}
+
+/// `volumeClaimTemplates` entry for the StatefulSet data volume.
+fn volume_claim_template(storage: &StorageConfig) -> String {
+    let class_line = match storage.storage_class.as_deref().filter(|s| !s.is_empty()) {
+        Some(class) => format!("        storageClassName: {}\n", class),
+        None => String::new(),
+    };
+    format!(
+        "  volumeClaimTemplates:\n    - metadata:\n        name: data\n      spec:\n        accessModes: [\"{mode}\"]\n{class}        resources:\n          requests:\n            storage: {size}\n",
+        mode = storage.access_mode.as_deref().filter(|s| !s.is_empty()).unwrap_or("ReadWriteOnce"),
+        class = class_line,
+        size = storage.size.as_deref().filter(|s| !s.is_empty()).unwrap_or("10Gi"),
+    )
+}
+
+/// `spec.replicas` line — omitted when an HPA owns the replica count, so
+/// re-applying the manifest doesn't reset what the autoscaler chose.
+fn replicas_line(cfg: &FieldConfig) -> String {
+    if cfg.autoscaling.is_some() {
+        String::new()
+    } else {
+        format!("  replicas: {}\n", cfg.replicas)
+    }
+}
+
+/// Most replicas the field can run — the HPA max when autoscaled.
+fn field_max_replicas(cfg: &FieldConfig) -> u32 {
+    cfg.autoscaling.as_ref().map(|a| a.max_replicas).unwrap_or(cfg.replicas)
+}
+
+/// Pod-level topologySpreadConstraints / podAntiAffinity for a multi-replica
+/// field. Spreading is best-effort (ScheduleAnyway) so a small cluster can
+/// still schedule every replica; "required" anti-affinity is strict.
+fn availability_block(cfg: &FieldConfig) -> String {
+    if field_max_replicas(cfg) <= 1 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let topology_key = match cfg.availability.spread.as_deref() {
+        Some("zone") => Some("topology.kubernetes.io/zone"),
+        Some("hostname") => Some("kubernetes.io/hostname"),
+        _ => None,
+    };
+    if let Some(key) = topology_key {
+        out.push_str(&format!(
+            "      topologySpreadConstraints:\n        - maxSkew: 1\n          topologyKey: {key}\n          whenUnsatisfiable: ScheduleAnyway\n          labelSelector:\n            matchLabels:\n              app: {name}\n",
+            key = key,
+            name = cfg.id,
+        ));
+    }
+    // podAffinityTerm body at the given indentation, first line unindented
+    let term = |pad: &str| {
+        format!(
+            "labelSelector:\n{pad}  matchLabels:\n{pad}    app: {name}\n{pad}topologyKey: kubernetes.io/hostname\n",
+            pad = pad,
+            name = cfg.id,
+        )
+    };
+    match cfg.availability.anti_affinity.as_deref() {
+        Some("required") => out.push_str(&format!(
+            "      affinity:\n        podAntiAffinity:\n          requiredDuringSchedulingIgnoredDuringExecution:\n            - {}",
+            term("              ")
+        )),
+        Some("preferred") => out.push_str(&format!(
+            "      affinity:\n        podAntiAffinity:\n          preferredDuringSchedulingIgnoredDuringExecution:\n            - weight: 100\n              podAffinityTerm:\n                {}",
+            term("                ")
+        )),
+        _ => {}
+    }
+    out
+}
+
+/// PodDisruptionBudget for a multi-replica field, if enabled.
+fn generate_pdb_yaml(cfg: &FieldConfig) -> Option<String> {
+    if !cfg.availability.pdb || field_max_replicas(cfg) <= 1 {
+        return None;
+    }
+    let bound = match (&cfg.availability.pdb_min_available, &cfg.availability.pdb_max_unavailable) {
+        (Some(min), _) if !min.is_empty() => format!("minAvailable: {}", pdb_bound(min)),
+        (_, Some(max)) if !max.is_empty() => format!("maxUnavailable: {}", pdb_bound(max)),
+        _ => "maxUnavailable: 1".to_string(),
+    };
+    Some(format!(
+        r#"apiVersion: policy/v1
+kind: PodDisruptionBudget
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app: {name}
+    managed-by: endfield
+spec:
+  {bound}
+  selector:
+    matchLabels:
+      app: {name}
+"#,
+        name = cfg.id,
+        ns = cfg.namespace,
+        bound = bound,
+    ))
+}
+
+/// Percentages must be quoted strings in a PDB, counts plain integers.
+fn pdb_bound(value: &str) -> String {
+    if value.ends_with('%') {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn generate_deployment_yaml(cfg: &FieldConfig) -> String {
+    let env_block = field_env_block(cfg);
+    let rt = resolve_container_runtime(&cfg.runtime, image_to_type_id(&cfg.image));
+    let port = (cfg.port > 0).then_some(cfg.port);
+    let mount_lines = volume_mount_lines(&cfg.volumes);
+    let mounts = if mount_lines.is_empty() {
+        String::new()
+    } else {
+        format!("          volumeMounts:\n{}", mount_lines)
+    };
+    let (init, sidecars) = field_extra_containers(cfg, &pod_volume_names(&cfg.volumes), false);
+
+    format!(
+        r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app: {name}
+    managed-by: endfield
+spec:
+{replicas}  selector:
+    matchLabels:
+      app: {name}
+  template:
+    metadata:
+      labels:
+        app: {name}
+    spec:
+{pod}      containers:
+        - name: {name}
+          image: {image}
+          ports:
+            - containerPort: {port}
+{env}          resources:
+            requests:
+              cpu: "100m"
               memory: "128Mi"
             limits:
               cpu: "500m"
               memory: "512Mi"
-"#,
+{container}{mounts}{sidecars}{volumes}"#,
         name = cfg.id,
         ns = cfg.namespace,
-        replicas = cfg.replicas,
+        replicas = replicas_line(cfg),
         image = cfg.image,
         port = cfg.port,
         env = env_block,
+        pod = pod_runtime_block(&rt) + &service_account_block(cfg) + &availability_block(cfg) + &placement_block(cfg) + &init,
+        container = container_runtime_block(&rt, port),
+        mounts = mounts,
+        sidecars = sidecars,
+        volumes = pod_volumes_block(&cfg.volumes),
     )
 }
 
@@ -913,11 +2284,10 @@ fn generate_helm_values_yaml(cfg: &InfraConfig, helm: &HelmInfraConfig) -> Strin
     )
 }
 
+/// Plaintext Secret manifest for the field's sensitive env vars.
+/// Never written to disk as-is — see `encrypt_secret_manifest`.
 fn generate_secret_yaml(cfg: &FieldConfig) -> Option<String> {
-    let secret_keys = ["PASSWORD", "SECRET", "KEY", "TOKEN", "PASS"];
-    let sensitive: Vec<&EnvVar> = cfg.env.iter()
-        .filter(|e| secret_keys.iter().any(|k| e.key.to_uppercase().contains(k)))
-        .collect();
+    let sensitive = field_sensitive_env(cfg);
     if sensitive.is_empty() {
         return None;
     }
@@ -943,27 +2313,16 @@ stringData:
 }
 
 fn generate_statefulset_yaml(cfg: &FieldConfig) -> String {
-    let secret_keys = ["PASSWORD", "SECRET", "KEY", "TOKEN", "PASS"];
-    let has_secret = cfg.env.iter()
-        .any(|e| secret_keys.iter().any(|k| e.key.to_uppercase().contains(k)));
-    let secret_name = format!("{}-secret", cfg.id);
-
-    let env_block = if cfg.env.is_empty() {
-        String::new()
-    } else {
-        let vars: String = cfg.env.iter().map(|e| {
-            let is_sensitive = secret_keys.iter().any(|k| e.key.to_uppercase().contains(k));
-            if is_sensitive && has_secret {
-                format!(
-                    "            - name: {key}\n              valueFrom:\n                secretKeyRef:\n                  name: {secret}\n                  key: {key}\n",
-                    key = e.key, secret = secret_name,
-                )
-            } else {
-                format!("            - name: {}\n              value: \"{}\"\n", e.key, e.value)
-            }
-        }).collect();
-        format!("          env:\n{}\n", vars)
-    };
+    let env_block = field_env_block(cfg);
+    let rt = resolve_container_runtime(&cfg.runtime, image_to_type_id(&cfg.image));
+    let port = (cfg.port > 0).then_some(cfg.port);
+    let data_path = cfg.storage.mount_path.clone()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| format!("/var/lib/{}", cfg.id));
+    // the claim template's "data" volume can be shared with extra containers
+    let mut volume_names = pod_volume_names(&cfg.volumes);
+    volume_names.push("data".to_string());
+    let (init, sidecars) = field_extra_containers(cfg, &volume_names, false);
 
     format!(
         r#"apiVersion: apps/v1
@@ -976,8 +2335,7 @@ metadata:
     managed-by: endfield
 spec:
   serviceName: {name}
-  replicas: {replicas}
-  selector:
+{replicas}  selector:
     matchLabels:
       app: {name}
   template:
@@ -985,7 +2343,7 @@ spec:
       labels:
         app: {name}
     spec:
-      containers:
+{pod}      containers:
         - name: {name}
           image: {image}
           ports:
@@ -997,82 +2355,481 @@ spec:
             limits:
               cpu: "500m"
               memory: "512Mi"
-          volumeMounts:
+{container}          volumeMounts:
             - name: data
-              mountPath: /var/lib/{name}
-  volumeClaimTemplates:
-    - metadata:
-        name: data
-      spec:
-        accessModes: ["ReadWriteOnce"]
-        resources:
-          requests:
-            storage: 10Gi
-"#,
+              mountPath: {data_path}
+{mounts}{sidecars}{volumes}{claim}"#,
         name = cfg.id,
         ns = cfg.namespace,
-        replicas = cfg.replicas,
+        replicas = replicas_line(cfg),
         image = cfg.image,
         port = cfg.port,
         env = env_block,
+        pod = pod_runtime_block(&rt) + &service_account_block(cfg) + &availability_block(cfg) + &placement_block(cfg) + &init,
+        container = container_runtime_block(&rt, port),
+        data_path = data_path,
+        mounts = volume_mount_lines(&cfg.volumes),
+        sidecars = sidecars,
+        volumes = pod_volumes_block(&cfg.volumes),
+        claim = volume_claim_template(&cfg.storage),
     )
 }
 
-fn is_stateful_image(image: &str) -> bool {
-    let img = image.to_lowercase();
-    let img = img.split(':').next().unwrap_or("").split('/').last().unwrap_or("");
-    img.contains("postgres") || img.contains("mysql") || img.contains("mongo")
-        || img.contains("mariadb") || img.contains("redis") || img.contains("kafka")
-        || img.contains("redpanda") || img.contains("cassandra") || img.contains("clickhouse")
-        || img.contains("rabbitmq") || img.contains("nats") || img.contains("elasticsearch")
-}
-
-// ─── Patch replicas ────────────────────────────────────────────────────────────
-
-fn patch_replicas_in_file(
-    file_path: &str,
-    node_label: &str,
-    new_replicas: u32,
-) -> Result<(), String> {
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Cannot read {}: {}", file_path, e))?;
-
-    let docs: Vec<&str> = content.split("\n---").collect();
-    let mut patched: Vec<String> = Vec::new();
-    let mut found = false;
-
-    for doc in &docs {
-        let name = extract_metadata_field(doc, "name").unwrap_or("");
-        if name == node_label && extract_replicas(doc).is_some() {
-            let fixed = doc
-                .lines()
-                .map(|line| {
-                    if line.trim().starts_with("replicas:") {
-                        let indent: String =
-                            line.chars().take_while(|c| c.is_whitespace()).collect();
-                        format!("{}replicas: {}", indent, new_replicas)
-                    } else {
-                        line.to_string()
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
-            patched.push(fixed);
-            found = true;
-        } else {
-            patched.push(doc.to_string());
+/// Pod-level nodeSelector / tolerations / hostNetwork.
+fn placement_block(cfg: &FieldConfig) -> String {
+    let placement = &cfg.placement;
+    let mut out = String::new();
+    if placement.host_network {
+        // keep cluster DNS working on the host network
+        out.push_str("      hostNetwork: true\n      dnsPolicy: ClusterFirstWithHostNet\n");
+    }
+    if !placement.node_selector.is_empty() {
+        let mut labels: Vec<_> = placement.node_selector.iter().collect();
+        labels.sort();
+        out.push_str("      nodeSelector:\n");
+        for (k, v) in labels {
+            out.push_str(&format!("        {}: \"{}\"\n", k, v));
         }
     }
-
-    if !found {
-        return Err(format!(
-            "'{}' with replicas not found in {}",
-            node_label, file_path
-        ));
+    let mut tolerations = String::new();
+    if placement.tolerate_all {
+        tolerations.push_str("        - operator: Exists\n");
     }
-
-    fs::write(file_path, patched.join("\n---"))
-        .map_err(|e| format!("Cannot write {}: {}", file_path, e))
+    for t in &placement.tolerations {
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(key) = t.key.as_deref().filter(|s| !s.is_empty()) {
+            fields.push(format!("key: {}", key));
+        }
+        let operator = t.operator.as_deref().unwrap_or(if t.value.is_some() { "Equal" } else { "Exists" });
+        fields.push(format!("operator: {}", operator));
+        if let Some(value) = t.value.as_deref().filter(|_| operator == "Equal") {
+            fields.push(format!("value: \"{}\"", value));
+        }
+        if let Some(effect) = t.effect.as_deref().filter(|s| !s.is_empty()) {
+            fields.push(format!("effect: {}", effect));
+        }
+        tolerations.push_str(&format!("        - {}\n", fields.join("\n          ")));
+    }
+    if !tolerations.is_empty() {
+        out.push_str("      tolerations:\n");
+        out.push_str(&tolerations);
+    }
+    out
+}
+
+/// DaemonSet: one pod per (matching) node, rolled one node at a time.
+fn generate_daemonset_yaml(cfg: &FieldConfig) -> String {
+    let env_block = field_env_block(cfg);
+    let rt = resolve_container_runtime(&cfg.runtime, image_to_type_id(&cfg.image));
+    let port = (cfg.port > 0).then_some(cfg.port);
+    let ports = match port {
+        Some(p) => format!("          ports:\n            - containerPort: {}\n", p),
+        None => String::new(),
+    };
+    let mount_lines = volume_mount_lines(&cfg.volumes);
+    let mounts = if mount_lines.is_empty() {
+        String::new()
+    } else {
+        format!("          volumeMounts:\n{}", mount_lines)
+    };
+    let (init, sidecars) = field_extra_containers(cfg, &pod_volume_names(&cfg.volumes), false);
+
+    format!(
+        r#"apiVersion: apps/v1
+kind: DaemonSet
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app: {name}
+    managed-by: endfield
+spec:
+  selector:
+    matchLabels:
+      app: {name}
+  updateStrategy:
+    type: RollingUpdate
+    rollingUpdate:
+      maxUnavailable: 1
+  template:
+    metadata:
+      labels:
+        app: {name}
+    spec:
+{pod}      containers:
+        - name: {name}
+          image: {image}
+{ports}{env}          resources:
+            requests:
+              cpu: "50m"
+              memory: "64Mi"
+            limits:
+              cpu: "200m"
+              memory: "256Mi"
+{container}{mounts}{sidecars}{volumes}"#,
+        name = cfg.id,
+        ns = cfg.namespace,
+        pod = pod_runtime_block(&rt) + &service_account_block(cfg) + &placement_block(cfg) + &init,
+        image = cfg.image,
+        ports = ports,
+        env = env_block,
+        container = container_runtime_block(&rt, port),
+        mounts = mounts,
+        sidecars = sidecars,
+        volumes = pod_volumes_block(&cfg.volumes),
+    )
+}
+
+/// Indent every non-empty line of `text` by `n` spaces.
+fn indent_lines(text: &str, n: usize) -> String {
+    let pad = " ".repeat(n);
+    text.lines()
+        .map(|l| if l.is_empty() { "\n".to_string() } else { format!("{}{}\n", pad, l) })
+        .collect()
+}
+
+/// Job `spec:` body (at indent 2) shared by Job and CronJob. Batch pods get
+/// command/args and securityContext but no probes or preStop hook.
+fn job_spec_body(cfg: &FieldConfig) -> String {
+    let mut rt = resolve_container_runtime(&cfg.runtime, image_to_type_id(&cfg.image));
+    rt.liveness_probe = None;
+    rt.readiness_probe = None;
+    rt.startup_probe = None;
+    rt.pre_stop_sleep_seconds = None;
+    let batch = &cfg.batch;
+    let mut limits = format!(
+        "  backoffLimit: {}\n  ttlSecondsAfterFinished: {}\n",
+        batch.backoff_limit.unwrap_or(3),
+        batch.ttl_seconds_after_finished.unwrap_or(86400),
+    );
+    if let Some(secs) = batch.active_deadline_seconds {
+        limits.push_str(&format!("  activeDeadlineSeconds: {}\n", secs));
+    }
+    let mount_lines = volume_mount_lines(&cfg.volumes);
+    let mounts = if mount_lines.is_empty() {
+        String::new()
+    } else {
+        format!("          volumeMounts:\n{}", mount_lines)
+    };
+    let (init, _) = field_extra_containers(cfg, &pod_volume_names(&cfg.volumes), true);
+    format!(
+        r#"{limits}  template:
+    metadata:
+      labels:
+        app: {name}
+    spec:
+      restartPolicy: {restart}
+{pod}      containers:
+        - name: {name}
+          image: {image}
+{env}          resources:
+            requests:
+              cpu: "100m"
+              memory: "128Mi"
+            limits:
+              cpu: "500m"
+              memory: "512Mi"
+{container}{mounts}{volumes}"#,
+        limits = limits,
+        name = cfg.id,
+        restart = batch.restart_policy.as_deref().unwrap_or("OnFailure"),
+        pod = pod_runtime_block(&rt) + &service_account_block(cfg) + &placement_block(cfg) + &init,
+        image = cfg.image,
+        env = field_env_block(cfg),
+        container = container_runtime_block(&rt, None),
+        mounts = mounts,
+        volumes = pod_volumes_block(&cfg.volumes),
+    )
+}
+
+fn generate_job_yaml(cfg: &FieldConfig) -> String {
+    format!(
+        r#"apiVersion: batch/v1
+kind: Job
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app: {name}
+    managed-by: endfield
+spec:
+{body}"#,
+        name = cfg.id,
+        ns = cfg.namespace,
+        body = job_spec_body(cfg),
+    )
+}
+
+fn generate_cronjob_yaml(cfg: &FieldConfig) -> Result<String, String> {
+    let batch = &cfg.batch;
+    let schedule = batch.schedule.as_deref().filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| format!("CronJob {} needs a schedule", cfg.id))?;
+    let mut spec = format!(
+        "  schedule: \"{}\"\n  concurrencyPolicy: {}\n",
+        schedule,
+        batch.concurrency_policy.as_deref().unwrap_or("Forbid"),
+    );
+    if let Some(tz) = batch.time_zone.as_deref().filter(|s| !s.is_empty()) {
+        spec.push_str(&format!("  timeZone: {}\n", tz));
+    }
+    if let Some(n) = batch.successful_jobs_history_limit {
+        spec.push_str(&format!("  successfulJobsHistoryLimit: {}\n", n));
+    }
+    if let Some(n) = batch.failed_jobs_history_limit {
+        spec.push_str(&format!("  failedJobsHistoryLimit: {}\n", n));
+    }
+    Ok(format!(
+        r#"apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app: {name}
+    managed-by: endfield
+spec:
+{spec}  jobTemplate:
+    metadata:
+      labels:
+        app: {name}
+    spec:
+{body}"#,
+        name = cfg.id,
+        ns = cfg.namespace,
+        spec = spec,
+        body = indent_lines(&job_spec_body(cfg), 4),
+    ))
+}
+
+/// Workload kind generate_field writes for a field.
+fn field_workload_kind(cfg: &FieldConfig) -> Result<&'static str, String> {
+    match cfg.workload_kind.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("") if is_stateful_image(&cfg.image) => Ok("StatefulSet"),
+        None | Some("") => Ok("Deployment"),
+        Some("deployment") => Ok("Deployment"),
+        Some("statefulset") => Ok("StatefulSet"),
+        Some("daemonset") => Ok("DaemonSet"),
+        Some("job") => Ok("Job"),
+        Some("cronjob") => Ok("CronJob"),
+        Some(other) => Err(format!(
+            "Unknown workload kind '{}' (expected deployment, statefulset, daemonset, job or cronjob)",
+            other
+        )),
+    }
+}
+
+/// `autoscaling/v2` HPA for a workload. Without any target it defaults to
+/// 80% CPU, the one metric every cluster with metrics-server has.
+fn generate_hpa_yaml(kind: &str, name: &str, ns: &str, cfg: &AutoscalingConfig) -> String {
+    let resource_metric = |resource: &str, pct: u32| {
+        format!(
+            "    - type: Resource\n      resource:\n        name: {}\n        target:\n          type: Utilization\n          averageUtilization: {}\n",
+            resource, pct
+        )
+    };
+    let mut metrics = String::new();
+    if let Some(pct) = cfg.cpu_utilization {
+        metrics.push_str(&resource_metric("cpu", pct));
+    }
+    if let Some(pct) = cfg.memory_utilization {
+        metrics.push_str(&resource_metric("memory", pct));
+    }
+    for m in &cfg.custom_metrics {
+        let selector = if m.selector.is_empty() {
+            String::new()
+        } else {
+            let mut labels: Vec<_> = m.selector.iter().collect();
+            labels.sort();
+            let lines: String = labels
+                .iter()
+                .map(|(k, v)| format!("              {}: \"{}\"\n", k, v))
+                .collect();
+            format!("          selector:\n            matchLabels:\n{}", lines)
+        };
+        let (metric_type, key) = if m.kind == "external" { ("External", "external") } else { ("Pods", "pods") };
+        metrics.push_str(&format!(
+            "    - type: {t}\n      {k}:\n        metric:\n          name: {name}\n{sel}        target:\n          type: AverageValue\n          averageValue: \"{value}\"\n",
+            t = metric_type,
+            k = key,
+            name = m.name,
+            sel = selector,
+            value = m.average_value,
+        ));
+    }
+    if metrics.is_empty() {
+        metrics = resource_metric("cpu", 80);
+    }
+    let behavior = match cfg.scale_down_stabilization_seconds {
+        Some(secs) => format!(
+            "  behavior:\n    scaleDown:\n      stabilizationWindowSeconds: {}\n",
+            secs
+        ),
+        None => String::new(),
+    };
+    format!(
+        r#"apiVersion: autoscaling/v2
+kind: HorizontalPodAutoscaler
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app: {name}
+    managed-by: endfield
+spec:
+  scaleTargetRef:
+    apiVersion: apps/v1
+    kind: {kind}
+    name: {name}
+  minReplicas: {min}
+  maxReplicas: {max}
+  metrics:
+{metrics}{behavior}"#,
+        name = name,
+        ns = ns,
+        kind = kind,
+        min = cfg.min_replicas.max(1),
+        max = cfg.max_replicas.max(cfg.min_replicas.max(1)),
+        metrics = metrics,
+        behavior = behavior,
+    )
+}
+
+fn is_stateful_image(image: &str) -> bool {
+    let img = image.to_lowercase();
+    let img = img.split(':').next().unwrap_or("").split('/').last().unwrap_or("");
+    img.contains("postgres") || img.contains("mysql") || img.contains("mongo")
+        || img.contains("mariadb") || img.contains("redis") || img.contains("kafka")
+        || img.contains("redpanda") || img.contains("cassandra") || img.contains("clickhouse")
+        || img.contains("rabbitmq") || img.contains("nats") || img.contains("elasticsearch")
+}
+
+// ─── Patch replicas ────────────────────────────────────────────────────────────
+
+fn patch_replicas_in_file(
+    file_path: &str,
+    node_label: &str,
+    new_replicas: u32,
+) -> Result<(), String> {
+    let patched = patch_replicas_in_content(file_path, node_label, new_replicas)?;
+    fs::write(file_path, patched)
+        .map_err(|e| format!("Cannot write {}: {}", file_path, e))
+}
+
+/// Read `file_path` and return its content with `node_label`'s replicas
+/// replaced, without writing anything back.
+fn patch_replicas_in_content(
+    file_path: &str,
+    node_label: &str,
+    new_replicas: u32,
+) -> Result<String, String> {
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Cannot read {}: {}", file_path, e))?;
+
+    let docs: Vec<&str> = content.split("\n---").collect();
+    let mut patched: Vec<String> = Vec::new();
+    let mut found = false;
+
+    for doc in &docs {
+        let name = extract_metadata_field(doc, "name").unwrap_or("");
+        if name == node_label && extract_replicas(doc).is_some() {
+            let fixed = doc
+                .lines()
+                .map(|line| {
+                    if line.trim().starts_with("replicas:") {
+                        let indent: String =
+                            line.chars().take_while(|c| c.is_whitespace()).collect();
+                        format!("{}replicas: {}", indent, new_replicas)
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            patched.push(fixed);
+            found = true;
+        } else {
+            patched.push(doc.to_string());
+        }
+    }
+
+    if !found {
+        return Err(format!(
+            "'{}' with replicas not found in {}",
+            node_label, file_path
+        ));
+    }
+
+    Ok(patched.join("\n---"))
+}
+
+/// `file_path` content with HPA `hpa_name`'s minReplicas/maxReplicas replaced.
+fn patch_hpa_bounds_in_content(
+    file_path: &str,
+    hpa_name: &str,
+    min_replicas: u32,
+    max_replicas: u32,
+) -> Result<String, String> {
+    if min_replicas == 0 || max_replicas < min_replicas {
+        return Err(format!(
+            "Invalid HPA bounds: min {} / max {} (need 1 ≤ min ≤ max)",
+            min_replicas, max_replicas
+        ));
+    }
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Cannot read {}: {}", file_path, e))?;
+    let mut found = false;
+    let patched: Vec<String> = content
+        .split("\n---")
+        .map(|doc| {
+            let is_target = extract_yaml_field(doc, "kind") == Some("HorizontalPodAutoscaler")
+                && extract_metadata_field(doc, "name") == Some(hpa_name);
+            if !is_target {
+                return doc.to_string();
+            }
+            found = true;
+            doc.lines()
+                .map(|line| {
+                    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+                    match line.trim() {
+                        t if t.starts_with("minReplicas:") => format!("{}minReplicas: {}", indent, min_replicas),
+                        t if t.starts_with("maxReplicas:") => format!("{}maxReplicas: {}", indent, max_replicas),
+                        _ => line.to_string(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+    if !found {
+        return Err(format!("HPA '{}' not found in {}", hpa_name, file_path));
+    }
+    Ok(patched.join("\n---"))
+}
+
+/// HPA in the project that scales workload `node_label` defined in `file_path`.
+fn workload_autoscaler(root: &Path, file_path: &str, node_label: &str) -> Option<HpaInfo> {
+    let content = fs::read_to_string(file_path).ok()?;
+    let doc = content
+        .split("\n---")
+        .find(|doc| extract_metadata_field(doc, "name") == Some(node_label))?;
+    let kind = extract_yaml_field(doc, "kind").unwrap_or_default();
+    let ns = extract_metadata_field(doc, "namespace").unwrap_or("default");
+    project_yaml_docs(root)
+        .iter()
+        .filter_map(|(file, doc)| parse_hpa_doc(doc, file))
+        .find(|hpa| {
+            hpa.namespace == ns
+                && hpa.target_name == node_label
+                && (hpa.target_kind.is_empty() || hpa.target_kind == kind)
+        })
+}
+
+fn autoscaled_replicas_error(node_label: &str, hpa: &HpaInfo) -> String {
+    format!(
+        "{} is scaled by HPA {} (min {}, max {}) — its replicas field is ignored. \
+         Pass hpaMaxReplicas to set the HPA's min/max instead.",
+        node_label, hpa.name, hpa.min_replicas, hpa.max_replicas
+    )
 }
 
 // ─── .endfield layout ─────────────────────────────────────────────────────────
@@ -1094,9 +2851,11 @@ pub struct EndfieldLayout {
 
 #[tauri::command]
 fn save_endfield_layout(
+    roots: tauri::State<ProjectRootState>,
     project_path: String,
     fields: Vec<FieldLayoutEntry>,
 ) -> Result<(), String> {
+    let project_dir = confine_path(&roots, &project_path)?;
     let layout = EndfieldLayout {
         version: 1,
         project_path: project_path.clone(),
@@ -1104,14 +2863,17 @@ fn save_endfield_layout(
     };
     let json = serde_json::to_string_pretty(&layout)
         .map_err(|e| format!("Serialize error: {}", e))?;
-    let out_path = Path::new(&project_path).join(".endfield");
+    let out_path = project_dir.join(".endfield");
     fs::write(&out_path, json)
         .map_err(|e| format!("Cannot write .endfield: {}", e))
 }
 
 #[tauri::command]
-fn load_endfield_layout(project_path: String) -> Result<EndfieldLayout, String> {
-    let in_path = Path::new(&project_path).join(".endfield");
+fn load_endfield_layout(
+    roots: tauri::State<ProjectRootState>,
+    project_path: String,
+) -> Result<EndfieldLayout, String> {
+    let in_path = confine_path(&roots, &project_path)?.join(".endfield");
     if !in_path.exists() {
         return Err("No .endfield file found".to_string());
     }
@@ -1120,1928 +2882,8791 @@ fn load_endfield_layout(project_path: String) -> Result<EndfieldLayout, String>
     serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))
 }
 
-// ─── NEW: Generate Field ───────────────────────────────────────────────────────
+// ─── .endfield-config (project settings) ──────────────────────────────────────
 
-/// Generate manifests for a new Field (app/service) and write them to disk.
-/// Does NOT deploy — call deploy_resource() after reviewing.
-///
-/// Directory layout created:
-///   <project_path>/apps/<field_id>/
-///     namespace.yaml        (only if namespace is new)
-///     deployment.yaml
-///     service.yaml
-///     configmap.yaml
-#[tauri::command]
-fn generate_field(mut config: FieldConfig) -> GenerateResult {
-    let mut generated_files: Vec<String> = Vec::new();
-    let mut warnings: Vec<String> = Vec::new();
+fn default_protected_namespaces() -> Vec<String> {
+    vec![
+        "kube-system".to_string(),
+        "kube-public".to_string(),
+        "kube-node-lease".to_string(),
+    ]
+}
 
-    // Auto-derive namespace if empty — all apps go to "apps" namespace
-    if config.namespace.is_empty() {
-        config.namespace = "apps".to_string();
-    }
+/// Per-project settings stored in `<project>/.endfield-config` (JSON).
+/// Unlike `.endfield` (canvas layout) this file is meant to be committed
+/// and edited by hand.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectConfig {
+    /// kubectl contexts where destructive commands need a confirmation token.
+    /// `*` matches any run of characters, e.g. "prod-*".
+    #[serde(default)]
+    pub protected_contexts: Vec<String>,
+    /// Namespaces where destructive commands need a confirmation token
+    #[serde(default = "default_protected_namespaces")]
+    pub protected_namespaces: Vec<String>,
+    /// Encryption of Secret manifests stored in the project
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// Registries reached over plain http (`host:port`); localhost always is
+    #[serde(default)]
+    pub insecure_registries: Vec<String>,
+}
 
-    let field_dir = Path::new(&config.project_path)
-        .join("apps")
-        .join(&config.id);
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SecretsConfig {
+    /// age public keys (`age1…`) Secret files are encrypted for.
+    /// Empty → the public key of the local age identity is used.
+    #[serde(default)]
+    pub age_recipients: Vec<String>,
+    /// Local age identity used for decryption.
+    /// Default: $SOPS_AGE_KEY_FILE, then ~/.config/sops/age/keys.txt
+    #[serde(default)]
+    pub age_key_file: Option<String>,
+    /// Default backend for generated secrets: "sops" | "external-secrets" | "sealed-secrets"
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub external_secrets: ExternalSecretsConfig,
+    #[serde(default)]
+    pub sealed_secrets: SealedSecretsConfig,
+}
 
-    // Create directory
-    if let Err(e) = fs::create_dir_all(&field_dir) {
-        return GenerateResult {
-            generated_files,
-            namespace_created: false,
-            namespace: config.namespace,
-            warnings,
-            error: Some(format!("Cannot create directory {}: {}", field_dir.display(), e)),
-        };
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExternalSecretsConfig {
+    /// SecretStore / ClusterSecretStore the ExternalSecrets point at
+    pub store_name: String,
+    /// "SecretStore" | "ClusterSecretStore"
+    pub store_kind: String,
+    /// Remote key path; `{namespace}` and `{name}` are substituted
+    pub key_path: String,
+    pub refresh_interval: String,
+}
+
+impl Default for ExternalSecretsConfig {
+    fn default() -> Self {
+        ExternalSecretsConfig {
+            store_name: "default".to_string(),
+            store_kind: "ClusterSecretStore".to_string(),
+            key_path: "endfield/{namespace}/{name}".to_string(),
+            refresh_interval: "1h".to_string(),
+        }
     }
+}
 
-    // Write namespace.yaml
-    let ns_path = field_dir.join("namespace.yaml");
-    let ns_yaml = generate_namespace_yaml(&config.namespace);
-    if let Err(e) = fs::write(&ns_path, &ns_yaml) {
-        return GenerateResult {
-            generated_files,
-            namespace_created: false,
-            namespace: config.namespace,
-            warnings,
-            error: Some(format!("Cannot write namespace.yaml: {}", e)),
-        };
-    }
-    generated_files.push(ns_path.to_string_lossy().to_string());
-
-    // Write secret.yaml if env has sensitive keys
-    if let Some(secret_yaml) = generate_secret_yaml(&config) {
-        let secret_path = field_dir.join(format!("{}-secret.yaml", config.id));
-        if let Err(e) = fs::write(&secret_path, &secret_yaml) {
-            warnings.push(format!("Cannot write secret.yaml: {}", e));
-        } else {
-            generated_files.push(secret_path.to_string_lossy().to_string());
-        }
-    }
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SealedSecretsConfig {
+    /// Public cert of the sealed-secrets controller (`kubeseal --fetch-cert`),
+    /// relative to the project root or absolute
+    #[serde(default)]
+    pub cert_path: Option<String>,
+}
 
-    // StatefulSet for databases/caches/queues, Deployment for everything else
-    let use_statefulset = is_stateful_image(&config.image);
-    if use_statefulset {
-        let ss_path = field_dir.join("statefulset.yaml");
-        let ss_yaml = generate_statefulset_yaml(&config);
-        if let Err(e) = fs::write(&ss_path, &ss_yaml) {
-            warnings.push(format!("Cannot write statefulset.yaml: {}", e));
-        } else {
-            generated_files.push(ss_path.to_string_lossy().to_string());
-        }
-    } else {
-        let deploy_path = field_dir.join("deployment.yaml");
-        let deploy_yaml = generate_deployment_yaml(&config);
-        if let Err(e) = fs::write(&deploy_path, &deploy_yaml) {
-            warnings.push(format!("Cannot write deployment.yaml: {}", e));
-        } else {
-            generated_files.push(deploy_path.to_string_lossy().to_string());
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        ProjectConfig {
+            protected_contexts: vec![],
+            protected_namespaces: default_protected_namespaces(),
+            secrets: SecretsConfig::default(),
+            insecure_registries: vec![],
         }
     }
+}
 
-    // Write service.yaml
-    let svc_path = field_dir.join("service.yaml");
-    let svc_yaml = generate_service_yaml(&config);
-    if let Err(e) = fs::write(&svc_path, &svc_yaml) {
-        warnings.push(format!("Cannot write service.yaml: {}", e));
-    } else {
-        generated_files.push(svc_path.to_string_lossy().to_string());
+fn read_project_config(root: &Path) -> Result<ProjectConfig, String> {
+    let path = root.join(".endfield-config");
+    if !path.exists() {
+        return Ok(ProjectConfig::default());
     }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read .endfield-config: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Parse error in .endfield-config: {}", e))
+}
 
-    // ConfigMap only for stateless workloads
-    if !use_statefulset {
-        let cm_path = field_dir.join("configmap.yaml");
-        let cm_yaml = generate_configmap_yaml(&config);
-        if let Err(e) = fs::write(&cm_path, &cm_yaml) {
-            warnings.push(format!("Cannot write configmap.yaml: {}", e));
-        } else {
-            generated_files.push(cm_path.to_string_lossy().to_string());
+/// Config of the active project, or defaults when no project is open.
+/// Project-relative paths inside it are resolved against the root.
+fn active_project_config(roots: &ProjectRootState) -> Result<ProjectConfig, String> {
+    match project_root(roots) {
+        Ok(root) => {
+            let mut config = read_project_config(&root)?;
+            if let Some(cert) = &config.secrets.sealed_secrets.cert_path {
+                config.secrets.sealed_secrets.cert_path =
+                    Some(root.join(cert).to_string_lossy().to_string());
+            }
+            Ok(config)
         }
-    }
-
-    GenerateResult {
-        generated_files,
-        namespace_created: true, // file was written; actual cluster create happens on deploy
-        namespace: config.namespace,
-        warnings,
-        error: None,
+        Err(_) => Ok(ProjectConfig::default()),
     }
 }
 
-// ─── NEW: Generate Infra ──────────────────────────────────────────────────────
+#[tauri::command]
+fn load_project_config(roots: tauri::State<ProjectRootState>) -> Result<ProjectConfig, String> {
+    read_project_config(&project_root(&roots)?)
+}
 
-/// Generate manifests or Helm scaffold for an Infrastructure component.
-///
-/// For Helm:
-///   <project_path>/infra/<infra_id>/
-///     namespace.yaml
-///     helm/Chart.yaml
-///     helm/values.yaml
-///     rendered/           (empty, populated on helm_template)
-///
-/// For Raw:
-///   Files are expected to exist already — this command just validates structure.
 #[tauri::command]
-fn generate_infra(config: InfraConfig) -> GenerateResult {
-    let mut generated_files: Vec<String> = Vec::new();
-    let mut warnings: Vec<String> = Vec::new();
+fn save_project_config(
+    roots: tauri::State<ProjectRootState>,
+    config: ProjectConfig,
+) -> Result<(), String> {
+    let path = project_root(&roots)?.join(".endfield-config");
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Serialize error: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Cannot write .endfield-config: {}", e))
+}
 
-    let namespace = config
-        .namespace
-        .clone()
-        .unwrap_or_else(|| format!("infra-{}", config.id));
+// ─── Protected targets / confirmation tokens ──────────────────────────────────
 
-    let infra_dir = Path::new(&config.project_path)
-        .join("infra")
-        .join(&config.id);
+/// How long a token from a `plan_*` command stays valid.
+const CONFIRMATION_TTL: Duration = Duration::from_secs(300);
 
-    if let Err(e) = fs::create_dir_all(&infra_dir) {
-        return GenerateResult {
-            generated_files,
-            namespace_created: false,
-            namespace,
-            warnings,
-            error: Some(format!("Cannot create directory {}: {}", infra_dir.display(), e)),
-        };
-    }
+/// A destructive action that was planned against a protected target
+/// and is waiting for the user to confirm it.
+struct PendingConfirmation {
+    /// Identifies the exact action + arguments the token was issued for
+    action_key: String,
+    /// Objects shown to the user at plan time
+    affected: Vec<String>,
+    issued_at: Instant,
+}
 
-    // Write namespace.yaml
-    let ns_path = infra_dir.join("namespace.yaml");
-    let ns_yaml = generate_namespace_yaml(&namespace);
-    if let Err(e) = fs::write(&ns_path, &ns_yaml) {
-        return GenerateResult {
-            generated_files,
-            namespace_created: false,
-            namespace,
-            warnings,
-            error: Some(format!("Cannot write namespace.yaml: {}", e)),
-        };
-    }
-    generated_files.push(ns_path.to_string_lossy().to_string());
+/// Outstanding confirmation tokens, keyed by token. Tokens are single-use.
+#[derive(Default)]
+pub struct ConfirmationState(Mutex<std::collections::HashMap<String, PendingConfirmation>>);
 
-    if config.source == "helm" {
-        let helm = match &config.helm {
-            Some(h) => h.clone(),
-            None => {
-                return GenerateResult {
-                    generated_files,
-                    namespace_created: false,
-                    namespace,
-                    warnings,
-                    error: Some("source=helm but helm config is missing".to_string()),
-                }
-            }
-        };
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DestructivePlan {
+    /// e.g. "delete_by_label", "remove_resource"
+    pub action: String,
+    pub context: String,
+    pub namespace: String,
+    /// True when the context or namespace is protected and a token is required
+    pub protected: bool,
+    /// Exact objects that would be deleted, as `kind/name`
+    pub affected: Vec<String>,
+    /// Pass back to the executing command. Only issued for protected targets.
+    pub confirmation_token: Option<String>,
+    pub expires_in_secs: u64,
+}
 
-        let helm_dir = infra_dir.join("helm");
-        if let Err(e) = fs::create_dir_all(&helm_dir) {
-            return GenerateResult {
-                generated_files,
-                namespace_created: false,
-                namespace,
-                warnings,
-                error: Some(format!("Cannot create helm/: {}", e)),
-            };
-        }
+fn current_context() -> String {
+    run_kubectl(&["config", "current-context"])
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
 
-        // Write Chart.yaml
-        let chart_path = helm_dir.join("Chart.yaml");
-        let chart_yaml = generate_helm_chart_yaml(&config, &helm);
-        if let Err(e) = fs::write(&chart_path, &chart_yaml) {
-            warnings.push(format!("Cannot write Chart.yaml: {}", e));
+/// Minimal glob: `*` matches any (possibly empty) run of characters.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let mut rest = value;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else if let Some(pos) = rest.find(part) {
+            rest = &rest[pos + part.len()..];
         } else {
-            generated_files.push(chart_path.to_string_lossy().to_string());
+            return false;
         }
+    }
+    true
+}
 
-        // Write values.yaml (only if no custom values_path provided)
-        if helm.values_path.is_none() {
-            let values_path = helm_dir.join("values.yaml");
-            let values_yaml = generate_helm_values_yaml(&config, &helm);
-            if let Err(e) = fs::write(&values_path, &values_yaml) {
-                warnings.push(format!("Cannot write values.yaml: {}", e));
-            } else {
-                generated_files.push(values_path.to_string_lossy().to_string());
-            }
-        }
+fn is_protected(config: &ProjectConfig, context: &str, namespace: &str) -> bool {
+    config.protected_contexts.iter().any(|p| glob_match(p, context))
+        || config.protected_namespaces.iter().any(|p| glob_match(p, namespace))
+}
 
-        // Create rendered/ placeholder
-        let rendered_dir = infra_dir.join("rendered");
-        if let Err(e) = fs::create_dir_all(&rendered_dir) {
-            warnings.push(format!("Cannot create rendered/: {}", e));
-        } else {
-            // .gitkeep so the directory is tracked by git
-            let gitkeep = rendered_dir.join(".gitkeep");
-            let _ = fs::write(&gitkeep, "");
-            generated_files.push(rendered_dir.to_string_lossy().to_string());
-        }
-    } else {
-        // Raw YAML — validate that a yaml file exists
-        if let Some(raw_path) = &config.raw_yaml_path {
-            let full = Path::new(&config.project_path).join(raw_path);
-            if !full.exists() {
-                warnings.push(format!(
-                    "Raw YAML path does not exist: {}",
-                    full.display()
-                ));
-            }
-        } else {
-            warnings.push("source=raw but raw_yaml_path is not provided".to_string());
-        }
+/// 128 bits from the OS random number generator, as hex.
+fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| format!("OS random number generator failed: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Build a plan for a destructive action, issuing a token if the target is protected.
+fn plan_destructive(
+    confirmations: &ConfirmationState,
+    config: &ProjectConfig,
+    action: &str,
+    action_key: String,
+    namespace: &str,
+    affected: Vec<String>,
+) -> DestructivePlan {
+    let context = current_context();
+    let protected = is_protected(config, &context, namespace);
+    // without a token the protected action cannot be confirmed, so it stays blocked
+    let confirmation_token = protected.then(random_token).and_then(Result::ok).inspect(|token| {
+        let mut pending = confirmations.0.lock().unwrap();
+        pending.retain(|_, p| p.issued_at.elapsed() < CONFIRMATION_TTL);
+        pending.insert(
+            token.clone(),
+            PendingConfirmation {
+                action_key: format!("{}|{}", context, action_key),
+                affected: affected.clone(),
+                issued_at: Instant::now(),
+            },
+        );
+    });
+    DestructivePlan {
+        action: action.to_string(),
+        context,
+        namespace: namespace.to_string(),
+        protected,
+        affected,
+        confirmation_token,
+        expires_in_secs: CONFIRMATION_TTL.as_secs(),
     }
+}
 
-    GenerateResult {
-        generated_files,
-        namespace_created: true,
-        namespace,
-        warnings,
-        error: None,
+/// Gate for executing a destructive action. Unprotected targets pass straight
+/// through. Protected ones need a live token issued for the same action and
+/// context, and the affected objects must not have changed since planning.
+fn check_confirmation(
+    confirmations: &ConfirmationState,
+    config: &ProjectConfig,
+    action_key: &str,
+    namespace: &str,
+    token: Option<&str>,
+    affected_now: impl FnOnce() -> Result<Vec<String>, String>,
+) -> Result<(), String> {
+    let context = current_context();
+    if !is_protected(config, &context, namespace) {
+        return Ok(());
+    }
+    let token = token.ok_or_else(|| {
+        format!(
+            "Context '{}' / namespace '{}' is protected — run the plan_* command first and confirm",
+            context, namespace
+        )
+    })?;
+    let pending = confirmations
+        .0
+        .lock()
+        .unwrap()
+        .remove(token)
+        .ok_or("Confirmation token is unknown or already used")?;
+    if pending.issued_at.elapsed() >= CONFIRMATION_TTL {
+        return Err("Confirmation token expired — plan again".to_string());
+    }
+    if pending.action_key != format!("{}|{}", context, action_key) {
+        return Err("Confirmation token was issued for a different action or context".to_string());
     }
+    let mut now = affected_now()?;
+    let mut planned = pending.affected;
+    now.sort();
+    planned.sort();
+    if now != planned {
+        return Err("Affected objects changed since the plan was made — plan again".to_string());
+    }
+    Ok(())
 }
 
-// ─── NEW: Deploy Resource ─────────────────────────────────────────────────────
+/// `kind/name` of every object in a multi-document manifest.
+fn manifest_object_names(raw: &str) -> Vec<String> {
+    raw.split("\n---")
+        .filter_map(|doc| {
+            let kind = extract_yaml_field(doc, "kind")?;
+            let name = extract_metadata_field(doc, "name")?;
+            Some(format!("{}/{}", kind.to_lowercase(), name))
+        })
+        .collect()
+}
 
-/// Deploy a resource to the cluster.
-///
-/// For source="helm":
-///   1. helm repo add <repo_name> <repo_url>       (if repo_url is set)
-///   2. helm dependency update <helm_dir>
-///   3. helm template → write to rendered/
-///   4. helm upgrade --install ...
-///
-/// For source="raw":
-///   1. kubectl apply -f <dir>  (entire field/infra dir)
-///
-/// Namespace is always ensured before deploy.
-#[tauri::command]
-async fn deploy_resource(
-    resource_id: String,
-    source: String,
-    resource_dir: String,
-    namespace: String,
-    helm_release: Option<String>,
-    helm_repo_name: Option<String>,
-    helm_repo_url: Option<String>,
-    values_file: Option<String>,
-) -> DeployResult {
-    tauri::async_runtime::spawn_blocking(move || {
-        deploy_resource_inner(resource_id, source, resource_dir, namespace,
-            helm_release, helm_repo_name, helm_repo_url, values_file)
-    }).await.unwrap_or_else(|e| DeployResult {
-        resource_id: String::new(), namespace: String::new(),
-        source: String::new(), stdout: String::new(),
-        stderr: format!("spawn error: {}", e),
-        success: false, commands_run: vec![],
-    })
+/// Names printed by a `kubectl get … -o name`. A failing lookup is an error,
+/// never an empty list — a plan must not show "nothing affected" by mistake.
+fn kubectl_object_names(args: &[&str]) -> Result<Vec<String>, String> {
+    Ok(run_kubectl(args)?
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
 }
 
-fn deploy_resource_inner(
-    resource_id: String,
-    source: String,
-    resource_dir: String,
-    namespace: String,
-    helm_release: Option<String>,
-    helm_repo_name: Option<String>,
-    helm_repo_url: Option<String>,
-    values_file: Option<String>,
-) -> DeployResult {
-    let mut commands_run: Vec<String> = Vec::new();
-    let dir = Path::new(&resource_dir);
+fn affected_by_label(label: &str, namespace: &str) -> Result<Vec<String>, String> {
+    kubectl_object_names(&[
+        "get", "all",
+        "-l", &format!("app={}", label),
+        "-n", namespace,
+        "-o", "name",
+        "--ignore-not-found=true",
+    ])
+}
 
-    // Ensure namespace exists in cluster
-    match ensure_namespace(&namespace) {
-        Ok(_created) => {
-            commands_run.push(format!(
-                "kubectl get namespace {} || kubectl create namespace {}",
-                namespace, namespace
-            ));
-        }
-        Err(e) => {
-            return DeployResult {
-                resource_id,
-                namespace,
-                source,
-                stdout: String::new(),
-                stderr: e.clone(),
-                success: false,
-                commands_run,
-            };
-        }
-    }
+/// Objects of a Helm release, from its stored manifest.
+fn affected_by_release(release: &str, namespace: &str) -> Result<Vec<String>, String> {
+    run_helm(&["get", "manifest", release, "--namespace", namespace], Path::new("."))
+        .map(|raw| manifest_object_names(&raw))
+}
 
+/// Live objects that `kubectl delete -f <path>` / `helm uninstall` would remove.
+fn affected_by_resource(
+    source: &str,
+    resource_dir: &str,
+    namespace: &str,
+    release: &str,
+) -> Result<Vec<String>, String> {
     if source == "helm" {
-        let helm_dir = dir.join("helm");
-        let release = helm_release.unwrap_or_else(|| resource_id.clone());
-        let values_path = values_file.unwrap_or_else(|| {
-            helm_dir.join("values.yaml").to_string_lossy().to_string()
-        });
-
-        // Step 1: helm repo add (if repo_url provided)
-        if let (Some(repo_name), Some(repo_url)) = (&helm_repo_name, &helm_repo_url) {
-            let repo_add_cmd = format!("helm repo add {} {}", repo_name, repo_url);
-            commands_run.push(repo_add_cmd);
-            // Not fatal — repo might already exist
-            let _ = run_helm(&["repo", "add", repo_name, repo_url], dir);
-            let _ = run_helm(&["repo", "update"], dir);
-            commands_run.push("helm repo update".to_string());
-        }
+        affected_by_release(release, namespace)
+    } else {
+        kubectl_object_names(&[
+            "get", "-f", resource_dir, "--recursive",
+            "-o", "name", "--ignore-not-found=true",
+        ])
+    }
+}
 
-        // Step 2: helm dependency update
-        let dep_cmd = format!("helm dependency update {}", helm_dir.display());
-        commands_run.push(dep_cmd);
-        if let Err(e) = run_helm(&["dependency", "update", "."], &helm_dir) {
-            return DeployResult {
-                resource_id,
-                namespace,
-                source,
-                stdout: String::new(),
-                stderr: format!("helm dependency update failed: {}", e),
-                success: false,
-                commands_run,
-            };
-        }
+/// A single live object, e.g. the workload a rollout action targets.
+fn affected_object(kind: &str, name: &str, namespace: &str) -> Result<Vec<String>, String> {
+    kubectl_object_names(&[
+        "get", &format!("{}/{}", kind.to_lowercase(), name),
+        "-n", namespace,
+        "-o", "name",
+        "--ignore-not-found=true",
+    ])
+}
 
-        // Step 3: helm template → rendered/
-        let template_cmd = format!(
-            "helm template {} . --namespace {} --values {} --include-crds",
-            release, namespace, values_path
-        );
-        commands_run.push(template_cmd);
-        match run_helm(
-            &[
-                "template", &release, ".",
-                "--namespace", &namespace,
-                "--values", &values_path,
-                "--include-crds",
-            ],
-            &helm_dir,
-        ) {
-            Ok(raw) => {
-                let rendered_dir = dir.join("rendered");
-                let _ = fs::create_dir_all(&rendered_dir);
-                // Clear old rendered files
-                if let Ok(entries) = fs::read_dir(&rendered_dir) {
-                    for entry in entries.flatten() {
-                        let p = entry.path();
-                        if p.file_name().and_then(|n| n.to_str()) != Some(".gitkeep") {
-                            let _ = fs::remove_file(&p);
-                        }
-                    }
-                }
-                for (filename, content) in split_rendered_manifests(&raw) {
-                    let _ = fs::write(rendered_dir.join(&filename), content);
-                }
-            }
-            Err(e) => {
-                // Non-fatal — warn but continue to install
-                eprintln!("helm template warning: {}", e);
-            }
-        }
+// ─── Audit log ────────────────────────────────────────────────────────────────
 
-        // Step 4: helm upgrade --install (no --wait — returns immediately, cluster deploys async)
-        let install_cmd = format!(
-            "helm upgrade --install {} . --namespace {} --create-namespace --values {} --atomic=false",
-            release, namespace, values_path
-        );
-        commands_run.push(install_cmd);
-        let (stdout, stderr, success) = run_helm_output(
-            &[
-                "upgrade", "--install", &release, ".",
-                "--namespace", &namespace,
-                "--create-namespace",
-                "--values", &values_path,
-                "--atomic=false",
-            ],
-            &helm_dir,
-        );
+/// Keep audit entries small: output beyond this is cut.
+const AUDIT_OUTPUT_LIMIT: usize = 4000;
 
-        DeployResult {
-            resource_id,
-            namespace,
-            source,
-            stdout,
-            stderr,
-            success,
-            commands_run,
-        }
-    } else {
-        // Raw YAML — apply entire directory
-        let apply_cmd = format!("kubectl apply -f {} --recursive", dir.display());
-        commands_run.push(apply_cmd);
-        let dir_str = dir.to_string_lossy().to_string();
-        let (stdout, stderr, success) = run_kubectl_output(&[
-            "apply", "-f", &dir_str, "--recursive",
-        ]);
+/// One mutating operation, appended as a JSON line to
+/// `<project>/.endfield-audit.jsonl`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    /// Unix time, seconds
+    pub timestamp: u64,
+    pub user: String,
+    pub context: String,
+    pub namespace: String,
+    /// Command name, e.g. "deploy_resource", "apply_replicas"
+    pub action: String,
+    /// What was targeted: field id, release name, file path…
+    pub resource: String,
+    /// Shell commands that were actually executed
+    pub commands: Vec<String>,
+    pub success: bool,
+    /// stdout + stderr, truncated to AUDIT_OUTPUT_LIMIT bytes
+    pub output: String,
+}
 
-        DeployResult {
-            resource_id,
-            namespace,
-            source,
-            stdout,
-            stderr,
-            success,
-            commands_run,
-        }
-    }
+/// Filters for `query_audit_log`. All are optional and combined with AND.
+#[derive(Debug, Deserialize, Default)]
+pub struct AuditQuery {
+    pub action: Option<String>,
+    pub namespace: Option<String>,
+    pub context: Option<String>,
+    /// Substring match on `resource`
+    pub resource: Option<String>,
+    pub user: Option<String>,
+    /// Unix seconds, inclusive
+    pub since: Option<u64>,
+    /// Unix seconds, inclusive
+    pub until: Option<u64>,
+    pub success: Option<bool>,
+    /// Max entries to return (newest first)
+    pub limit: Option<usize>,
 }
 
-// ─── NEW: Delete Resource ─────────────────────────────────────────────────────
+fn audit_log_path(root: &Path) -> PathBuf {
+    root.join(".endfield-audit.jsonl")
+}
 
-/// Remove a resource from the cluster.
-/// For helm — runs helm uninstall.
-/// For raw — runs kubectl delete -f <dir>.
-/// Does NOT remove files from disk.
-#[tauri::command]
-async fn remove_resource(
-    resource_id: String,
-    source: String,
-    resource_dir: String,
-    namespace: String,
-    helm_release: Option<String>,
-) -> DeployResult {
-    tauri::async_runtime::spawn_blocking(move || {
-        remove_resource_inner(resource_id, source, resource_dir, namespace, helm_release)
-    }).await.unwrap_or_else(|e| DeployResult {
-        resource_id: String::new(), namespace: String::new(),
-        source: String::new(), stdout: String::new(),
-        stderr: format!("spawn error: {}", e),
-        success: false, commands_run: vec![],
-    })
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
 }
 
-fn remove_resource_inner(
-    resource_id: String,
-    source: String,
-    resource_dir: String,
-    namespace: String,
-    helm_release: Option<String>,
-) -> DeployResult {
-    let dir = Path::new(&resource_dir);
-    let mut commands_run: Vec<String> = Vec::new();
+fn truncate_output(s: &str) -> String {
+    if s.len() <= AUDIT_OUTPUT_LIMIT {
+        return s.to_string();
+    }
+    let mut end = AUDIT_OUTPUT_LIMIT;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… [truncated {} bytes]", &s[..end], s.len() - end)
+}
 
-    if source == "helm" {
-        let release = helm_release.unwrap_or_else(|| resource_id.clone());
-        let cmd = format!(
-            "helm uninstall {} --namespace {} --ignore-not-found",
-            release, namespace
-        );
-        commands_run.push(cmd);
-        let (stdout, stderr, success) = run_helm_output(
-            &["uninstall", &release, "--namespace", &namespace, "--ignore-not-found"],
-            dir,
-        );
-        DeployResult { resource_id, namespace, source, stdout, stderr, success, commands_run }
-    } else {
-        let dir_str = dir.to_string_lossy().to_string();
-        let cmd = format!("kubectl delete -f {} --recursive --ignore-not-found=true", dir_str);
-        commands_run.push(cmd);
-        let (stdout, stderr, success) = run_kubectl_output(&[
-            "delete", "-f", &dir_str, "--recursive", "--ignore-not-found=true", "--wait=false",
-        ]);
-        DeployResult { resource_id, namespace, source, stdout, stderr, success, commands_run }
+/// Append an entry to the project's audit log. Best effort: an unwritable
+/// log must never fail the operation itself, so errors only go to stderr.
+/// Without an open project there is nowhere to write and nothing is logged.
+fn record_audit(
+    root: Option<&Path>,
+    action: &str,
+    namespace: &str,
+    resource: &str,
+    commands: &[String],
+    success: bool,
+    output: &str,
+) {
+    use std::io::Write;
+    let Some(root) = root else { return };
+    let entry = AuditEntry {
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        user: current_user(),
+        context: current_context(),
+        namespace: namespace.to_string(),
+        action: action.to_string(),
+        resource: resource.to_string(),
+        commands: commands.to_vec(),
+        success,
+        output: truncate_output(output.trim()),
+    };
+    let Ok(line) = serde_json::to_string(&entry) else { return };
+    let written = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_log_path(root))
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = written {
+        eprintln!("audit log write failed: {}", e);
     }
 }
 
-// ─── NEW: Diff Resource ───────────────────────────────────────────────────────
+fn record_deploy_audit(root: Option<&Path>, action: &str, result: &DeployResult) {
+    record_audit(
+        root,
+        action,
+        &result.namespace,
+        &result.resource_id,
+        &result.commands_run,
+        result.success,
+        &format!("{}{}", result.stdout, result.stderr),
+    );
+}
 
-/// Show what would change if we applied the local YAML vs the live cluster state.
-/// Uses `kubectl diff -f <dir>` which requires a cluster connection.
-/// For Helm, diffs using `helm diff upgrade` (requires helm-diff plugin).
-#[tauri::command]
-fn diff_resource(
-    resource_id: String,
-    source: String,
-    resource_dir: String,
-    namespace: String,
-    helm_release: Option<String>,
-    values_file: Option<String>,
-) -> DiffResult {
-    let dir = Path::new(&resource_dir);
+/// Audit a single command whose outcome is a plain `Result<String, String>`.
+fn record_result_audit(
+    root: Option<&Path>,
+    action: &str,
+    namespace: &str,
+    resource: &str,
+    command: String,
+    result: &Result<String, String>,
+) {
+    let (success, output) = match result {
+        Ok(out) => (true, out.as_str()),
+        Err(e) => (false, e.as_str()),
+    };
+    record_audit(root, action, namespace, resource, &[command], success, output);
+}
 
-    if source == "helm" {
-        let helm_dir = dir.join("helm");
-        let release = helm_release.unwrap_or_else(|| resource_id.clone());
-        let values_path = values_file.unwrap_or_else(|| {
-            helm_dir.join("values.yaml").to_string_lossy().to_string()
-        });
+/// Read the audit log of the open project, newest entries first.
+/// Lines that fail to parse are skipped.
+#[tauri::command]
+fn query_audit_log(
+    roots: tauri::State<ProjectRootState>,
+    query: Option<AuditQuery>,
+) -> Result<Vec<AuditEntry>, String> {
+    let query = query.unwrap_or_default();
+    let path = audit_log_path(&project_root(&roots)?);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read audit log: {}", e))?;
 
-        // Try helm diff upgrade (requires helm-diff plugin)
-        let (stdout, stderr, success) = run_helm_output(
-            &[
-                "diff", "upgrade", &release, ".",
-                "--namespace", &namespace,
-                "--values", &values_path,
-                "--allow-unreleased",
-            ],
-            &helm_dir,
-        );
+    let mut entries: Vec<AuditEntry> = content
+        .lines()
+        .filter_map(|l| serde_json::from_str::<AuditEntry>(l).ok())
+        .filter(|e| query.action.as_ref().is_none_or(|a| &e.action == a))
+        .filter(|e| query.namespace.as_ref().is_none_or(|n| &e.namespace == n))
+        .filter(|e| query.context.as_ref().is_none_or(|c| &e.context == c))
+        .filter(|e| query.user.as_ref().is_none_or(|u| &e.user == u))
+        .filter(|e| query.resource.as_ref().is_none_or(|r| e.resource.contains(r.as_str())))
+        .filter(|e| query.since.is_none_or(|t| e.timestamp >= t))
+        .filter(|e| query.until.is_none_or(|t| e.timestamp <= t))
+        .filter(|e| query.success.is_none_or(|s| e.success == s))
+        .collect();
 
-        if success || !stdout.is_empty() {
-            return DiffResult {
-                resource_id,
-                diff: stdout,
-                has_changes: true,
-                error: None,
-            };
-        }
+    entries.reverse();
+    if let Some(limit) = query.limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
 
-        // Fallback: diff using rendered/ directory
-        let rendered_dir = dir.join("rendered");
-        if rendered_dir.exists() {
-            let rendered_str = rendered_dir.to_string_lossy().to_string();
-            let (diff_out, diff_err, _) = run_kubectl_output(&[
-                "diff", "-f", &rendered_str,
-            ]);
-            return DiffResult {
-                resource_id,
-                has_changes: !diff_out.is_empty(),
-                diff: if diff_out.is_empty() { diff_err } else { diff_out },
-                error: if stderr.is_empty() { None } else { Some(stderr) },
-            };
-        }
+// ─── Encrypted secrets (SOPS + age) ───────────────────────────────────────────
+//
+// Secret manifests in the project are stored SOPS-encrypted: only the
+// `data`/`stringData` values are ciphertext, so the file still diffs and
+// scans like YAML. Plaintext only ever exists in memory — it is piped to
+// and from `sops` via stdin/stdout and straight into `kubectl apply -f -`.
 
-        DiffResult {
-            resource_id,
-            diff: String::new(),
-            has_changes: false,
-            error: Some(format!("helm diff failed and no rendered/ dir: {}", stderr)),
-        }
-    } else {
-        let dir_str = dir.to_string_lossy().to_string();
-        let (stdout, stderr, _exit) = run_kubectl_output(&[
-            "diff", "-f", &dir_str, "--recursive",
-        ]);
-        // kubectl diff exits 1 when there ARE differences — that's not an error
-        let has_changes = !stdout.is_empty();
-        let error = if !has_changes && !stderr.is_empty() {
-            Some(stderr)
-        } else {
-            None
-        };
-        DiffResult {
-            resource_id,
-            diff: stdout,
-            has_changes,
-            error,
-        }
+fn age_key_file(config: &ProjectConfig) -> Option<PathBuf> {
+    if let Some(path) = &config.secrets.age_key_file {
+        return Some(PathBuf::from(path));
     }
+    if let Ok(path) = std::env::var("SOPS_AGE_KEY_FILE") {
+        return Some(PathBuf::from(path));
+    }
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).ok()?;
+    Some(Path::new(&home).join(".config").join("sops").join("age").join("keys.txt"))
 }
 
-// ─── NEW: Get Logs ────────────────────────────────────────────────────────────
-
-/// Get logs for a field. Tries to find a running pod by label app=<field_id>
-/// and returns recent logs.
-#[tauri::command]
-fn get_field_logs(
-    field_id: String,
-    namespace: String,
-    tail: u32,
-    previous: bool,
-) -> Result<String, String> {
-    // List pods matching label
-    let pods_raw = run_kubectl(&[
-        "get", "pods",
-        "-n", &namespace,
-        "-l", &format!("app={}", field_id),
-        "--no-headers",
-        "-o", "custom-columns=NAME:.metadata.name,STATUS:.status.phase",
-    ])?;
-
-    let pod_name = pods_raw
+/// Recipients to encrypt for: the project's configured list, or else the
+/// public key of the local age identity (`# public key: age1…` line).
+fn age_recipients(config: &ProjectConfig) -> Result<Vec<String>, String> {
+    if !config.secrets.age_recipients.is_empty() {
+        return Ok(config.secrets.age_recipients.clone());
+    }
+    let key_file = age_key_file(config).ok_or("No age key file configured")?;
+    let content = fs::read_to_string(&key_file).map_err(|e| {
+        format!(
+            "No age recipients in .endfield-config and cannot read {}: {}",
+            key_file.display(),
+            e
+        )
+    })?;
+    let recipients: Vec<String> = content
         .lines()
-        .find_map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 && parts[1] == "Running" {
-                Some(parts[0].to_string())
-            } else if parts.len() >= 1 {
-                Some(parts[0].to_string()) // fallback: first pod
-            } else {
-                None
-            }
-        })
-        .ok_or_else(|| format!("No pods found for app={} in {}", field_id, namespace))?;
-
-    let tail_str = tail.to_string();
-    let tail_arg = format!("--tail={}", tail_str);
-    let mut args = vec![
-        "logs", "-n", &namespace, &pod_name,
-        &tail_arg,
-    ];
-    if previous {
-        args.push("--previous");
+        .filter_map(|l| l.trim().strip_prefix("# public key:"))
+        .map(|k| k.trim().to_string())
+        .collect();
+    if recipients.is_empty() {
+        return Err(format!("No public key found in {}", key_file.display()));
     }
-
-    run_kubectl(&args)
+    Ok(recipients)
 }
 
-// ─── Scan all project files (for Explorer file tree) ─────────────────────────
-
-/// Returns all .yaml/.yml file paths under a directory recursively,
-/// without any kind filtering — used by the Explorer file tree.
-fn scan_all_yaml_paths(dir: &Path, result: &mut Vec<String>) {
-    let Ok(entries) = std::fs::read_dir(dir) else { return };
-    let mut entries: Vec<_> = entries.flatten().collect();
-    entries.sort_by_key(|e| e.file_name());
-
-    for entry in entries {
-        let path = entry.path();
-        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        if name.starts_with('.') || name == "node_modules" || name == "vendor" {
-            continue;
-        }
-        if path.is_dir() {
-            scan_all_yaml_paths(&path, result);
-        } else if path.is_file() {
-            result.push(path.to_string_lossy().to_string());
-        }
+/// Run sops on `input` fed through stdin, output on stdout — plaintext
+/// never touches the disk. sops wants a file argument, so it reads
+/// `/dev/stdin`; where that doesn't exist (Windows) this fails instead of
+/// falling back to a temp file. Callers pass `--input-type`/`--output-type`.
+fn run_sops(args: &[&str], input: &str, key_file: Option<&Path>) -> Result<String, String> {
+    use std::io::Write;
+    if !cfg!(unix) {
+        return Err("SOPS encryption needs /dev/stdin, which this platform doesn't have".to_string());
+    }
+    let mut cmd = Command::new("sops");
+    cmd.args(args)
+        .arg("/dev/stdin")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    if let Some(key_file) = key_file {
+        cmd.env("SOPS_AGE_KEY_FILE", key_file);
+    }
+    let mut child = cmd.spawn().map_err(|e| format!("sops not found: {}", e))?;
+    // written from a thread so a full stdout pipe can't deadlock the two
+    let writer = child.stdin.take().map(|mut stdin| {
+        let input = input.to_string();
+        std::thread::spawn(move || stdin.write_all(input.as_bytes()))
+    });
+    let output = child.wait_with_output()
+        .map_err(|e| format!("sops wait error: {}", e))?;
+    let written = writer.map(|w| w.join());
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    if let Some(Ok(Err(e))) = written {
+        return Err(format!("stdin write error: {}", e));
     }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-#[tauri::command]
-fn scan_project_files(folder_path: String) -> Vec<String> {
-    let mut files = Vec::new();
-    scan_all_yaml_paths(Path::new(&folder_path), &mut files);
-    files
+/// Encrypt the values of a plaintext Secret manifest for the project's recipients.
+fn encrypt_secret_manifest(yaml: &str, config: &ProjectConfig) -> Result<String, String> {
+    let recipients = age_recipients(config)?.join(",");
+    run_sops(
+        &[
+            "--encrypt",
+            "--age", &recipients,
+            "--encrypted-regex", "^(data|stringData)$",
+            "--input-type", "yaml",
+            "--output-type", "yaml",
+        ],
+        yaml,
+        None,
+    )
+    .map_err(|e| format!("sops encrypt failed: {}", e.trim()))
 }
 
+fn decrypt_secret_manifest(yaml: &str, config: &ProjectConfig) -> Result<String, String> {
+    run_sops(
+        &["--decrypt", "--input-type", "yaml", "--output-type", "yaml"],
+        yaml,
+        age_key_file(config).as_deref(),
+    )
+    .map_err(|e| format!("sops decrypt failed: {}", e.trim()))
+}
 
-
-#[tauri::command]
-async fn open_folder_dialog(app: tauri::AppHandle) -> Option<String> {
-    app.dialog()
-        .file()
-        .set_title("Select Kubernetes config folder")
-        .blocking_pick_folder()
-        .map(|p: tauri_plugin_dialog::FilePath| p.to_string())
+/// SOPS adds a top-level `sops:` block with the key metadata.
+fn is_sops_encrypted(content: &str) -> bool {
+    content.lines().any(|l| l.starts_with("sops:"))
 }
 
-#[tauri::command]
-fn scan_yaml_files(folder_path: String) -> ScanResult {
-    let path = Path::new(&folder_path);
-    let mut nodes: Vec<YamlNode> = Vec::new();
-    let mut errors = Vec::new();
+/// If `dir` contains SOPS-encrypted manifests, return every manifest under it
+/// as one multi-document stream with the encrypted ones decrypted in memory,
+/// ready for `kubectl … -f -`. Returns None when nothing is encrypted so
+/// callers can keep using `-f <dir>` directly.
+fn decrypted_manifest_stream(dir: &Path, config: &ProjectConfig) -> Result<Option<String>, String> {
+    let (stream, any_encrypted) = manifest_stream(dir, config)?;
+    Ok(any_encrypted.then_some(stream))
+}
 
-    if !path.exists() || !path.is_dir() {
-        errors.push(format!("Path does not exist: {}", folder_path));
-        return ScanResult {
-            nodes,
-            project_path: folder_path,
-            errors,
-        };
+/// Every manifest under `dir` (or the single file `dir`) as one
+/// multi-document stream, encrypted ones decrypted, plus whether any were.
+fn manifest_stream(dir: &Path, config: &ProjectConfig) -> Result<(String, bool), String> {
+    let mut files = Vec::new();
+    if dir.is_file() {
+        files.push(dir.to_string_lossy().to_string());
+    } else {
+        scan_all_yaml_paths(dir, &mut files);
+        files.retain(|f| f.ends_with(".yaml") || f.ends_with(".yml"));
     }
 
-    scan_dir(path, &mut nodes, &mut errors);
+    let mut any_encrypted = false;
+    let mut docs: Vec<String> = Vec::new();
+    for file in &files {
+        let content = fs::read_to_string(file)
+            .map_err(|e| format!("Cannot read {}: {}", file, e))?;
+        if is_sops_encrypted(&content) {
+            any_encrypted = true;
+            docs.push(decrypt_secret_manifest(&content, config)
+                .map_err(|e| format!("{}: {}", file, e))?);
+        } else {
+            docs.push(content);
+        }
+    }
+    Ok((docs.join("\n---\n"), any_encrypted))
+}
 
-    let priority = |kind: &str, source: &str| {
-        if source == "helm" {
-            return 0u32;
+/// `kubectl apply -f <path>` for a project file or dir. Encrypted Secrets are
+/// decrypted in memory and everything is piped via stdin instead.
+fn kubectl_apply_project_path(
+    path: &Path,
+    extra_args: &[&str],
+    config: &ProjectConfig,
+    commands_run: &mut Vec<String>,
+) -> (String, String, bool) {
+    let path_str = path.to_string_lossy().to_string();
+    match decrypted_manifest_stream(path, config) {
+        Ok(Some(stream)) => {
+            let mut args = vec!["apply", "-f", "-"];
+            args.extend_from_slice(extra_args);
+            commands_run.push(format!("sops --decrypt <secrets in {}>", path_str));
+            commands_run.push(format!("kubectl {}", args.join(" ")));
+            run_kubectl_stdin(&args, &stream)
         }
-        match kind {
-            "StatefulSet" => 1,
-            "Deployment" => 2,
-            "DaemonSet" => 3,
-            "ReplicaSet" => 4,
-            "Job" => 5,
-            "CronJob" => 6,
-            "Pod" => 7,
-            _ => 8,
+        Ok(None) => {
+            let mut args = vec!["apply", "-f", path_str.as_str()];
+            args.extend_from_slice(extra_args);
+            commands_run.push(format!("kubectl {}", args.join(" ")));
+            run_kubectl_output(&args)
         }
-    };
-
-    let mut seen: std::collections::HashMap<String, usize> =
-        std::collections::HashMap::new();
-    let mut deduped: Vec<YamlNode> = Vec::new();
-
-    for node in nodes {
-        // For workloads: deduplicate by label+namespace (prefer StatefulSet over Deployment etc.)
-        // For configs/services/etc.: use kind+label+namespace so they always show separately
-        let workload_kinds = ["Deployment", "StatefulSet", "DaemonSet", "ReplicaSet", "Job", "CronJob", "Pod"];
-        let key = if workload_kinds.contains(&node.kind.as_str()) {
-            format!("{}::{}", node.label, node.namespace)
-        } else {
-            format!("{}::{}::{}", node.kind, node.label, node.namespace)
-        };
+        Err(e) => (String::new(), e, false),
+    }
+}
 
-        if let Some(&existing_idx) = seen.get(&key) {
-            if priority(&node.kind, &node.source)
-                < priority(&deduped[existing_idx].kind, &deduped[existing_idx].source)
-            {
-                deduped[existing_idx] = node;
-            }
-        } else {
-            seen.insert(key, deduped.len());
-            deduped.push(node);
-        }
+/// Plaintext of a SOPS-encrypted Helm values file, to be passed as
+/// `--values -`; None when the file isn't encrypted (or unreadable — helm
+/// reports that itself).
+fn decrypted_helm_values(values_path: &str, config: &ProjectConfig) -> Result<Option<String>, String> {
+    match fs::read_to_string(values_path) {
+        Ok(content) if is_sops_encrypted(&content) => decrypt_secret_manifest(&content, config)
+            .map(Some)
+            .map_err(|e| format!("{}: {}", values_path, e)),
+        _ => Ok(None),
     }
+}
 
-    for (i, node) in deduped.iter_mut().enumerate() {
-        node.id = format!("{}-{}", node.id, i);
+/// Run helm with `--values <values_path>` appended, or `--values -` fed the
+/// decrypted file when it is SOPS-encrypted.
+fn run_helm_with_values(
+    args: &[&str],
+    values_path: &str,
+    cwd: &Path,
+    config: &ProjectConfig,
+) -> (String, String, bool) {
+    match decrypted_helm_values(values_path, config) {
+        Ok(Some(values)) => {
+            let mut args = args.to_vec();
+            args.extend_from_slice(&["--values", "-"]);
+            run_helm_stdin(&args, cwd, &values)
+        }
+        Ok(None) => {
+            let mut args = args.to_vec();
+            args.extend_from_slice(&["--values", values_path]);
+            run_helm_output(&args, cwd)
+        }
+        Err(e) => (String::new(), e, false),
     }
+}
 
-    ScanResult {
-        nodes: deduped,
-        project_path: folder_path,
-        errors,
+/// Decrypt a project Secret file for the editor. Plain files are returned as-is.
+#[tauri::command]
+fn read_secret_file(
+    roots: tauri::State<ProjectRootState>,
+    file_path: String,
+) -> Result<String, String> {
+    let path = confine_path(&roots, &file_path)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read {}: {}", file_path, e))?;
+    if !is_sops_encrypted(&content) {
+        return Ok(content);
     }
+    decrypt_secret_manifest(&content, &active_project_config(&roots)?)
 }
 
+/// Encrypt plaintext from the editor and write the ciphertext to `file_path`.
 #[tauri::command]
-fn read_yaml_file(file_path: String) -> Result<String, String> {
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("Cannot read {}: {}", file_path, e))
+fn save_secret_file(
+    roots: tauri::State<ProjectRootState>,
+    file_path: String,
+    content: String,
+) -> Result<(), String> {
+    let path = confine_subpath(&roots, &file_path)?;
+    let encrypted = encrypt_secret_manifest(&content, &active_project_config(&roots)?)?;
+    fs::write(&path, encrypted)
+        .map_err(|e| format!("Cannot write {}: {}", file_path, e))
 }
 
+/// Encrypt a plaintext Secret file already in the project, in place.
+/// For migrating secrets generated before encryption was enabled.
 #[tauri::command]
-fn save_yaml_file(file_path: String, content: String) -> Result<(), String> {
-    if let Some(parent) = Path::new(&file_path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Cannot create dir {}: {}", parent.display(), e))?;
+fn encrypt_secret_file(
+    roots: tauri::State<ProjectRootState>,
+    file_path: String,
+) -> Result<(), String> {
+    let path = confine_subpath(&roots, &file_path)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read {}: {}", file_path, e))?;
+    if is_sops_encrypted(&content) {
+        return Ok(());
     }
-    fs::write(&file_path, content)
+    let encrypted = encrypt_secret_manifest(&content, &active_project_config(&roots)?)?;
+    fs::write(&path, encrypted)
         .map_err(|e| format!("Cannot write {}: {}", file_path, e))
 }
 
-#[tauri::command]
-async fn delete_field_files(file_paths: Vec<String>, namespace: String) -> DeleteResult {
-    tauri::async_runtime::spawn_blocking(move || {
-        delete_field_files_inner(file_paths, namespace)
-    }).await.unwrap_or_else(|e| DeleteResult {
-        deleted_files: vec![],
-        missing_files: vec![],
-        file_errors: vec![format!("spawn error: {}", e)],
-        kubectl_output: None,
-        kubectl_error: None,
-    })
+// ─── Secret backends (External Secrets / Sealed Secrets) ──────────────────────
+//
+// Besides SOPS, sensitive env vars can be handed to a cluster-side secret
+// manager: an ExternalSecret only names the remote keys (no values in the
+// repo at all), a SealedSecret is encrypted offline with the controller's
+// public cert. Either way the workload still reads `<id>-secret`.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SecretBackend {
+    Sops,
+    ExternalSecrets,
+    SealedSecrets,
 }
 
-fn delete_field_files_inner(file_paths: Vec<String>, namespace: String) -> DeleteResult {
-    let mut result = DeleteResult {
-        deleted_files: vec![],
-        missing_files: vec![],
-        file_errors: vec![],
-        kubectl_output: None,
-        kubectl_error: None,
-    };
+/// Backend for a generated secret: the request's choice, then the project
+/// default, then SOPS.
+fn secret_backend(requested: Option<&str>, project: &ProjectConfig) -> Result<SecretBackend, String> {
+    let name = requested
+        .filter(|s| !s.is_empty())
+        .or(project.secrets.backend.as_deref())
+        .unwrap_or("sops");
+    match name {
+        "sops" => Ok(SecretBackend::Sops),
+        "external-secrets" => Ok(SecretBackend::ExternalSecrets),
+        "sealed-secrets" => Ok(SecretBackend::SealedSecrets),
+        other => Err(format!(
+            "Unknown secret backend '{}' (expected sops, external-secrets or sealed-secrets)",
+            other
+        )),
+    }
+}
 
-    let mut kubectl_out_lines: Vec<String> = vec![];
-    let mut kubectl_err_lines: Vec<String> = vec![];
+/// ExternalSecret producing Secret `target`, one entry per key, all read as
+/// properties of a single remote key (`key_path` with `{namespace}`/`{name}`
+/// substituted). Uses the `v1` API (External Secrets Operator 0.17+).
+fn generate_external_secret_yaml(
+    target: &str,
+    app: &str,
+    ns: &str,
+    keys: &[&str],
+    ext: &ExternalSecretsConfig,
+) -> String {
+    let remote_key = ext.key_path
+        .replace("{namespace}", ns)
+        .replace("{name}", app);
+    let data: String = keys.iter()
+        .map(|k| format!(
+            "    - secretKey: {key}\n      remoteRef:\n        key: {remote}\n        property: {key}\n",
+            key = k,
+            remote = remote_key,
+        ))
+        .collect();
+    format!(
+        r#"apiVersion: external-secrets.io/v1
+kind: ExternalSecret
+metadata:
+  name: {target}
+  namespace: {ns}
+  labels:
+    app: {app}
+    managed-by: endfield
+spec:
+  refreshInterval: {refresh}
+  secretStoreRef:
+    name: {store}
+    kind: {store_kind}
+  target:
+    name: {target}
+    creationPolicy: Owner
+  data:
+{data}"#,
+        target = target,
+        app = app,
+        ns = ns,
+        refresh = ext.refresh_interval,
+        store = ext.store_name,
+        store_kind = ext.store_kind,
+        data = data,
+    )
+}
 
-    for file_path in &file_paths {
-        let p = Path::new(file_path);
-        if !p.exists() {
-            result.missing_files.push(file_path.clone());
-            continue;
+/// Seal a plaintext Secret offline with `kubeseal --cert` (stdin → stdout).
+fn seal_secret_manifest(yaml: &str, config: &ProjectConfig) -> Result<String, String> {
+    use std::io::Write;
+    let cert = config.secrets.sealed_secrets.cert_path.as_deref()
+        .ok_or("sealed-secrets backend needs secrets.sealed_secrets.cert_path in .endfield-config")?;
+    let mut child = Command::new("kubeseal")
+        .args(["--cert", cert, "--format", "yaml"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("kubeseal not found: {}", e))?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(yaml.as_bytes())
+            .map_err(|e| format!("stdin write error: {}", e))?;
+    }
+    let output = child.wait_with_output()
+        .map_err(|e| format!("kubeseal wait error: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("kubeseal failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Secret manifest for a field in the chosen backend, ready to write to the
+/// repo, plus the file suffix (`secret`, `externalsecret`, `sealedsecret`).
+/// None when the field has no sensitive env vars.
+fn field_secret_manifest(
+    cfg: &FieldConfig,
+    project: &ProjectConfig,
+) -> Result<Option<(String, &'static str)>, String> {
+    let backend = secret_backend(cfg.secret_backend.as_deref(), project)?;
+    if backend == SecretBackend::ExternalSecrets {
+        let keys: Vec<&str> = field_sensitive_env(cfg).iter()
+            .map(|e| e.key.as_str())
+            .collect();
+        if keys.is_empty() {
+            return Ok(None);
         }
+        let yaml = generate_external_secret_yaml(
+            &format!("{}-secret", cfg.id),
+            &cfg.id,
+            &cfg.namespace,
+            &keys,
+            &project.secrets.external_secrets,
+        );
+        return Ok(Some((yaml, "externalsecret")));
+    }
+    let Some(plain) = generate_secret_yaml(cfg) else {
+        return Ok(None);
+    };
+    match backend {
+        SecretBackend::SealedSecrets => Ok(Some((seal_secret_manifest(&plain, project)?, "sealedsecret"))),
+        _ => Ok(Some((encrypt_secret_manifest(&plain, project)?, "secret"))),
+    }
+}
 
-        // Step 1: kubectl delete from cluster
-        // Directories (helm component dirs or raw dirs) need --recursive
-        let kubectl_args: Vec<&str> = if p.is_dir() {
-            vec!["delete", "-f", file_path, "--recursive", "--ignore-not-found=true", "--wait=false"]
-        } else {
-            vec!["delete", "-f", file_path, "--ignore-not-found=true", "--wait=false"]
-        };
+/// Name of the Secret an object materialises in the cluster:
+/// Secret → metadata.name, ExternalSecret → spec.target.name,
+/// SealedSecret → spec.template.metadata.name (both falling back to metadata.name).
+fn produced_secret_name(doc: &str) -> Option<String> {
+    let own_name = extract_metadata_field(doc, "name")?.to_string();
+    let nested = match extract_yaml_field(doc, "kind")? {
+        "Secret" => return Some(own_name),
+        "ExternalSecret" => extract_block_field(doc, "target", "name"),
+        "SealedSecret" => extract_block_field(doc, "template", "name"),
+        _ => return None,
+    };
+    Some(nested.unwrap_or(own_name))
+}
 
-        match run_kubectl(&kubectl_args) {
-            Ok(out) => {
-                if !out.trim().is_empty() {
-                    kubectl_out_lines.push(format!("✓ {} → {}", file_path, out.trim()));
-                }
-            }
-            Err(e) => {
-                kubectl_err_lines.push(format!("✗ {} → {}", file_path, e.trim()));
+/// Secret names a workload references: env `secretKeyRef`, `envFrom.secretRef`,
+/// `secret.secretName` volumes and `imagePullSecrets`.
+fn extract_secret_refs(doc: &str) -> Vec<String> {
+    let mut refs: Vec<String> = Vec::new();
+    let mut block_indent: Option<usize> = None;
+    for line in doc.lines() {
+        let raw = line.trim_start();
+        let indent = line.len() - raw.len();
+        let t = raw.trim_start_matches("- ");
+        let unquote = |v: &str| v.trim().trim_matches('"').trim_matches('\'').to_string();
+        if let Some(name) = t.strip_prefix("secretName:") {
+            refs.push(unquote(name));
+            continue;
+        }
+        if t == "secretKeyRef:" || t == "secretRef:" || t == "imagePullSecrets:" {
+            block_indent = Some(indent);
+            continue;
+        }
+        if let Some(bi) = block_indent {
+            // list items of imagePullSecrets may sit at the key's own indent
+            let inside = raw.is_empty() || indent > bi || (indent == bi && raw.starts_with("- "));
+            if !inside {
+                block_indent = None;
+            } else if let Some(name) = t.strip_prefix("name:") {
+                refs.push(unquote(name));
             }
         }
+    }
+    refs.retain(|r| !r.is_empty());
+    refs.sort();
+    refs.dedup();
+    refs
+}
 
-        // Step 2: Delete from disk — handle both files and directories
-        let remove_result = if p.is_dir() {
-            fs::remove_dir_all(p)
-        } else {
-            fs::remove_file(p)
-        };
+// ─── NEW: Generate Field ───────────────────────────────────────────────────────
 
-        match remove_result {
-            Ok(_) => {
-                result.deleted_files.push(file_path.clone());
-                // For single files: clean up empty parent dir
-                if p.is_file() {
-                    if let Some(parent) = p.parent() {
-                        if parent.is_dir() {
-                            let is_empty = fs::read_dir(parent)
-                                .map(|mut d| d.next().is_none())
-                                .unwrap_or(false);
-                            if is_empty {
-                                let _ = fs::remove_dir(parent);
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                result.file_errors.push(format!("{}: {}", file_path, e));
-            }
-        }
-    }
+/// Generate manifests for a new Field (app/service) and write them to disk.
+/// Does NOT deploy — call deploy_resource() after reviewing.
+///
+/// Directory layout created:
+///   <project_path>/apps/<field_id>/
+///     namespace.yaml        (only if namespace is new)
+///     deployment.yaml       (or statefulset/daemonset/job/cronjob.yaml)
+///     service.yaml          (not for Jobs/CronJobs)
+///     configmap.yaml
+#[tauri::command]
+fn generate_field(
+    roots: tauri::State<ProjectRootState>,
+    config: FieldConfig,
+) -> GenerateResult {
+    write_field(&roots, config)
+}
 
-    if !kubectl_out_lines.is_empty() {
-        result.kubectl_output = Some(kubectl_out_lines.join("\n"));
-    }
-    if !kubectl_err_lines.is_empty() {
-        result.kubectl_error = Some(kubectl_err_lines.join("\n"));
+fn write_field(roots: &ProjectRootState, mut config: FieldConfig) -> GenerateResult {
+    let mut generated_files: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    // Auto-derive namespace if empty — all apps go to "apps" namespace
+    if config.namespace.is_empty() {
+        config.namespace = "apps".to_string();
     }
 
-    let _ = namespace;
-    result
-}
+    let field_dir = Path::new(&config.project_path)
+        .join("apps")
+        .join(&config.id);
+    let field_dir = match confine_subpath(roots, &field_dir.to_string_lossy()) {
+        Ok(d) => d,
+        Err(e) => {
+            return GenerateResult {
+                generated_files,
+                namespace_created: false,
+                namespace: config.namespace,
+                warnings,
+                error: Some(e.to_string()),
+            }
+        }
+    };
 
-#[tauri::command]
-fn kubectl_delete_by_label(label: String, namespace: String) -> Result<String, String> {
-    run_kubectl(&[
-        "delete",
-        "all",
-        "-l",
-        &format!("app={}", label),
-        "-n",
-        &namespace,
-        "--ignore-not-found=true",
-    ])
-}
+    // Secret for sensitive env keys — SOPS-encrypted, sealed or an
+    // ExternalSecret reference depending on the backend; never plaintext.
+    // Built before anything is written: without it the workload would
+    // reference a Secret that doesn't exist.
+    let secret = match active_project_config(roots)
+        .and_then(|project| field_secret_manifest(&config, &project))
+    {
+        Ok(secret) => secret,
+        Err(e) => {
+            let backend = config.secret_backend.as_deref().unwrap_or("configured").to_string();
+            return GenerateResult {
+                generated_files,
+                namespace_created: false,
+                namespace: config.namespace,
+                warnings,
+                error: Some(format!(
+                    "Secret cannot be generated — plaintext is never stored and the {} backend failed: {}",
+                    backend, e
+                )),
+            };
+        }
+    };
 
-#[tauri::command]
-fn get_cluster_status() -> ClusterStatus {
-    if run_kubectl(&["version", "--client"]).is_err() {
-        return ClusterStatus {
-            fields: vec![],
-            kubectl_available: false,
-            error: Some("kubectl not found".to_string()),
+    // Create directory
+    if let Err(e) = fs::create_dir_all(&field_dir) {
+        return GenerateResult {
+            generated_files,
+            namespace_created: false,
+            namespace: config.namespace,
+            warnings,
+            error: Some(format!("Cannot create directory {}: {}", field_dir.display(), e)),
         };
     }
 
-    let pods_raw = match run_kubectl(&["get", "pods", "--all-namespaces", "--no-headers"]) {
-        Ok(o) => o,
+    // Write namespace.yaml
+    let ns_path = field_dir.join("namespace.yaml");
+    let ns_yaml = generate_namespace_yaml(&config.namespace);
+    if let Err(e) = fs::write(&ns_path, &ns_yaml) {
+        return GenerateResult {
+            generated_files,
+            namespace_created: false,
+            namespace: config.namespace,
+            warnings,
+            error: Some(format!("Cannot write namespace.yaml: {}", e)),
+        };
+    }
+    generated_files.push(ns_path.to_string_lossy().to_string());
+
+    if let Some((manifest, suffix)) = secret {
+        let secret_path = field_dir.join(format!("{}-{}.yaml", config.id, suffix));
+        if let Err(e) = fs::write(&secret_path, &manifest) {
+            return GenerateResult {
+                generated_files,
+                namespace_created: false,
+                namespace: config.namespace,
+                warnings,
+                error: Some(format!("Cannot write {}-{}.yaml: {}", config.id, suffix, e)),
+            };
+        }
+        generated_files.push(secret_path.to_string_lossy().to_string());
+        if suffix == "externalsecret" {
+            warnings.push(format!(
+                "{}-secret is an ExternalSecret — populate its keys in the secret store before deploying",
+                config.id
+            ));
+        }
+    }
+
+    warnings.extend(config.volumes.iter().filter_map(|v| check_volume(v).err()));
+
+    let kind = match field_workload_kind(&config) {
+        Ok(k) => k,
         Err(e) => {
-            return ClusterStatus {
-                fields: vec![],
-                kubectl_available: true,
+            return GenerateResult {
+                generated_files,
+                namespace_created: false,
+                namespace: config.namespace,
+                warnings,
                 error: Some(e),
             }
         }
     };
-
-    let pods: Vec<PodInfo> = pods_raw
-        .lines()
-        .filter_map(|line| {
-            let p: Vec<&str> = line.split_whitespace().collect();
-            if p.len() < 5 {
-                return None;
+    let use_statefulset = kind == "StatefulSet";
+    let is_batch = kind == "Job" || kind == "CronJob";
+    if !use_statefulset && (config.storage.size.is_some() || config.storage.storage_class.is_some()) {
+        warnings.push(format!(
+            "{} is not a StatefulSet — storage settings only apply to StatefulSets; use a pvc volume instead",
+            config.id
+        ));
+    }
+    let mut volume_names = pod_volume_names(&config.volumes);
+    if use_statefulset {
+        volume_names.push("data".to_string());
+    }
+    warnings.extend(extra_container_warnings(
+        &config.id,
+        &config.init_containers,
+        &config.sidecars,
+        &volume_names,
+        &field_sensitive_env(&config).iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+    ));
+
+    // Workload manifest: StatefulSet for databases/caches/queues unless
+    // overridden, Deployment for everything else, or a batch Job/CronJob
+    let (workload_file, workload_yaml) = match kind {
+        "StatefulSet" => ("statefulset.yaml", Ok(generate_statefulset_yaml(&config))),
+        "DaemonSet" => ("daemonset.yaml", Ok(generate_daemonset_yaml(&config))),
+        "Job" => ("job.yaml", Ok(generate_job_yaml(&config))),
+        "CronJob" => ("cronjob.yaml", generate_cronjob_yaml(&config)),
+        _ => ("deployment.yaml", Ok(generate_deployment_yaml(&config))),
+    };
+    match workload_yaml {
+        Ok(yaml) => {
+            let workload_path = field_dir.join(workload_file);
+            if let Err(e) = fs::write(&workload_path, &yaml) {
+                warnings.push(format!("Cannot write {}: {}", workload_file, e));
+            } else {
+                generated_files.push(workload_path.to_string_lossy().to_string());
             }
-            let (ready, total) = parse_ready(p[2]);
-            Some(PodInfo {
-                namespace: p[0].to_string(),
-                name: p[1].to_string(),
-                phase: p[3].to_string(),
-                ready,
-                total,
-                restarts: p[4].parse().unwrap_or(0),
-            })
-        })
-        .collect();
+        }
+        Err(e) => warnings.push(e),
+    }
 
-    let mut fields: Vec<FieldStatus> = Vec::new();
+    // Replica count isn't ours to set for batch and per-node workloads
+    let fixed_replicas = is_batch || kind == "DaemonSet";
+    if fixed_replicas && (config.autoscaling.is_some() || config.availability.pdb) {
+        warnings.push(format!("{} is a {} — autoscaling and PDB settings ignored", config.id, kind));
+    }
 
-    for resource in &["deployments", "statefulsets"] {
-        let raw = run_kubectl(&["get", resource, "--all-namespaces", "--no-headers"])
-            .unwrap_or_default();
-        for line in raw.lines() {
-            let p: Vec<&str> = line.split_whitespace().collect();
-            if p.len() < 4 {
-                continue;
-            }
-            let ns = p[0].to_string();
-            let name = p[1].to_string();
+    if let Some(autoscaling) = config.autoscaling.as_ref().filter(|_| !fixed_replicas) {
+        let hpa_path = field_dir.join("hpa.yaml");
+        let hpa_yaml = generate_hpa_yaml(kind, &config.id, &config.namespace, autoscaling);
+        if let Err(e) = fs::write(&hpa_path, &hpa_yaml) {
+            warnings.push(format!("Cannot write hpa.yaml: {}", e));
+        } else {
+            generated_files.push(hpa_path.to_string_lossy().to_string());
+        }
+    }
 
-            let (desired, ready, available) =
-                if *resource == "deployments" && p.len() >= 5 {
-                    let (r, d) = parse_ready(p[2]);
-                    let avail: u32 = p[4].parse().unwrap_or(r);
-                    (d, r, avail)
+    if let Some(pdb_yaml) = generate_pdb_yaml(&config).filter(|_| !fixed_replicas) {
+        let pdb_path = field_dir.join("pdb.yaml");
+        if let Err(e) = fs::write(&pdb_path, &pdb_yaml) {
+            warnings.push(format!("Cannot write pdb.yaml: {}", e));
+        } else {
+            generated_files.push(pdb_path.to_string_lossy().to_string());
+        }
+    } else if config.availability.pdb && !fixed_replicas {
+        warnings.push(format!("{} runs a single replica — PodDisruptionBudget skipped", config.id));
+    }
+
+    if let Some(rbac) = &config.rbac {
+        match generate_rbac_yaml(&config, rbac) {
+            Ok(rbac_yaml) => {
+                let rbac_path = field_dir.join("rbac.yaml");
+                if let Err(e) = fs::write(&rbac_path, &rbac_yaml) {
+                    warnings.push(format!("Cannot write rbac.yaml: {}", e));
                 } else {
-                    let (r, d) = parse_ready(p[2]);
-                    (d, r, r)
-                };
+                    generated_files.push(rbac_path.to_string_lossy().to_string());
+                }
+            }
+            Err(e) => warnings.push(format!("rbac.yaml not written: {}", e)),
+        }
+        let wildcard = rbac.rules.iter()
+            .chain(&rbac.cluster_rules)
+            .any(|r| r.verbs.iter().chain(&r.resources).any(|v| v == "*"));
+        if wildcard {
+            warnings.push(format!("{} ServiceAccount has wildcard RBAC rules — grant only what it needs", config.id));
+        }
+    }
 
-            let my_pods: Vec<PodInfo> = pods
-                .iter()
-                .filter(|pod| pod.namespace == ns && pod.name.starts_with(&name))
-                .cloned()
-                .collect();
+    // Write service.yaml — batch pods don't serve traffic, portless agents neither
+    if !is_batch && (kind != "DaemonSet" || config.port > 0) {
+        let svc_path = field_dir.join("service.yaml");
+        let svc_yaml = generate_service_yaml(&config);
+        if let Err(e) = fs::write(&svc_path, &svc_yaml) {
+            warnings.push(format!("Cannot write service.yaml: {}", e));
+        } else {
+            generated_files.push(svc_path.to_string_lossy().to_string());
+        }
+    }
 
-            let status = compute_status(ready, desired).to_string();
-            fields.push(FieldStatus {
-                label: name,
-                namespace: ns,
-                desired,
-                ready,
-                available,
-                status,
-                pods: my_pods,
-            });
+    // ConfigMap only for stateless workloads
+    if !use_statefulset {
+        let cm_path = field_dir.join("configmap.yaml");
+        let cm_yaml = generate_configmap_yaml(&config);
+        if let Err(e) = fs::write(&cm_path, &cm_yaml) {
+            warnings.push(format!("Cannot write configmap.yaml: {}", e));
+        } else {
+            generated_files.push(cm_path.to_string_lossy().to_string());
         }
     }
 
-    ClusterStatus {
-        fields,
-        kubectl_available: true,
+    GenerateResult {
+        generated_files,
+        namespace_created: true, // file was written; actual cluster create happens on deploy
+        namespace: config.namespace,
+        warnings,
         error: None,
     }
 }
 
+/// Generate an HPA for an existing workload and write it next to the
+/// workload's manifest as `<name>-hpa.yaml`. Does NOT deploy.
 #[tauri::command]
-fn apply_replicas(
+fn generate_hpa(
+    roots: tauri::State<ProjectRootState>,
     file_path: String,
     node_label: String,
-    replicas: u32,
-) -> Result<String, String> {
-    patch_replicas_in_file(&file_path, &node_label, replicas)?;
-    let out = run_kubectl(&["apply", "-f", &file_path])?;
-    Ok(format!("✓ {}", out.trim()))
-}
+    autoscaling: AutoscalingConfig,
+) -> GenerateResult {
+    let mut warnings: Vec<String> = Vec::new();
+    let fail = |namespace: String, error: String| GenerateResult {
+        generated_files: vec![],
+        namespace_created: false,
+        namespace,
+        warnings: vec![],
+        error: Some(error),
+    };
 
-#[tauri::command]
-fn kubectl_apply(path: String) -> Result<String, String> {
-    run_kubectl(&["apply", "-f", &path])
+    let (path, root) = match confine_subpath(&roots, &file_path).and_then(|p| Ok((p, project_root(&roots)?))) {
+        Ok(v) => v,
+        Err(e) => return fail(String::new(), e.to_string()),
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) => return fail(String::new(), format!("Cannot read {}: {}", file_path, e)),
+    };
+    let Some(doc) = content
+        .split("\n---")
+        .find(|doc| extract_metadata_field(doc, "name") == Some(node_label.as_str()))
+    else {
+        return fail(String::new(), format!("'{}' not found in {}", node_label, file_path));
+    };
+    let kind = extract_yaml_field(doc, "kind").unwrap_or_default().to_string();
+    let namespace = extract_metadata_field(doc, "namespace").unwrap_or("default").to_string();
+    if !matches!(kind.as_str(), "Deployment" | "StatefulSet" | "ReplicaSet") {
+        return fail(namespace, format!("{} {} cannot be autoscaled", kind, node_label));
+    }
+    let path_str = path.to_string_lossy().to_string();
+    if let Some(hpa) = workload_autoscaler(&root, &path_str, &node_label) {
+        return fail(
+            namespace,
+            format!("{} is already scaled by HPA {} ({})", node_label, hpa.name, hpa.file_path),
+        );
+    }
+
+    let hpa_path = path.with_file_name(format!("{}-hpa.yaml", node_label));
+    let hpa_yaml = generate_hpa_yaml(&kind, &node_label, &namespace, &autoscaling);
+    if let Err(e) = fs::write(&hpa_path, &hpa_yaml) {
+        return fail(namespace, format!("Cannot write {}: {}", hpa_path.display(), e));
+    }
+    if extract_replicas(doc).is_some() {
+        warnings.push(format!(
+            "{} still sets spec.replicas — remove it so re-applying the manifest doesn't fight the HPA",
+            file_path
+        ));
+    }
+    GenerateResult {
+        generated_files: vec![hpa_path.to_string_lossy().to_string()],
+        namespace_created: false,
+        namespace,
+        warnings,
+        error: None,
+    }
 }
 
-#[tauri::command]
-fn get_pod_logs(namespace: String, pod_name: String, tail: u32) -> Result<String, String> {
-    run_kubectl(&[
-        "logs",
-        "-n",
-        &namespace,
-        &pod_name,
-        &format!("--tail={}", tail),
-    ])
+// ─── Docker Compose import ────────────────────────────────────────────────────
+//
+// `docker compose config --format json` does the YAML parsing, `extends`,
+// env_file merging and interpolation; what comes back is the normalized
+// model (long-form ports/volumes, depends_on as a map), which is mapped onto
+// one FieldConfig per service and written through the generate_field layout.
+
+/// Image of the init containers that wait for a dependency's port.
+const WAIT_IMAGE: &str = "busybox:1.36";
+
+/// Compose service keys mapped onto the field.
+const COMPOSE_MAPPED_KEYS: &[&str] = &[
+    "image", "build", "ports", "expose", "environment", "volumes", "depends_on", "command",
+    "entrypoint", "healthcheck", "deploy", "user", "cap_add", "cap_drop",
+    "stop_grace_period", "networks",
+];
+
+/// Keys dropped without a warning: naming and TTY details with no effect
+/// on a pod, and `env_file`, which compose has already merged into
+/// `environment`. `restart` is checked on its own.
+const COMPOSE_IGNORED_KEYS: &[&str] = &[
+    "container_name", "restart", "labels", "hostname", "tty", "stdin_open", "env_file",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComposeImportResult {
+    /// Configs of the generated fields, one per imported service
+    pub fields: Vec<FieldConfig>,
+    pub generated_files: Vec<String>,
+    /// Compose features that could not be carried over, per service
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
 }
 
-#[tauri::command]
-fn get_events(namespace: String) -> Result<String, String> {
-    if namespace == "all" {
-        run_kubectl(&[
-            "get",
-            "events",
-            "--all-namespaces",
-            "--sort-by=.lastTimestamp",
-            "--no-headers",
-        ])
-    } else {
-        run_kubectl(&[
-            "get",
-            "events",
-            "-n",
-            &namespace,
-            "--sort-by=.lastTimestamp",
-            "--no-headers",
-        ])
+/// Normalized compose model of `path`, via `docker compose` or the
+/// standalone `docker-compose`.
+fn compose_config_json(path: &Path) -> Result<serde_json::Value, String> {
+    let path_str = path.to_string_lossy().to_string();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let attempts: [(&str, Vec<&str>); 2] = [
+        ("docker", vec!["compose", "-f", &path_str, "config", "--format", "json"]),
+        ("docker-compose", vec!["-f", &path_str, "config", "--format", "json"]),
+    ];
+    let mut last_error = String::new();
+    for (program, args) in attempts {
+        match Command::new(program).args(&args).current_dir(dir).output() {
+            Ok(out) if out.status.success() => {
+                return serde_json::from_slice(&out.stdout)
+                    .map_err(|e| format!("Cannot parse compose config: {}", e));
+            }
+            Ok(out) => last_error = String::from_utf8_lossy(&out.stderr).trim().to_string(),
+            Err(e) if last_error.is_empty() => last_error = format!("{} not found: {}", program, e),
+            Err(_) => {}
+        }
     }
+    Err(format!("docker compose config failed: {}", last_error))
 }
 
-// ─── Helm commands ────────────────────────────────────────────────────────────
+/// Compose service name → field id (DNS-1123 label).
+fn compose_field_id(service: &str) -> String {
+    let id: String = service
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    id.trim_matches('-').to_string()
+}
 
-#[tauri::command]
-fn helm_template(
-    component_dir: String,
-    release_name: String,
-    namespace: String,
-    values_file: Option<String>,
-) -> HelmRenderResult {
-    let dir = Path::new(&component_dir);
-    let helm_dir = dir.join("helm");
-    let rendered_dir = dir.join("rendered");
-
-    if run_helm(&["version", "--short"], dir).is_err() {
-        return HelmRenderResult {
-            rendered_files: vec![],
-            warnings: vec![],
-            error: Some(
-                "helm CLI not found — install helm 3 to render templates".to_string(),
-            ),
+/// Seconds of a compose duration ("1m30s", "500ms"), rounded up.
+fn parse_compose_duration(value: &str) -> Option<u32> {
+    let mut total_ms: u64 = 0;
+    let mut number = String::new();
+    let mut chars = value.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let n: f64 = number.parse().ok()?;
+        number.clear();
+        let unit_ms = match c {
+            'h' => 3_600_000.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                1.0
+            }
+            'm' => 60_000.0,
+            's' => 1000.0,
+            _ => return None,
         };
+        total_ms += (n * unit_ms) as u64;
+    }
+    if !number.is_empty() {
+        return None;
     }
+    Some(total_ms.div_ceil(1000) as u32)
+}
 
-    if let Err(e) = run_helm(&["dependency", "update", "."], &helm_dir) {
-        return HelmRenderResult {
-            rendered_files: vec![],
-            warnings: vec![],
-            error: Some(format!("helm dependency update failed: {}", e)),
-        };
+/// Compose command/entrypoint: a list, or a string run through a shell.
+fn compose_command(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() => vec!["sh".to_string(), "-c".to_string(), s.clone()],
+        serde_json::Value::Array(items) => items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect(),
+        _ => vec![],
     }
+}
 
-    let values_path = values_file
-        .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+fn compose_scalar(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
 
-    let raw = match run_helm(
-        &[
-            "template",
-            &release_name,
-            ".",
-            "--namespace",
-            &namespace,
-            "--values",
-            &values_path,
-            "--include-crds",
-        ],
-        &helm_dir,
-    ) {
-        Ok(out) => out,
-        Err(e) => {
-            return HelmRenderResult {
-                rendered_files: vec![],
-                warnings: vec![],
-                error: Some(format!("helm template failed: {}", e)),
-            }
-        }
+/// Container port of a service: the first `ports` target, else `expose`.
+fn compose_service_port(service: &serde_json::Value) -> Option<u32> {
+    let from_ports = service["ports"].as_array().and_then(|ports| {
+        ports.iter().find_map(|p| p["target"].as_u64().or_else(|| compose_scalar(&p["target"])?.parse().ok()))
+    });
+    let from_expose = || {
+        service["expose"].as_array()?.iter().find_map(|e| {
+            compose_scalar(e)?.split(['/', '-']).next()?.parse::<u64>().ok()
+        })
     };
+    from_ports.or_else(from_expose).map(|p| p as u32)
+}
 
-    if !rendered_dir.exists() {
-        if let Err(e) = fs::create_dir_all(&rendered_dir) {
-            return HelmRenderResult {
-                rendered_files: vec![],
-                warnings: vec![],
-                error: Some(format!("Cannot create rendered/: {}", e)),
-            };
-        }
-    } else if let Ok(entries) = fs::read_dir(&rendered_dir) {
-        for entry in entries.flatten() {
-            let p = entry.path();
-            if p.file_name().and_then(|n| n.to_str()) != Some(".gitkeep") {
-                let _ = fs::remove_file(&p);
+/// Compose healthcheck → readiness probe. `NONE`/disabled turns the default
+/// probe off.
+fn compose_healthcheck_probe(check: &serde_json::Value) -> Option<ProbeConfig> {
+    let test = compose_command(&check["test"]);
+    let disabled = check["disable"].as_bool() == Some(true) || test.first().map(String::as_str) == Some("NONE");
+    let command = match test.first().map(String::as_str) {
+        _ if disabled => vec![],
+        Some("CMD") => test[1..].to_vec(),
+        Some("CMD-SHELL") => vec!["sh".to_string(), "-c".to_string(), test[1..].join(" ")],
+        Some("sh") => test,
+        _ => return None,
+    };
+    let seconds = |key: &str| check[key].as_str().and_then(parse_compose_duration);
+    Some(ProbeConfig {
+        kind: if disabled { "none" } else { "exec" }.to_string(),
+        path: None,
+        port: None,
+        command,
+        initial_delay_seconds: seconds("start_period"),
+        period_seconds: seconds("interval"),
+        timeout_seconds: seconds("timeout"),
+        failure_threshold: check["retries"].as_u64().map(|r| r as u32),
+    })
+}
+
+fn generate_pvc_yaml(name: &str, ns: &str, size: &str, access_mode: &str) -> String {
+    format!(
+        r#"apiVersion: v1
+kind: PersistentVolumeClaim
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    managed-by: endfield
+spec:
+  accessModes:
+    - {access_mode}
+  resources:
+    requests:
+      storage: {size}
+"#,
+        name = name,
+        ns = ns,
+        size = size,
+        access_mode = access_mode,
+    )
+}
+
+/// Map one compose service onto a FieldConfig. Returns the config, the
+/// PVCs (claim name) it mounts and warnings for what was dropped.
+fn compose_service_field(
+    name: &str,
+    service: &serde_json::Value,
+    all_services: &serde_json::Map<String, serde_json::Value>,
+    namespace: &str,
+    project_path: &str,
+) -> (FieldConfig, Vec<String>, Vec<String>) {
+    let id = compose_field_id(name);
+    let mut warnings: Vec<String> = Vec::new();
+    let mut warn = |msg: String| warnings.push(format!("{}: {}", name, msg));
+
+    if let Some(map) = service.as_object() {
+        for key in map.keys() {
+            if !COMPOSE_MAPPED_KEYS.contains(&key.as_str()) && !COMPOSE_IGNORED_KEYS.contains(&key.as_str()) {
+                warn(format!("`{}` is not supported — skipped", key));
             }
         }
     }
+    // Deployment pods always restart
+    if let Some(policy) = service["restart"].as_str().filter(|p| !matches!(*p, "always" | "unless-stopped")) {
+        warn(format!("restart: {} is not carried over — Kubernetes restarts the container whenever it exits", policy));
+    }
 
-    let manifests = split_rendered_manifests(&raw);
-    let mut rendered_files = Vec::new();
-    let mut warnings = Vec::new();
-
-    for (filename, content) in &manifests {
-        let out_path = rendered_dir.join(filename);
-        match fs::write(&out_path, content) {
-            Ok(_) => rendered_files.push(out_path.to_string_lossy().to_string()),
-            Err(e) => warnings.push(format!("Failed to write {}: {}", filename, e)),
+    let image = match service["image"].as_str() {
+        Some(image) => image.to_string(),
+        None => {
+            warn(format!("builds from source — push the image and set it; using {}:latest", id));
+            format!("{}:latest", id)
         }
+    };
+    if service["build"].is_object() && service["image"].is_string() {
+        warn("has both build and image — the image is used, the build section ignored".to_string());
     }
 
-    HelmRenderResult {
-        rendered_files,
-        warnings,
-        error: None,
+    let port = compose_service_port(service).unwrap_or(0);
+    let port_count = service["ports"].as_array().map_or(0, |p| p.len())
+        + service["expose"].as_array().map_or(0, |e| e.len());
+    if port_count > 1 {
+        warn(format!("exposes several ports — only {} is carried over", port));
+    }
+    let published: Vec<String> = service["ports"].as_array().into_iter().flatten()
+        .filter_map(|p| compose_scalar(&p["published"]))
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !published.is_empty() {
+        warn(format!(
+            "host port(s) {} not published — reach the field through its Service or an ingress route",
+            published.join(", ")
+        ));
     }
-}
 
-#[tauri::command]
-fn helm_install(
-    component_dir: String,
-    release_name: String,
-    namespace: String,
-    values_file: Option<String>,
-) -> Result<String, String> {
-    let dir = Path::new(&component_dir);
-    let helm_dir = dir.join("helm");
+    let mut env: Vec<EnvVar> = Vec::new();
+    if let Some(vars) = service["environment"].as_object() {
+        for (key, value) in vars {
+            let value = compose_scalar(value).unwrap_or_else(|| {
+                warn(format!("{} has no value (taken from the host) — left empty", key));
+                String::new()
+            });
+            env.push(EnvVar { key: key.clone(), value });
+        }
+    }
+    env.sort_by(|a, b| a.key.cmp(&b.key));
 
-    run_helm(&["dependency", "update", "."], &helm_dir)?;
+    let deploy = &service["deploy"];
+    let global = deploy["mode"].as_str() == Some("global");
+    let replicas = deploy["replicas"].as_u64().unwrap_or(1) as u32;
+    if deploy["resources"].is_object() {
+        warn("deploy.resources not carried over — the type defaults apply".to_string());
+    }
+    let workload_kind = global.then(|| "daemonset".to_string());
+    let stateful = !global && is_stateful_image(&image);
+
+    let mut storage = StorageConfig::default();
+    let mut volumes: Vec<VolumeConfig> = Vec::new();
+    let mut claims: Vec<String> = Vec::new();
+    for (i, volume) in service["volumes"].as_array().into_iter().flatten().enumerate() {
+        let target = volume["target"].as_str().unwrap_or_default().to_string();
+        let source = volume["source"].as_str().unwrap_or_default();
+        let read_only = volume["read_only"].as_bool().unwrap_or(false);
+        match volume["type"].as_str().unwrap_or("volume") {
+            "volume" if !source.is_empty() => {
+                let claim = compose_field_id(source);
+                if stateful && storage.mount_path.is_none() {
+                    // the StatefulSet's own claim template replaces the named volume
+                    storage.mount_path = Some(target);
+                    continue;
+                }
+                volumes.push(VolumeConfig {
+                    name: claim.clone(),
+                    kind: "pvc".to_string(),
+                    source: Some(claim.clone()),
+                    mount_path: target,
+                    sub_path: None,
+                    read_only,
+                    size_limit: None,
+                    medium: None,
+                });
+                claims.push(claim);
+            }
+            "volume" | "tmpfs" => {
+                let tmpfs = volume["type"].as_str() == Some("tmpfs");
+                volumes.push(VolumeConfig {
+                    name: format!("{}-{}", if tmpfs { "tmpfs" } else { "scratch" }, i),
+                    kind: "emptyDir".to_string(),
+                    source: None,
+                    mount_path: target,
+                    sub_path: None,
+                    read_only: false,
+                    size_limit: None,
+                    medium: tmpfs.then(|| "Memory".to_string()),
+                });
+            }
+            other => warn(format!(
+                "{} mount {} → {} not carried over — use a ConfigMap or bake the files into the image",
+                other, source, target
+            )),
+        }
+    }
 
-    let values_path = values_file
-        .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+    let mut init_containers: Vec<ExtraContainer> = Vec::new();
+    let depends_on: Vec<(String, String)> = match &service["depends_on"] {
+        serde_json::Value::Object(deps) => deps.iter()
+            .map(|(dep, opts)| (dep.clone(), opts["condition"].as_str().unwrap_or("service_started").to_string()))
+            .collect(),
+        serde_json::Value::Array(deps) => deps.iter()
+            .filter_map(|d| d.as_str().map(|d| (d.to_string(), "service_started".to_string())))
+            .collect(),
+        _ => vec![],
+    };
+    for (dep, condition) in depends_on {
+        let dep_id = compose_field_id(&dep);
+        if condition == "service_completed_successfully" {
+            warn(format!("depends_on {} completing — not expressible, run it as a Job before deploying", dep));
+            continue;
+        }
+        let Some(dep_port) = all_services.get(&dep).and_then(compose_service_port) else {
+            warn(format!("depends_on {} which exposes no port — no wait container added", dep));
+            continue;
+        };
+        init_containers.push(ExtraContainer {
+            name: format!("wait-for-{}", dep_id),
+            image: WAIT_IMAGE.to_string(),
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("until nc -z {dep} {port}; do echo waiting for {dep}; sleep 2; done", dep = dep_id, port = dep_port),
+            ],
+            args: vec![],
+            env: vec![],
+            secret_env: vec![],
+            ports: vec![],
+            volume_mounts: vec![],
+        });
+    }
 
-    let out = run_helm(
-        &[
-            "upgrade",
-            "--install",
-            &release_name,
-            ".",
-            "--namespace",
-            &namespace,
-            "--create-namespace",
-            "--values",
-            &values_path,
-            "--atomic=false",
-        ],
-        &helm_dir,
-    )?;
+    let networks: Vec<&String> = service["networks"].as_object()
+        .map(|n| n.keys().filter(|k| *k != "default").collect())
+        .unwrap_or_default();
+    if !networks.is_empty() {
+        warn("custom networks are flattened — every field can reach every other through its Service".to_string());
+    }
 
-    Ok(format!("✓ {}", out.trim()))
-}
+    let mut security_context: Option<SecurityContextConfig> = None;
+    let user = service["user"].as_str().unwrap_or_default();
+    let cap_list = |key: &str| -> Vec<String> {
+        service[key].as_array().into_iter().flatten()
+            .filter_map(|c| c.as_str().map(|c| c.trim_start_matches("CAP_").to_string()))
+            .collect()
+    };
+    let (cap_add, cap_drop) = (cap_list("cap_add"), cap_list("cap_drop"));
+    if !user.is_empty() || !cap_add.is_empty() || !cap_drop.is_empty() {
+        let (uid, gid) = user.split_once(':').unwrap_or((user, ""));
+        if !uid.is_empty() && uid.parse::<u64>().is_err() {
+            warn(format!("user {} is a name — Kubernetes needs a numeric UID", uid));
+        }
+        security_context = Some(SecurityContextConfig {
+            run_as_user: uid.parse().ok(),
+            run_as_group: gid.parse().ok(),
+            allow_privilege_escalation: Some(false),
+            add_capabilities: cap_add,
+            drop_capabilities: cap_drop,
+            ..Default::default()
+        });
+    }
 
-#[tauri::command]
-fn helm_uninstall(release_name: String, namespace: String) -> Result<String, String> {
-    run_helm(
-        &[
-            "uninstall",
-            &release_name,
-            "--namespace",
-            &namespace,
-            "--ignore-not-found",
-        ],
-        Path::new("."),
-    )
-}
+    let runtime = ContainerRuntime {
+        readiness_probe: compose_healthcheck_probe(&service["healthcheck"]),
+        security_context,
+        command: compose_command(&service["entrypoint"]),
+        args: compose_command(&service["command"]),
+        termination_grace_period_seconds: service["stop_grace_period"].as_str().and_then(parse_compose_duration),
+        ..Default::default()
+    };
 
-#[tauri::command]
-fn helm_available() -> bool {
-    Command::new("helm")
-        .arg("version")
-        .arg("--short")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+    let config = FieldConfig {
+        id,
+        label: name.to_string(),
+        namespace: namespace.to_string(),
+        image,
+        replicas,
+        port,
+        env,
+        project_path: project_path.to_string(),
+        secret_backend: None,
+        runtime,
+        storage,
+        volumes,
+        autoscaling: None,
+        availability: AvailabilityConfig::default(),
+        workload_kind,
+        batch: BatchConfig::default(),
+        placement: PlacementConfig::default(),
+        init_containers,
+        sidecars: vec![],
+        rbac: None,
+    };
+    (config, claims, warnings)
 }
 
+/// Import a docker-compose file from the project: one field per service
+/// under apps/, generated like generate_field. Sensitive environment keys
+/// land in the field's Secret, named volumes become PVCs (or the claim
+/// template of a stateful image), `depends_on` becomes init containers
+/// waiting for the dependency's port. Services whose field directory already
+/// exists are skipped. Does NOT deploy.
 #[tauri::command]
-fn helm_template_async(
-    component_dir: String,
-    release_name: String,
-    namespace: String,
-    values_file: Option<String>,
-) -> Result<String, String> {
-    std::thread::spawn(move || {
-        let dir = std::path::Path::new(&component_dir);
-        let helm_dir = dir.join("helm");
-        let rendered_dir = dir.join("rendered");
-        if run_helm(&["version", "--short"], dir).is_err() {
-            return;
+fn import_compose(
+    roots: tauri::State<ProjectRootState>,
+    compose_path: String,
+    namespace: Option<String>,
+) -> ComposeImportResult {
+    let mut result = ComposeImportResult { fields: vec![], generated_files: vec![], warnings: vec![], error: None };
+    let paths = confine_path(&roots, &compose_path).and_then(|p| Ok((p, project_root(&roots)?)));
+    let (path, root) = match paths {
+        Ok(p) => p,
+        Err(e) => return ComposeImportResult { error: Some(e.to_string()), ..result },
+    };
+    let compose = match compose_config_json(&path) {
+        Ok(c) => c,
+        Err(e) => return ComposeImportResult { error: Some(e), ..result },
+    };
+    let Some(services) = compose["services"].as_object() else {
+        return ComposeImportResult { error: Some(format!("{} defines no services", compose_path)), ..result };
+    };
+    for key in ["secrets", "configs"] {
+        if compose[key].as_object().is_some_and(|m| !m.is_empty()) {
+            result.warnings.push(format!(
+                "top-level {} are not imported — add them as Secret/ConfigMap volumes on the fields",
+                key
+            ));
         }
-        if run_helm(&["dependency", "update", "."], &helm_dir).is_err() {
-            return;
+    }
+
+    let namespace = namespace.unwrap_or_default();
+    let root_str = root.to_string_lossy().to_string();
+    // claim → services mounting it; a claim is written once, next to its first user
+    let mut claim_users: std::collections::BTreeMap<String, Vec<String>> = std::collections::BTreeMap::new();
+    for (name, service) in services {
+        let (config, claims, warnings) = compose_service_field(name, service, services, &namespace, &root_str);
+        result.warnings.extend(warnings);
+        if config.id.is_empty() {
+            result.warnings.push(format!("{}: cannot derive a field id from the service name — skipped", name));
+            continue;
         }
-        let values_path = values_file
-            .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
-        let raw = match run_helm(
-            &[
-                "template",
-                &release_name,
-                ".",
-                "--namespace",
-                &namespace,
-                "--values",
-                &values_path,
-                "--include-crds",
-            ],
-            &helm_dir,
-        ) {
-            Ok(out) => out,
-            Err(_) => return,
-        };
-        if !rendered_dir.exists() {
-            if std::fs::create_dir_all(&rendered_dir).is_err() { return; }
-        } else if let Ok(entries) = std::fs::read_dir(&rendered_dir) {
-            for entry in entries.flatten() {
-                let p = entry.path();
-                if p.file_name().and_then(|n| n.to_str()) != Some(".gitkeep") {
-                    let _ = std::fs::remove_file(&p);
+        if root.join("apps").join(&config.id).exists() {
+            result.warnings.push(format!("{}: apps/{} already exists — skipped", name, config.id));
+            continue;
+        }
+        let generated = write_field(&roots, config.clone());
+        result.warnings.extend(generated.warnings.into_iter().map(|w| format!("{}: {}", name, w)));
+        result.generated_files.extend(generated.generated_files);
+        if let Some(e) = generated.error {
+            result.warnings.push(format!("{}: not generated — {}", name, e));
+            continue;
+        }
+        let field_ns = generated.namespace;
+        let config = FieldConfig { namespace: field_ns.clone(), ..config };
+        for claim in claims {
+            let users = claim_users.entry(claim.clone()).or_default();
+            if users.is_empty() {
+                let pvc_path = root.join("apps").join(&config.id).join(format!("{}-pvc.yaml", claim));
+                match fs::write(&pvc_path, generate_pvc_yaml(&claim, &field_ns, "10Gi", "ReadWriteOnce")) {
+                    Ok(_) => result.generated_files.push(pvc_path.to_string_lossy().to_string()),
+                    Err(e) => result.warnings.push(format!("Cannot write {}: {}", pvc_path.display(), e)),
                 }
             }
+            users.push(name.clone());
         }
-        let manifests = split_rendered_manifests(&raw);
-        for (filename, content) in &manifests {
-            let out_path = rendered_dir.join(filename);
-            let _ = std::fs::write(&out_path, content);
-        }
-    });
-    Ok("started".to_string())
+        result.fields.push(config);
+    }
+    for (claim, users) in claim_users.iter().filter(|(_, u)| u.len() > 1) {
+        result.warnings.push(format!(
+            "volume {} is shared by {} — a ReadWriteOnce claim only mounts on one node; switch it to ReadWriteMany if your storage class supports it",
+            claim,
+            users.join(", ")
+        ));
+    }
+    result
 }
 
+// ─── NEW: Generate Infra ──────────────────────────────────────────────────────
+
+/// Generate manifests or Helm scaffold for an Infrastructure component.
+///
+/// For Helm:
+///   <project_path>/infra/<infra_id>/
+///     namespace.yaml
+///     helm/Chart.yaml
+///     helm/values.yaml
+///     rendered/           (empty, populated on helm_template)
+///
+/// For Raw:
+///   Files are expected to exist already — this command just validates structure.
 #[tauri::command]
-fn helm_install_async(
-    component_dir: String,
-    release_name: String,
+fn generate_infra(
+    roots: tauri::State<ProjectRootState>,
+    config: InfraConfig,
+) -> GenerateResult {
+    let mut generated_files: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    let namespace = config
+        .namespace
+        .clone()
+        .unwrap_or_else(|| format!("infra-{}", config.id));
+
+    let infra_dir = Path::new(&config.project_path)
+        .join("infra")
+        .join(&config.id);
+    let infra_dir = match confine_subpath(&roots, &infra_dir.to_string_lossy()) {
+        Ok(d) => d,
+        Err(e) => {
+            return GenerateResult {
+                generated_files,
+                namespace_created: false,
+                namespace,
+                warnings,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    if let Err(e) = fs::create_dir_all(&infra_dir) {
+        return GenerateResult {
+            generated_files,
+            namespace_created: false,
+            namespace,
+            warnings,
+            error: Some(format!("Cannot create directory {}: {}", infra_dir.display(), e)),
+        };
+    }
+
+    // Write namespace.yaml
+    let ns_path = infra_dir.join("namespace.yaml");
+    let ns_yaml = generate_namespace_yaml(&namespace);
+    if let Err(e) = fs::write(&ns_path, &ns_yaml) {
+        return GenerateResult {
+            generated_files,
+            namespace_created: false,
+            namespace,
+            warnings,
+            error: Some(format!("Cannot write namespace.yaml: {}", e)),
+        };
+    }
+    generated_files.push(ns_path.to_string_lossy().to_string());
+
+    if config.source == "helm" {
+        let helm = match &config.helm {
+            Some(h) => h.clone(),
+            None => {
+                return GenerateResult {
+                    generated_files,
+                    namespace_created: false,
+                    namespace,
+                    warnings,
+                    error: Some("source=helm but helm config is missing".to_string()),
+                }
+            }
+        };
+
+        let helm_dir = infra_dir.join("helm");
+        if let Err(e) = fs::create_dir_all(&helm_dir) {
+            return GenerateResult {
+                generated_files,
+                namespace_created: false,
+                namespace,
+                warnings,
+                error: Some(format!("Cannot create helm/: {}", e)),
+            };
+        }
+
+        // Write Chart.yaml
+        let chart_path = helm_dir.join("Chart.yaml");
+        let chart_yaml = generate_helm_chart_yaml(&config, &helm);
+        if let Err(e) = fs::write(&chart_path, &chart_yaml) {
+            warnings.push(format!("Cannot write Chart.yaml: {}", e));
+        } else {
+            generated_files.push(chart_path.to_string_lossy().to_string());
+        }
+
+        // Write values.yaml (only if no custom values_path provided)
+        if helm.values_path.is_none() {
+            let values_path = helm_dir.join("values.yaml");
+            let values_yaml = generate_helm_values_yaml(&config, &helm);
+            if let Err(e) = fs::write(&values_path, &values_yaml) {
+                warnings.push(format!("Cannot write values.yaml: {}", e));
+            } else {
+                generated_files.push(values_path.to_string_lossy().to_string());
+            }
+        }
+
+        // Create rendered/ placeholder
+        let rendered_dir = infra_dir.join("rendered");
+        if let Err(e) = fs::create_dir_all(&rendered_dir) {
+            warnings.push(format!("Cannot create rendered/: {}", e));
+        } else {
+            // .gitkeep so the directory is tracked by git
+            let gitkeep = rendered_dir.join(".gitkeep");
+            let _ = fs::write(&gitkeep, "");
+            generated_files.push(rendered_dir.to_string_lossy().to_string());
+        }
+    } else {
+        // Raw YAML — validate that a yaml file exists
+        if let Some(raw_path) = &config.raw_yaml_path {
+            let full = Path::new(&config.project_path).join(raw_path);
+            if !full.exists() {
+                warnings.push(format!(
+                    "Raw YAML path does not exist: {}",
+                    full.display()
+                ));
+            }
+        } else {
+            warnings.push("source=raw but raw_yaml_path is not provided".to_string());
+        }
+    }
+
+    GenerateResult {
+        generated_files,
+        namespace_created: true,
+        namespace,
+        warnings,
+        error: None,
+    }
+}
+
+// ─── NEW: Deploy Resource ─────────────────────────────────────────────────────
+
+/// Deploy a resource to the cluster.
+///
+/// For source="helm":
+///   1. helm repo add <repo_name> <repo_url>       (if repo_url is set)
+///   2. helm dependency update <helm_dir>
+///   3. helm template → write to rendered/
+///   4. helm upgrade --install ...
+///
+/// For source="raw":
+///   1. kubectl apply -f <dir>  (entire field/infra dir)
+///
+/// Namespace is always ensured before deploy. With `wait_timeout_secs` the
+/// deployed workloads are then tracked until ready (see `wait_after_deploy`).
+///
+/// `pin_images` resolves every tagged image to its registry digest first:
+///   "apply" — only the applied manifests (and helm's rendered/) are pinned
+///   "files" — once the apply succeeded, the raw manifests in the project
+///             are rewritten as well
+/// A pinned Helm deploy still goes through `helm upgrade`, with the chart's
+/// image values overridden (see `helm_image_overrides`); a chart whose
+/// images can't all be set through its values is not deployed pinned.
+#[tauri::command]
+async fn deploy_resource(
+    app: tauri::AppHandle,
+    resource_id: String,
+    source: String,
+    resource_dir: String,
     namespace: String,
+    helm_release: Option<String>,
+    helm_repo_name: Option<String>,
+    helm_repo_url: Option<String>,
     values_file: Option<String>,
-) -> Result<String, String> {
-    std::thread::spawn(move || {
-        let dir = std::path::Path::new(&component_dir);
+    wait_timeout_secs: Option<u64>,
+    pin_images: Option<String>,
+) -> DeployResult {
+    let roots = app.state::<ProjectRootState>();
+    let confined = confine_subpath(&roots, &resource_dir).and_then(|dir| {
+        let values = values_file
+            .as_deref()
+            .map(|v| confine_path(&roots, v))
+            .transpose()?;
+        Ok((dir, values))
+    });
+    let (resource_dir, values_file) = match confined {
+        Ok((dir, values)) => (
+            dir.to_string_lossy().to_string(),
+            values.map(|v| v.to_string_lossy().to_string()),
+        ),
+        Err(e) => return DeployResult {
+            resource_id, namespace, source, stdout: String::new(),
+            stderr: e.to_string(), success: false, commands_run: vec![],
+            readiness: None,
+        },
+    };
+    let root = project_root(&roots).ok();
+    let project = match active_project_config(&roots) {
+        Ok(p) => p,
+        Err(e) => return DeployResult {
+            resource_id, namespace, source, stdout: String::new(),
+            stderr: e, success: false, commands_run: vec![],
+            readiness: None,
+        },
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let release = (source == "helm").then(|| helm_release.clone().unwrap_or_else(|| resource_id.clone()));
+        let dir = PathBuf::from(&resource_dir);
+        let mut result = deploy_resource_inner(resource_id, source, resource_dir, namespace,
+            helm_release, helm_repo_name, helm_repo_url, values_file, pin_images.as_deref(), &project);
+        if let Some(timeout) = wait_timeout_secs.filter(|_| result.success) {
+            wait_after_deploy(&mut result, &dir, release.as_deref(), timeout);
+        }
+        record_deploy_audit(root.as_deref(), "deploy_resource", &result);
+        result
+    }).await.unwrap_or_else(|e| DeployResult {
+        resource_id: String::new(), namespace: String::new(),
+        source: String::new(), stdout: String::new(),
+        stderr: format!("spawn error: {}", e),
+        success: false, commands_run: vec![],
+        readiness: None,
+    })
+}
+
+fn deploy_resource_inner(
+    resource_id: String,
+    source: String,
+    resource_dir: String,
+    namespace: String,
+    helm_release: Option<String>,
+    helm_repo_name: Option<String>,
+    helm_repo_url: Option<String>,
+    values_file: Option<String>,
+    pin_images: Option<&str>,
+    project: &ProjectConfig,
+) -> DeployResult {
+    let mut commands_run: Vec<String> = Vec::new();
+    let dir = Path::new(&resource_dir);
+
+    if let Some(mode) = pin_images.filter(|m| *m != "apply" && *m != "files") {
+        return DeployResult {
+            resource_id,
+            namespace,
+            source,
+            stdout: String::new(),
+            stderr: format!("Unknown pin_images mode {:?} (expected \"apply\" or \"files\")", mode),
+            success: false,
+            commands_run,
+            readiness: None,
+        };
+    }
+
+    // Ensure namespace exists in cluster
+    match ensure_namespace(&namespace) {
+        Ok(_created) => {
+            commands_run.push(format!(
+                "kubectl get namespace {} || kubectl create namespace {}",
+                namespace, namespace
+            ));
+        }
+        Err(e) => {
+            return DeployResult {
+                resource_id,
+                namespace,
+                source,
+                stdout: String::new(),
+                stderr: e.clone(),
+                success: false,
+                commands_run,
+                readiness: None,
+            };
+        }
+    }
+
+    if source == "helm" {
         let helm_dir = dir.join("helm");
-        if run_helm(&["dependency", "update", "."], &helm_dir).is_err() {
-            return;
+        let release = helm_release.unwrap_or_else(|| resource_id.clone());
+        let values_path = values_file.unwrap_or_else(|| {
+            helm_dir.join("values.yaml").to_string_lossy().to_string()
+        });
+
+        // Step 1: helm repo add (if repo_url provided)
+        if let (Some(repo_name), Some(repo_url)) = (&helm_repo_name, &helm_repo_url) {
+            let repo_add_cmd = format!("helm repo add {} {}", repo_name, repo_url);
+            commands_run.push(repo_add_cmd);
+            // Not fatal — repo might already exist
+            let _ = run_helm(&["repo", "add", repo_name, repo_url], dir);
+            let _ = run_helm(&["repo", "update"], dir);
+            commands_run.push("helm repo update".to_string());
         }
-        let values_path = values_file
-            .unwrap_or_else(|| helm_dir.join(  "values.yaml").to_string_lossy().to_string());
-        let _ = run_helm(
+
+        // Step 2: helm dependency update
+        let dep_cmd = format!("helm dependency update {}", helm_dir.display());
+        commands_run.push(dep_cmd);
+        if let Err(e) = run_helm(&["dependency", "update", "."], &helm_dir) {
+            return DeployResult {
+                resource_id,
+                namespace,
+                source,
+                stdout: String::new(),
+                stderr: format!("helm dependency update failed: {}", e),
+                success: false,
+                commands_run,
+                readiness: None,
+            };
+        }
+
+        // Pinning: image overrides for the chart's values, resolved in memory
+        let (pins, overrides) = match pin_images {
+            Some(_) => {
+                commands_run.push(format!(
+                    "helm upgrade --install {} . --namespace {} --dry-run --output json  # read image values",
+                    release, namespace
+                ));
+                match helm_image_overrides(&release, &namespace, &values_path, &helm_dir, project) {
+                    Ok(o) => o,
+                    Err(e) => return DeployResult {
+                        resource_id, namespace, source, stdout: String::new(),
+                        stderr: e, success: false, commands_run,
+                        readiness: None,
+                    },
+                }
+            }
+            None => (vec![], vec![]),
+        };
+
+        // Step 3: helm template → rendered/
+        let template_cmd = format!(
+            "helm template {} . --namespace {} --values {} --include-crds",
+            release, namespace, values_path
+        );
+        commands_run.push(template_cmd);
+        match run_helm(
             &[
-                "upgrade",
-                "--install",
-                &release_name,
-                ".",
-                "--namespace",
-                &namespace,
-                "--create-namespace",
-                "--values",
-                &values_path,
+                "template", &release, ".",
+                "--namespace", &namespace,
+                "--values", &values_path,
+                "--include-crds",
             ],
             &helm_dir,
+        ) {
+            Ok(raw) => {
+                let raw = pin_manifest_images(&raw, &pins);
+                let rendered_dir = dir.join("rendered");
+                let _ = fs::create_dir_all(&rendered_dir);
+                // Clear old rendered files
+                if let Ok(entries) = fs::read_dir(&rendered_dir) {
+                    for entry in entries.flatten() {
+                        let p = entry.path();
+                        if p.file_name().and_then(|n| n.to_str()) != Some(".gitkeep") {
+                            let _ = fs::remove_file(&p);
+                        }
+                    }
+                }
+                for (filename, content) in split_rendered_manifests(&raw) {
+                    let _ = fs::write(rendered_dir.join(&filename), content);
+                }
+            }
+            Err(e) => {
+                // Non-fatal — warn but continue to install
+                eprintln!("helm template warning: {}", e);
+            }
+        }
+
+        // Step 4: helm upgrade --install (no --wait — returns immediately, cluster deploys async)
+        let install_cmd = format!(
+            "helm upgrade --install {} . --namespace {} --create-namespace --values {} --atomic=false{}",
+            release, namespace, values_path,
+            if pins.is_empty() {
+                String::new()
+            } else {
+                format!(" --set-json …  # {} image(s) pinned to digests", pins.len())
+            }
         );
+        commands_run.push(install_cmd);
+        let mut args = vec![
+            "upgrade", "--install", &release, ".",
+            "--namespace", &namespace,
+            "--create-namespace",
+            "--atomic=false",
+        ];
+        args.extend(overrides.iter().map(|a| a.as_str()));
+        let (stdout, stderr, success) = run_helm_with_values(&args, &values_path, &helm_dir, project);
+
+        DeployResult {
+            resource_id,
+            namespace,
+            source,
+            stdout,
+            stderr,
+            success,
+            commands_run,
+            readiness: None,
+        }
+    } else {
+        // Raw YAML — apply entire directory.
+        // Encrypted Secrets are decrypted in memory and the whole dir is piped via stdin.
+        let dir_str = dir.to_string_lossy().to_string();
+        if let Some(mode) = pin_images {
+            let pinned = manifest_stream(dir, project).and_then(|(stream, _)| {
+                let pins = resolve_image_pins(&stream, &namespace, project)?;
+                Ok((pin_manifest_images(&stream, &pins), pins))
+            });
+            let (stdout, stderr, success) = match pinned {
+                Ok((stream, pins)) => {
+                    commands_run.push(format!("kubectl apply -f -  # {} image(s) pinned to digests", pins.len()));
+                    let (stdout, mut stderr, success) = run_kubectl_stdin(&["apply", "-f", "-"], &stream);
+                    // the project files only change once the pinned images are deployed
+                    if success && mode == "files" {
+                        match pin_manifest_files(dir, &pins) {
+                            Ok(files) => commands_run.extend(files.iter().map(|f| format!("pin images in {}", f))),
+                            Err(e) => stderr.push_str(&format!("\nDeployed, but cannot pin images in the project files: {}", e)),
+                        }
+                    }
+                    (stdout, stderr, success)
+                }
+                Err(e) => (String::new(), e, false),
+            };
+            return DeployResult {
+                resource_id,
+                namespace,
+                source,
+                stdout,
+                stderr,
+                success,
+                commands_run,
+                readiness: None,
+            };
+        }
+        let (stdout, stderr, success) = match decrypted_manifest_stream(dir, project) {
+            Ok(Some(stream)) => {
+                commands_run.push(format!("sops --decrypt <secrets in {}>", dir.display()));
+                commands_run.push("kubectl apply -f -".to_string());
+                run_kubectl_stdin(&["apply", "-f", "-"], &stream)
+            }
+            Ok(None) => {
+                let apply_cmd = format!("kubectl apply -f {} --recursive", dir.display());
+                commands_run.push(apply_cmd);
+                run_kubectl_output(&["apply", "-f", &dir_str, "--recursive"])
+            }
+            Err(e) => (String::new(), e, false),
+        };
+
+        DeployResult {
+            resource_id,
+            namespace,
+            source,
+            stdout,
+            stderr,
+            success,
+            commands_run,
+            readiness: None,
+        }
+    }
+}
+
+/// Pull the rendered objects out of `helm upgrade --dry-run` output: the
+/// section after `MANIFEST:` up to `NOTES:` (hooks are listed before it).
+fn helm_dry_run_manifest(output: &str) -> String {
+    let mut in_manifest = false;
+    let mut manifest = String::new();
+    for line in output.lines() {
+        if line.starts_with("MANIFEST:") {
+            in_manifest = true;
+            continue;
+        }
+        if line.starts_with("NOTES:") {
+            break;
+        }
+        if in_manifest {
+            manifest.push_str(line);
+            manifest.push('\n');
+        }
+    }
+    manifest
+}
+
+/// Render a chart dir with `helm upgrade --install --dry-run` and send the
+/// manifest through `kubectl apply --dry-run=server`, so admission webhooks
+/// get a say. Returns kubectl's (stdout, stderr); None when helm failed, with
+/// `result.error` set.
+fn helm_release_dry_run(
+    helm_dir: &Path,
+    release: &str,
+    namespace: &str,
+    values_path: &str,
+    config: &ProjectConfig,
+    result: &mut DryRunResult,
+) -> Option<(String, String)> {
+    result.commands_run.push(format!("helm dependency update {}", helm_dir.display()));
+    if let Err(e) = run_helm(&["dependency", "update", "."], helm_dir) {
+        result.error = Some(format!("helm dependency update failed: {}", e.trim()));
+        return None;
+    }
+
+    result.commands_run.push(format!(
+        "helm upgrade --install {} . --namespace {} --values {} --dry-run",
+        release, namespace, values_path
+    ));
+    let (out, err, success) = run_helm_with_values(
+        &["upgrade", "--install", release, ".", "--namespace", namespace, "--dry-run"],
+        values_path,
+        helm_dir,
+        config,
+    );
+    if !success {
+        result.error = Some(format!("helm upgrade --dry-run failed: {}", err.trim()));
+        return None;
+    }
+    let rendered = helm_dry_run_manifest(&out);
+
+    result.commands_run.push(format!(
+        "kubectl apply --dry-run=server --namespace {} -f -",
+        namespace
+    ));
+    let (out, err, _) = run_kubectl_stdin(
+        &["apply", "--dry-run=server", "--namespace", namespace, "-f", "-"],
+        &rendered,
+    );
+    Some((out, err))
+}
+
+/// Warn (and count the namespace as created) when it doesn't exist yet.
+fn dry_run_missing_namespace(namespace: &str, result: &mut DryRunResult) {
+    if run_kubectl(&["get", "namespace", namespace]).is_err() {
+        result.created.push(format!("namespace/{}", namespace));
+        result.warnings.push(format!(
+            "Namespace {} does not exist yet — namespaced objects cannot be validated until it is created",
+            namespace
+        ));
+    }
+}
+
+/// Preview `deploy_resource` without changing the cluster.
+///
+/// For source="helm": `helm upgrade --install --dry-run` renders the release,
+/// then the manifest is sent through `kubectl apply --dry-run=server` so
+/// admission webhooks get a say.
+/// For source="raw": `kubectl apply -f <dir> --recursive --dry-run=server`.
+///
+/// Unlike the real deploy this does not create the namespace or touch rendered/.
+#[tauri::command]
+async fn deploy_resource_dry_run(
+    app: tauri::AppHandle,
+    resource_id: String,
+    source: String,
+    resource_dir: String,
+    namespace: String,
+    helm_release: Option<String>,
+    helm_repo_name: Option<String>,
+    helm_repo_url: Option<String>,
+    values_file: Option<String>,
+) -> DryRunResult {
+    let roots = app.state::<ProjectRootState>();
+    let confined = confine_subpath(&roots, &resource_dir).and_then(|dir| {
+        let values = values_file
+            .as_deref()
+            .map(|v| confine_path(&roots, v))
+            .transpose()?;
+        Ok((dir, values))
     });
-    Ok("started".to_string())
+    let (dir, values_file) = match confined {
+        Ok(c) => c,
+        Err(e) => return DryRunResult { error: Some(e.to_string()), ..Default::default() },
+    };
+    let project = match active_project_config(&roots) {
+        Ok(p) => p,
+        Err(e) => return DryRunResult { error: Some(e), ..Default::default() },
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut result = DryRunResult::default();
+        dry_run_missing_namespace(&namespace, &mut result);
+
+        let (stdout, stderr) = if source == "helm" {
+            let helm_dir = dir.join("helm");
+            let release = helm_release.unwrap_or_else(|| resource_id.clone());
+            let values_path = values_file
+                .map(|v| v.to_string_lossy().to_string())
+                .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+
+            if let (Some(repo_name), Some(repo_url)) = (&helm_repo_name, &helm_repo_url) {
+                result.commands_run.push(format!("helm repo add {} {}", repo_name, repo_url));
+                let _ = run_helm(&["repo", "add", repo_name, repo_url], &dir);
+            }
+            match helm_release_dry_run(&helm_dir, &release, &namespace, &values_path, &project, &mut result) {
+                Some(output) => output,
+                None => return result,
+            }
+        } else {
+            let dir_str = dir.to_string_lossy().to_string();
+            match decrypted_manifest_stream(&dir, &project) {
+                Ok(Some(stream)) => {
+                    result.commands_run.push(format!("sops --decrypt <secrets in {}>", dir_str));
+                    result.commands_run.push("kubectl apply -f - --dry-run=server".to_string());
+                    let (out, err, _) = run_kubectl_stdin(&["apply", "-f", "-", "--dry-run=server"], &stream);
+                    (out, err)
+                }
+                Ok(None) => {
+                    result.commands_run.push(format!(
+                        "kubectl apply -f {} --recursive --dry-run=server",
+                        dir_str
+                    ));
+                    let (out, err, _) = run_kubectl_output(&[
+                        "apply", "-f", &dir_str, "--recursive", "--dry-run=server",
+                    ]);
+                    (out, err)
+                }
+                Err(e) => {
+                    result.error = Some(e);
+                    return result;
+                }
+            }
+        };
+
+        collect_dry_run_output(&stdout, &stderr, &mut result);
+        result.success = result.rejected.is_empty();
+        result
+    }).await.unwrap_or_else(|e| DryRunResult {
+        error: Some(format!("spawn error: {}", e)),
+        ..Default::default()
+    })
+}
+
+// ─── NEW: Delete Resource ─────────────────────────────────────────────────────
+
+fn remove_resource_key(source: &str, resource_dir: &str, namespace: &str, release: &str) -> String {
+    format!("remove_resource|{}|{}|{}|{}", source, resource_dir, namespace, release)
+}
+
+/// Preview what `remove_resource` would delete. For protected targets the
+/// returned token must be passed to `remove_resource`.
+#[tauri::command]
+fn plan_remove_resource(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    resource_id: String,
+    source: String,
+    resource_dir: String,
+    namespace: String,
+    helm_release: Option<String>,
+) -> Result<DestructivePlan, String> {
+    let resource_dir = confine_subpath(&roots, &resource_dir)?.to_string_lossy().to_string();
+    let config = active_project_config(&roots)?;
+    let release = helm_release.unwrap_or(resource_id);
+    let affected = affected_by_resource(&source, &resource_dir, &namespace, &release)?;
+    Ok(plan_destructive(
+        &confirmations,
+        &config,
+        "remove_resource",
+        remove_resource_key(&source, &resource_dir, &namespace, &release),
+        &namespace,
+        affected,
+    ))
+}
+
+/// Remove a resource from the cluster.
+/// For helm — runs helm uninstall.
+/// For raw — runs kubectl delete -f <dir>.
+/// Does NOT remove files from disk.
+/// Protected contexts/namespaces require a token from plan_remove_resource.
+#[tauri::command]
+async fn remove_resource(
+    app: tauri::AppHandle,
+    resource_id: String,
+    source: String,
+    resource_dir: String,
+    namespace: String,
+    helm_release: Option<String>,
+    confirmation_token: Option<String>,
+) -> DeployResult {
+    let roots = app.state::<ProjectRootState>();
+    let resource_dir = match confine_subpath(&roots, &resource_dir) {
+        Ok(dir) => dir.to_string_lossy().to_string(),
+        Err(e) => return DeployResult {
+            resource_id, namespace, source, stdout: String::new(),
+            stderr: e.to_string(), success: false, commands_run: vec![],
+            readiness: None,
+        },
+    };
+    let config = active_project_config(&roots);
+    let root = project_root(&roots).ok();
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let release = helm_release.clone().unwrap_or_else(|| resource_id.clone());
+        let gate = config.and_then(|config| {
+            check_confirmation(
+                &app_handle.state::<ConfirmationState>(),
+                &config,
+                &remove_resource_key(&source, &resource_dir, &namespace, &release),
+                &namespace,
+                confirmation_token.as_deref(),
+                || affected_by_resource(&source, &resource_dir, &namespace, &release),
+            )
+        });
+        if let Err(e) = gate {
+            return DeployResult {
+                resource_id, namespace, source, stdout: String::new(),
+                stderr: e, success: false, commands_run: vec![],
+                readiness: None,
+            };
+        }
+        let result = remove_resource_inner(resource_id, source, resource_dir, namespace, helm_release);
+        record_deploy_audit(root.as_deref(), "remove_resource", &result);
+        result
+    }).await.unwrap_or_else(|e| DeployResult {
+        resource_id: String::new(), namespace: String::new(),
+        source: String::new(), stdout: String::new(),
+        stderr: format!("spawn error: {}", e),
+        success: false, commands_run: vec![],
+        readiness: None,
+    })
+}
+
+fn remove_resource_inner(
+    resource_id: String,
+    source: String,
+    resource_dir: String,
+    namespace: String,
+    helm_release: Option<String>,
+) -> DeployResult {
+    let dir = Path::new(&resource_dir);
+    let mut commands_run: Vec<String> = Vec::new();
+
+    if source == "helm" {
+        let release = helm_release.unwrap_or_else(|| resource_id.clone());
+        let cmd = format!(
+            "helm uninstall {} --namespace {} --ignore-not-found",
+            release, namespace
+        );
+        commands_run.push(cmd);
+        let (stdout, stderr, success) = run_helm_output(
+            &["uninstall", &release, "--namespace", &namespace, "--ignore-not-found"],
+            dir,
+        );
+        DeployResult { resource_id, namespace, source, stdout, stderr, success, commands_run, readiness: None }
+    } else {
+        let dir_str = dir.to_string_lossy().to_string();
+        let cmd = format!("kubectl delete -f {} --recursive --ignore-not-found=true", dir_str);
+        commands_run.push(cmd);
+        let (stdout, stderr, success) = run_kubectl_output(&[
+            "delete", "-f", &dir_str, "--recursive", "--ignore-not-found=true", "--wait=false",
+        ]);
+        DeployResult { resource_id, namespace, source, stdout, stderr, success, commands_run, readiness: None }
+    }
+}
+
+// ─── NEW: Diff Resource ───────────────────────────────────────────────────────
+
+/// Show what would change if we applied the local YAML vs the live cluster state.
+/// Uses `kubectl diff -f <dir>` which requires a cluster connection.
+/// For Helm, diffs using `helm diff upgrade` (requires helm-diff plugin).
+#[tauri::command]
+fn diff_resource(
+    roots: tauri::State<ProjectRootState>,
+    resource_id: String,
+    source: String,
+    resource_dir: String,
+    namespace: String,
+    helm_release: Option<String>,
+    values_file: Option<String>,
+) -> DiffResult {
+    let dir = match confine_subpath(&roots, &resource_dir) {
+        Ok(d) => d,
+        Err(e) => {
+            return DiffResult {
+                resource_id,
+                diff: String::new(),
+                has_changes: false,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    let values_file = match values_file.map(|v| confine_path(&roots, &v)).transpose() {
+        Ok(v) => v.map(|p| p.to_string_lossy().to_string()),
+        Err(e) => {
+            return DiffResult {
+                resource_id,
+                diff: String::new(),
+                has_changes: false,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    if source == "helm" {
+        let helm_dir = dir.join("helm");
+        let release = helm_release.unwrap_or_else(|| resource_id.clone());
+        let values_path = values_file.unwrap_or_else(|| {
+            helm_dir.join("values.yaml").to_string_lossy().to_string()
+        });
+
+        // Try helm diff upgrade (requires helm-diff plugin)
+        let (stdout, stderr, success) = run_helm_output(
+            &[
+                "diff", "upgrade", &release, ".",
+                "--namespace", &namespace,
+                "--values", &values_path,
+                "--allow-unreleased",
+            ],
+            &helm_dir,
+        );
+
+        if success || !stdout.is_empty() {
+            return DiffResult {
+                resource_id,
+                diff: stdout,
+                has_changes: true,
+                error: None,
+            };
+        }
+
+        // Fallback: diff using rendered/ directory
+        let rendered_dir = dir.join("rendered");
+        if rendered_dir.exists() {
+            let rendered_str = rendered_dir.to_string_lossy().to_string();
+            let (diff_out, diff_err, _) = run_kubectl_output(&[
+                "diff", "-f", &rendered_str,
+            ]);
+            return DiffResult {
+                resource_id,
+                has_changes: !diff_out.is_empty(),
+                diff: if diff_out.is_empty() { diff_err } else { diff_out },
+                error: if stderr.is_empty() { None } else { Some(stderr) },
+            };
+        }
+
+        DiffResult {
+            resource_id,
+            diff: String::new(),
+            has_changes: false,
+            error: Some(format!("helm diff failed and no rendered/ dir: {}", stderr)),
+        }
+    } else {
+        let dir_str = dir.to_string_lossy().to_string();
+        let stream = active_project_config(&roots)
+            .and_then(|project| decrypted_manifest_stream(&dir, &project));
+        let (stdout, stderr, _exit) = match stream {
+            Ok(Some(stream)) => run_kubectl_stdin(&["diff", "-f", "-"], &stream),
+            Ok(None) => run_kubectl_output(&["diff", "-f", &dir_str, "--recursive"]),
+            Err(e) => (String::new(), e, false),
+        };
+        // kubectl diff exits 1 when there ARE differences — that's not an error
+        let has_changes = !stdout.is_empty();
+        let error = if !has_changes && !stderr.is_empty() {
+            Some(stderr)
+        } else {
+            None
+        };
+        DiffResult {
+            resource_id,
+            diff: stdout,
+            has_changes,
+            error,
+        }
+    }
+}
+
+// ─── NEW: Get Logs ────────────────────────────────────────────────────────────
+
+/// Get logs for a field. Tries to find a running pod by label app=<field_id>
+/// and returns recent logs.
+#[tauri::command]
+fn get_field_logs(
+    field_id: String,
+    namespace: String,
+    tail: u32,
+    previous: bool,
+) -> Result<String, String> {
+    // List pods matching label
+    let pods_raw = run_kubectl(&[
+        "get", "pods",
+        "-n", &namespace,
+        "-l", &format!("app={}", field_id),
+        "--no-headers",
+        "-o", "custom-columns=NAME:.metadata.name,STATUS:.status.phase",
+    ])?;
+
+    let pod_name = pods_raw
+        .lines()
+        .find_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 && parts[1] == "Running" {
+                Some(parts[0].to_string())
+            } else if parts.len() >= 1 {
+                Some(parts[0].to_string()) // fallback: first pod
+            } else {
+                None
+            }
+        })
+        .ok_or_else(|| format!("No pods found for app={} in {}", field_id, namespace))?;
+
+    let tail_str = tail.to_string();
+    let tail_arg = format!("--tail={}", tail_str);
+    let mut args = vec![
+        "logs", "-n", &namespace, &pod_name,
+        &tail_arg,
+    ];
+    if previous {
+        args.push("--previous");
+    }
+
+    run_kubectl(&args)
+}
+
+// ─── Rollouts ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RolloutStatus {
+    pub kind: String,
+    pub name: String,
+    pub namespace: String,
+    pub desired: u32,
+    pub updated: u32,
+    pub ready: u32,
+    pub available: u32,
+    /// "progressing" | "complete" | "failed" | "paused" | "untracked"
+    /// (OnDelete update strategy — pods only change when deleted, so there
+    /// is no rollout to follow)
+    pub state: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloutRevision {
+    pub revision: u64,
+    /// `kubernetes.io/change-cause` annotation, if it was set
+    pub change_cause: Option<String>,
+    pub images: Vec<String>,
+    pub created: Option<String>,
+    /// The revision the workload currently runs
+    pub current: bool,
+}
+
+fn rollout_kind(kind: &str) -> Result<&'static str, String> {
+    match kind.to_lowercase().as_str() {
+        "deployment" | "deploy" => Ok("Deployment"),
+        "statefulset" | "sts" => Ok("StatefulSet"),
+        "daemonset" | "ds" => Ok("DaemonSet"),
+        _ => Err(format!(
+            "Rollouts only exist for Deployments, StatefulSets and DaemonSets, not {}",
+            kind
+        )),
+    }
+}
+
+/// One snapshot of a rollout, judged the way `kubectl rollout status` does:
+/// the controller must have seen the latest generation, every replica must
+/// be updated and available, and no pods of the old revision may be left.
+/// Only Deployments can fail on their own (progressDeadlineSeconds), and
+/// StatefulSets/DaemonSets with the OnDelete strategy are "untracked". A
+/// StatefulSet with a `partition` is complete once the pods from the
+/// partition up are updated.
+fn rollout_status(namespace: &str, kind: &str, name: &str) -> Result<RolloutStatus, String> {
+    let kind = rollout_kind(kind)?;
+    let (desired, updated, ready, available, extra) = match kind {
+        "Deployment" => (
+            ".spec.replicas", ".status.updatedReplicas", ".status.readyReplicas", ".status.availableReplicas",
+            "TOTAL:.status.replicas,PAUSED:.spec.paused,\
+REASON:.status.conditions[?(@.type==\"Progressing\")].reason",
+        ),
+        "StatefulSet" => (
+            ".spec.replicas", ".status.updatedReplicas", ".status.readyReplicas", ".status.availableReplicas",
+            "STRATEGY:.spec.updateStrategy.type,CURRENT:.status.currentRevision,UPDATE:.status.updateRevision,\
+PARTITION:.spec.updateStrategy.rollingUpdate.partition",
+        ),
+        _ => (
+            ".status.desiredNumberScheduled", ".status.updatedNumberScheduled",
+            ".status.numberReady", ".status.numberAvailable",
+            "STRATEGY:.spec.updateStrategy.type",
+        ),
+    };
+    let columns = format!(
+        "custom-columns=GEN:.metadata.generation,OBSERVED:.status.observedGeneration,\
+DESIRED:{},UPDATED:{},READY:{},AVAILABLE:{},{}",
+        desired, updated, ready, available, extra
+    );
+    let raw = run_kubectl(&[
+        "get", &kind.to_lowercase(), name, "-n", namespace, "--no-headers", "-o", &columns,
+    ])?;
+    let p: Vec<&str> = raw.split_whitespace().collect();
+    if p.len() < 7 {
+        return Err(format!("Unexpected kubectl output for {}/{}: {}", kind, name, raw.trim()));
+    }
+    let count = |s: &str| s.parse::<u32>().unwrap_or(0);
+    let (generation, observed) = (count(p[0]), count(p[1]));
+    let (desired, updated, ready, available) = (count(p[2]), count(p[3]), count(p[4]), count(p[5]));
+    // a partitioned StatefulSet only updates the pods with ordinal >= partition;
+    // the rest stay on the old revision by design
+    let partition = match kind {
+        "StatefulSet" if p.len() >= 10 => count(p[9]).min(desired),
+        _ => 0,
+    };
+    let to_update = desired - partition;
+    let (old_pods_left, paused, deadline_exceeded) = match kind {
+        "Deployment" if p.len() >= 9 => (count(p[6]) > updated, p[7] == "true", p[8] == "ProgressDeadlineExceeded"),
+        "StatefulSet" if p.len() >= 9 => (partition == 0 && p[7] != p[8], false, false),
+        _ => (false, false, false),
+    };
+    let on_delete = kind != "Deployment" && p[6] == "OnDelete";
+
+    let (state, message) = if on_delete {
+        (
+            "untracked",
+            format!("{} {} uses the OnDelete strategy — rollout status is only available for RollingUpdate", kind, name),
+        )
+    } else if observed < generation {
+        ("progressing", "Waiting for the controller to pick up the update".to_string())
+    } else if deadline_exceeded {
+        ("failed", format!("{} {} exceeded its progress deadline", kind, name))
+    } else if paused {
+        ("paused", "Rollout is paused".to_string())
+    } else if updated < to_update {
+        ("progressing", format!("{} of {} replicas updated", updated, to_update))
+    } else if old_pods_left {
+        ("progressing", "Waiting for pods of the old revision to terminate".to_string())
+    } else if available < desired {
+        ("progressing", format!("{} of {} updated replicas available", available, desired))
+    } else if partition > 0 {
+        ("complete", format!("Partitioned rollout complete: {} of {} replicas updated", updated, desired))
+    } else {
+        ("complete", "Rollout complete".to_string())
+    };
+    Ok(RolloutStatus {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        desired,
+        updated,
+        ready,
+        available,
+        state: state.to_string(),
+        message,
+    })
+}
+
+#[tauri::command]
+fn get_rollout_status(namespace: String, kind: String, name: String) -> Result<RolloutStatus, String> {
+    rollout_status(&namespace, &kind, &name)
+}
+
+/// Poll a rollout until it completes, fails or pauses, emitting a
+/// `rollout-progress` event whenever the status changes. Gives up with a
+/// "failed" status after `timeout_secs` (default 600 — the default
+/// progress deadline of a Deployment).
+#[tauri::command]
+async fn watch_rollout(
+    app: tauri::AppHandle,
+    namespace: String,
+    kind: String,
+    name: String,
+    timeout_secs: Option<u64>,
+) -> Result<RolloutStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let timeout = timeout_secs.unwrap_or(600);
+        let deadline = Instant::now() + Duration::from_secs(timeout);
+        let mut last: Option<RolloutStatus> = None;
+        loop {
+            let status = rollout_status(&namespace, &kind, &name)?;
+            if last.as_ref() != Some(&status) {
+                let _ = app.emit("rollout-progress", &status);
+            }
+            if status.state != "progressing" {
+                return Ok(status);
+            }
+            if Instant::now() >= deadline {
+                let timed_out = RolloutStatus {
+                    state: "failed".to_string(),
+                    message: format!("Rollout not complete after {}s — {}", timeout, status.message),
+                    ..status
+                };
+                let _ = app.emit("rollout-progress", &timed_out);
+                return Ok(timed_out);
+            }
+            last = Some(status);
+            std::thread::sleep(Duration::from_secs(2));
+        }
+    })
+    .await
+    .map_err(|e| format!("spawn error: {}", e))?
+}
+
+/// Revisions of a workload, newest first. Deployments keep them as
+/// ReplicaSets, StatefulSets and DaemonSets as ControllerRevisions; the
+/// images come from each revision's pod template.
+#[tauri::command]
+fn get_rollout_history(namespace: String, kind: String, name: String) -> Result<Vec<RolloutRevision>, String> {
+    let kind = rollout_kind(&kind)?;
+    let (resource, revision, template) = if kind == "Deployment" {
+        ("replicasets", ".metadata.annotations.deployment\\.kubernetes\\.io/revision", ".spec.template")
+    } else {
+        ("controllerrevisions", ".revision", ".data.spec.template")
+    };
+    // change-cause may contain spaces, so it goes last
+    let columns = format!(
+        "custom-columns=REVISION:{},OWNER_KIND:.metadata.ownerReferences[0].kind,\
+OWNER:.metadata.ownerReferences[0].name,IMAGES:{}.spec.containers[*].image,\
+CREATED:.metadata.creationTimestamp,CAUSE:.metadata.annotations.kubernetes\\.io/change-cause",
+        revision, template
+    );
+    let raw = run_kubectl(&["get", resource, "-n", &namespace, "--no-headers", "-o", &columns])?;
+    let value = |s: &str| (s != "<none>" && !s.is_empty()).then(|| s.to_string());
+
+    let mut revisions: Vec<RolloutRevision> = raw
+        .lines()
+        .filter_map(|line| {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            if p.len() < 6 || p[1] != kind || p[2] != name {
+                return None;
+            }
+            Some(RolloutRevision {
+                revision: p[0].parse().ok()?,
+                change_cause: value(&p[5..].join(" ")),
+                images: value(p[3])
+                    .map(|images| images.split(',').map(String::from).collect())
+                    .unwrap_or_default(),
+                created: value(p[4]),
+                current: false,
+            })
+        })
+        .collect();
+    revisions.sort_by_key(|r| std::cmp::Reverse(r.revision));
+    if let Some(latest) = revisions.first_mut() {
+        latest.current = true;
+    }
+    Ok(revisions)
+}
+
+/// `kubectl rollout <action> <kind>/<name>`, audited.
+fn run_rollout_action(
+    roots: &ProjectRootState,
+    action: &str,
+    namespace: &str,
+    kind: &str,
+    name: &str,
+    extra: &[&str],
+) -> Result<String, String> {
+    let kind = rollout_kind(kind)?;
+    let target = format!("{}/{}", kind.to_lowercase(), name);
+    let mut args = vec!["rollout", action, target.as_str(), "-n", namespace];
+    args.extend_from_slice(extra);
+    let result = run_kubectl(&args);
+    record_result_audit(
+        project_root(roots).ok().as_deref(),
+        &format!("rollout_{}", action),
+        namespace,
+        &target,
+        format!("kubectl {}", args.join(" ")),
+        &result,
+    );
+    result
+}
+
+fn rollout_action_key(action: &str, namespace: &str, kind: &str, name: &str, revision: Option<u64>) -> String {
+    format!("rollout_{}|{}|{}/{}|{:?}", action, namespace, kind.to_lowercase(), name, revision)
+}
+
+/// Preview a `restart` or `undo` rollout action (`revision` only applies to
+/// undo). For protected targets the token goes to `restart_rollout` /
+/// `undo_rollout`.
+#[tauri::command]
+fn plan_rollout_action(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    action: String,
+    namespace: String,
+    kind: String,
+    name: String,
+    revision: Option<u64>,
+) -> Result<DestructivePlan, String> {
+    if action != "restart" && action != "undo" {
+        return Err(format!("Unknown rollout action: {}", action));
+    }
+    let kind = rollout_kind(&kind)?;
+    Ok(plan_destructive(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &format!("rollout_{}", action),
+        rollout_action_key(&action, &namespace, kind, &name, revision),
+        &namespace,
+        affected_object(kind, &name, &namespace)?,
+    ))
+}
+
+/// Restart all pods of a workload with a rolling update — e.g. to pick up
+/// changed ConfigMaps or Secrets mounted without a checksum annotation.
+/// Protected contexts/namespaces require a token from plan_rollout_action.
+#[tauri::command]
+fn restart_rollout(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    namespace: String,
+    kind: String,
+    name: String,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    let workload_kind = rollout_kind(&kind)?;
+    check_confirmation(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &rollout_action_key("restart", &namespace, workload_kind, &name, None),
+        &namespace,
+        confirmation_token.as_deref(),
+        || affected_object(workload_kind, &name, &namespace),
+    )?;
+    run_rollout_action(&roots, "restart", &namespace, &kind, &name, &[])
+}
+
+/// Pause or resume a Deployment's rollout; changes made while paused roll
+/// out together on resume. Only Deployments support pausing.
+#[tauri::command]
+fn set_rollout_paused(
+    roots: tauri::State<ProjectRootState>,
+    namespace: String,
+    kind: String,
+    name: String,
+    paused: bool,
+) -> Result<String, String> {
+    if rollout_kind(&kind)? != "Deployment" {
+        return Err(format!("Only Deployments can be paused, not {}", kind));
+    }
+    let action = if paused { "pause" } else { "resume" };
+    run_rollout_action(&roots, action, &namespace, &kind, &name, &[])
+}
+
+/// Roll back to `revision`, or to the previous one when None.
+/// Protected contexts/namespaces require a token from plan_rollout_action.
+#[tauri::command]
+fn undo_rollout(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    namespace: String,
+    kind: String,
+    name: String,
+    revision: Option<u64>,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    let workload_kind = rollout_kind(&kind)?;
+    check_confirmation(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &rollout_action_key("undo", &namespace, workload_kind, &name, revision),
+        &namespace,
+        confirmation_token.as_deref(),
+        || affected_object(workload_kind, &name, &namespace),
+    )?;
+    let to_revision = revision.map(|r| format!("--to-revision={}", r));
+    let extra: Vec<&str> = to_revision.iter().map(String::as_str).collect();
+    run_rollout_action(&roots, "undo", &namespace, &kind, &name, &extra)
+}
+
+// ── Wait for ready ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub waited_secs: u64,
+    pub workloads: Vec<WorkloadReadiness>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkloadReadiness {
+    pub status: RolloutStatus,
+    /// Warning events of the workload and its ReplicaSets (failed pod
+    /// creation: quota, missing ServiceAccount, admission) — only when not ready
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Pods that aren't ready — only when the workload isn't
+    #[serde(default)]
+    pub pods: Vec<PodDiagnosis>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PodDiagnosis {
+    pub name: String,
+    pub phase: String,
+    /// Why containers are waiting now, e.g. "api: CrashLoopBackOff"
+    pub waiting_reasons: Vec<String>,
+    /// How containers last terminated, e.g. "api: OOMKilled (exit 137)"
+    pub last_terminations: Vec<String>,
+    pub restarts: u32,
+    /// Warning events of the pod, "Reason (xN): message"
+    pub events: Vec<String>,
+    /// Logs of the previous (crashed) containers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_logs: Option<String>,
+}
+
+/// Waiting reasons a pod doesn't get out of without a change to the
+/// manifest, image or cluster — no point waiting for the progress deadline.
+const FATAL_WAITING_REASONS: &[&str] = &[
+    "CrashLoopBackOff",
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "InvalidImageName",
+    "CreateContainerConfigError",
+    "CreateContainerError",
+];
+
+/// A workload with a rollout to wait for, from the deployed manifests.
+struct TrackedWorkload {
+    kind: String,
+    name: String,
+    namespace: String,
+    pod_labels: Labels,
+}
+
+/// Deployments, StatefulSets and DaemonSets among `docs`. Jobs are left
+/// out — they finish rather than become ready.
+fn tracked_workloads<'a>(docs: impl Iterator<Item = &'a str>, default_namespace: &str) -> Vec<TrackedWorkload> {
+    docs.filter_map(|doc| {
+        let kind = extract_yaml_field(doc, "kind")?;
+        rollout_kind(kind).ok()?;
+        Some(TrackedWorkload {
+            kind: kind.to_string(),
+            name: extract_metadata_field(doc, "name")?.to_string(),
+            namespace: extract_metadata_field(doc, "namespace").unwrap_or(default_namespace).to_string(),
+            pod_labels: extract_block_map(doc, "template", "labels"),
+        })
+    })
+    .collect()
+}
+
+/// Label that marks pods of the workload's newest revision:
+/// `pod-template-hash` of the newest ReplicaSet for Deployments,
+/// `controller-revision-hash` of the newest ControllerRevision otherwise.
+/// `None` if the revisions can't be read.
+fn current_revision_label(w: &TrackedWorkload, selector: &str) -> Option<String> {
+    let kind = rollout_kind(&w.kind).ok()?;
+    let (resource, revision, hash, label) = match kind {
+        "Deployment" => (
+            "replicasets",
+            ".metadata.annotations.deployment\\.kubernetes\\.io/revision",
+            ".metadata.labels.pod-template-hash",
+            "pod-template-hash",
+        ),
+        // StatefulSet pods carry the full revision name (status.updateRevision)
+        "StatefulSet" => ("controllerrevisions", ".revision", ".metadata.name", "controller-revision-hash"),
+        _ => (
+            "controllerrevisions",
+            ".revision",
+            ".metadata.labels.controller-revision-hash",
+            "controller-revision-hash",
+        ),
+    };
+    let columns = format!(
+        "custom-columns=OWNER_KIND:.metadata.ownerReferences[0].kind,OWNER:.metadata.ownerReferences[0].name,\
+REVISION:{},HASH:{}",
+        revision, hash
+    );
+    let raw = run_kubectl(&["get", resource, "-n", &w.namespace, "-l", selector, "--no-headers", "-o", &columns]).ok()?;
+    raw.lines()
+        .filter_map(|line| {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            if p.len() < 4 || p[0] != kind || p[1] != w.name || p[3] == "<none>" {
+                return None;
+            }
+            Some((p[2].parse::<u64>().ok()?, p[3]))
+        })
+        .max_by_key(|(revision, _)| *revision)
+        .map(|(_, hash)| format!("{}={}", label, hash))
+}
+
+/// Pods of the workload's newest revision — pods of the old revision are
+/// on their way out and say nothing about the rollout. Falls back to all
+/// of the workload's pods if the revision can't be determined.
+fn workload_pods(w: &TrackedWorkload) -> Vec<PodInfo> {
+    if w.pod_labels.is_empty() {
+        return vec![];
+    }
+    let mut selector: Vec<String> = w.pod_labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    if let Some(revision) = current_revision_label(w, &selector.join(",")) {
+        selector.push(revision);
+    }
+    run_kubectl(&["get", "pods", "-n", &w.namespace, "-l", &selector.join(","), "-o", "json"])
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|list| list["items"].as_array().map(|items| items.iter().map(|i| parse_pod(i).0).collect()))
+        .unwrap_or_default()
+}
+
+/// Warning events in `namespace`, "Reason (xN): message", for objects
+/// whose name passes `object`. Oldest first, at most the last `limit`.
+fn warning_events(namespace: &str, object: impl Fn(&str) -> bool, limit: usize) -> Vec<String> {
+    let raw = run_kubectl(&[
+        "get", "events", "-n", namespace, "--field-selector", "type=Warning",
+        "--sort-by=.lastTimestamp", "--no-headers",
+        "-o", "custom-columns=OBJECT:.involvedObject.name,REASON:.reason,COUNT:.count,MESSAGE:.message",
+    ])
+    .unwrap_or_default();
+    let events: Vec<String> = raw
+        .lines()
+        .filter_map(|line| {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            if p.len() < 4 || !object(p[0]) {
+                return None;
+            }
+            let count = p[2].parse::<u32>().unwrap_or(1);
+            let times = if count > 1 { format!(" (x{})", count) } else { String::new() };
+            Some(format!("{}{}: {}", p[1], times, p[3..].join(" ")))
+        })
+        .collect();
+    events[events.len().saturating_sub(limit)..].to_vec()
+}
+
+/// Diagnosis of every pod of the workload that isn't fully ready.
+fn diagnose_pods(w: &TrackedWorkload) -> Vec<PodDiagnosis> {
+    workload_pods(w)
+        .into_iter()
+        .filter(|pod| pod.phase != "Succeeded" && pod.ready < pod.total)
+        .map(|pod| {
+            let waiting_reasons = pod
+                .containers
+                .iter()
+                .filter(|c| c.state == "waiting")
+                .filter_map(|c| c.reason.as_ref().map(|r| format!("{}: {}", c.name, r)))
+                .collect();
+            let last_terminations = pod
+                .containers
+                .iter()
+                .filter_map(|c| {
+                    let reason = c.last_termination_reason.as_ref()?;
+                    Some(match c.last_exit_code {
+                        Some(code) => format!("{}: {} (exit {})", c.name, reason, code),
+                        None => format!("{}: {}", c.name, reason),
+                    })
+                })
+                .collect();
+            // Per container: `--all-containers --previous` fails as a whole
+            // as soon as one container has no previous instance
+            let logs: Vec<String> = pod
+                .containers
+                .iter()
+                .filter(|c| c.restarts > 0)
+                .filter_map(|c| {
+                    run_kubectl(&[
+                        "logs", "-n", &w.namespace, &pod.name, "-c", &c.name, "--previous", "--tail=50",
+                    ])
+                    .ok()
+                    .map(|log| format!("==> {} <==\n{}", c.name, log.trim_end()))
+                })
+                .collect();
+            let previous_logs = (!logs.is_empty()).then(|| logs.join("\n\n"));
+            PodDiagnosis {
+                events: warning_events(&w.namespace, |obj| obj == pod.name, 5),
+                waiting_reasons,
+                last_terminations,
+                restarts: pod.containers.iter().map(|c| c.restarts).sum(),
+                previous_logs,
+                name: pod.name,
+                phase: pod.phase,
+            }
+        })
+        .collect()
+}
+
+/// Poll the workloads until all rollouts complete, one fails (progress
+/// deadline or a pod stuck in a fatal waiting reason), or `timeout` passes.
+/// OnDelete workloads have no rollout to wait for and count as settled.
+/// Unfinished workloads get events, pod states and previous logs attached.
+fn wait_for_workloads(workloads: &[TrackedWorkload], timeout: Duration) -> ReadinessReport {
+    let settled = |s: &RolloutStatus| matches!(s.state.as_str(), "complete" | "untracked");
+    let started = Instant::now();
+    let mut statuses: Vec<Option<RolloutStatus>> = workloads.iter().map(|_| None).collect();
+    loop {
+        let mut failed = false;
+        for (w, status) in workloads.iter().zip(statuses.iter_mut()) {
+            if status.as_ref().is_some_and(settled) {
+                continue;
+            }
+            let mut current = rollout_status(&w.namespace, &w.kind, &w.name).unwrap_or_else(|e| RolloutStatus {
+                kind: w.kind.clone(),
+                name: w.name.clone(),
+                namespace: w.namespace.clone(),
+                desired: 0,
+                updated: 0,
+                ready: 0,
+                available: 0,
+                state: "progressing".to_string(),
+                message: e,
+            });
+            if current.state == "progressing" {
+                let stuck = workload_pods(w).into_iter().find_map(|pod| {
+                    pod.containers
+                        .into_iter()
+                        .filter(|c| c.state == "waiting")
+                        .find(|c| c.reason.as_deref().is_some_and(|r| FATAL_WAITING_REASONS.contains(&r)))
+                        .map(|c| (pod.name, c.name, c.reason.unwrap_or_default()))
+                });
+                if let Some((pod, container, reason)) = stuck {
+                    current.state = "failed".to_string();
+                    current.message = format!("Pod {} (container {}) is in {}", pod, container, reason);
+                }
+            }
+            failed |= matches!(current.state.as_str(), "failed" | "paused");
+            *status = Some(current);
+        }
+        let all_complete = statuses.iter().all(|s| s.as_ref().is_some_and(settled));
+        if all_complete || failed || started.elapsed() >= timeout {
+            break;
+        }
+        std::thread::sleep(Duration::from_secs(3));
+    }
+
+    let timed_out = started.elapsed() >= timeout;
+    let workloads: Vec<WorkloadReadiness> = workloads
+        .iter()
+        .zip(statuses)
+        .filter_map(|(w, status)| {
+            let mut status = status?;
+            if settled(&status) {
+                return Some(WorkloadReadiness { status, events: vec![], pods: vec![] });
+            }
+            if timed_out && status.state == "progressing" {
+                status.state = "failed".to_string();
+                status.message = format!("Not ready after {}s — {}", timeout.as_secs(), status.message);
+            }
+            // StatefulSet/DaemonSet pods and Deployment ReplicaSets are named after the workload
+            let prefix = format!("{}-", w.name);
+            Some(WorkloadReadiness {
+                events: warning_events(&w.namespace, |obj| obj == w.name || obj.starts_with(&prefix), 10),
+                pods: diagnose_pods(w),
+                status,
+            })
+        })
+        .collect();
+    ReadinessReport {
+        ready: workloads.iter().all(|w| settled(&w.status)),
+        waited_secs: started.elapsed().as_secs(),
+        workloads,
+    }
+}
+
+/// Post-deploy wait: find the workloads that were just applied (the
+/// directory's manifests, or the Helm release's) and wait for them. A
+/// deploy whose workloads don't become ready is reported as failed.
+fn wait_after_deploy(result: &mut DeployResult, dir: &Path, helm_release: Option<&str>, timeout_secs: u64) {
+    let manifest = match helm_release {
+        Some(release) => {
+            result.commands_run.push(format!("helm get manifest {} --namespace {}", release, result.namespace));
+            match run_helm(&["get", "manifest", release, "--namespace", &result.namespace], dir) {
+                Ok(m) => m,
+                Err(e) => {
+                    result.stderr.push_str(&format!("\nCannot read release manifest to wait for: {}", e));
+                    return;
+                }
+            }
+        }
+        None => project_yaml_docs(dir)
+            .into_iter()
+            .map(|(_, doc)| doc)
+            .collect::<Vec<_>>()
+            .join("\n---\n"),
+    };
+    let workloads = tracked_workloads(manifest.split("\n---"), &result.namespace);
+    let report = wait_for_workloads(&workloads, Duration::from_secs(timeout_secs));
+    if !report.ready {
+        result.success = false;
+        let failed: Vec<String> = report
+            .workloads
+            .iter()
+            .filter(|w| !matches!(w.status.state.as_str(), "complete" | "untracked"))
+            .map(|w| format!("{}/{}: {}", w.status.kind, w.status.name, w.status.message))
+            .collect();
+        result.stderr.push_str(&format!("\nDeployed, but not ready:\n{}", failed.join("\n")));
+    }
+    result.readiness = Some(report);
+}
+
+// ─── Batch jobs (Job / CronJob) ───────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobRun {
+    pub name: String,
+    pub namespace: String,
+    /// Owning CronJob, None for standalone Jobs
+    pub cronjob: Option<String>,
+    /// "Running" | "Succeeded" | "Failed" | "Pending"
+    pub status: String,
+    pub succeeded: u32,
+    pub failed: u32,
+    pub active: u32,
+    pub start_time: Option<String>,
+    pub completion_time: Option<String>,
+    /// Completion − start, or time running so far
+    pub duration_secs: Option<u64>,
+}
+
+/// Seconds since the Unix epoch for a Kubernetes timestamp
+/// (`2024-05-01T12:00:00Z`, always UTC).
+fn parse_k8s_timestamp(ts: &str) -> Option<u64> {
+    let ts = ts.trim().strip_suffix('Z')?;
+    let (date, time) = ts.split_once('T')?;
+    let mut d = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (y, m, day) = (d.next()??, d.next()??, d.next()??);
+    let mut t = time.split(':').map(|p| p.split('.').next().unwrap_or("").parse::<i64>().ok());
+    let (hh, mm, ss) = (t.next()??, t.next()??, t.next()??);
+    // days from civil (Howard Hinnant)
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    u64::try_from(days * 86400 + hh * 3600 + mm * 60 + ss).ok()
+}
+
+fn trigger_cronjob_key(namespace: &str, name: &str) -> String {
+    format!("trigger_cronjob|{}|{}", namespace, name)
+}
+
+/// Preview a manual CronJob run.
+#[tauri::command]
+fn plan_trigger_cronjob(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    namespace: String,
+    name: String,
+) -> Result<DestructivePlan, String> {
+    Ok(plan_destructive(
+        &confirmations,
+        &active_project_config(&roots)?,
+        "trigger_cronjob",
+        trigger_cronjob_key(&namespace, &name),
+        &namespace,
+        affected_object("CronJob", &name, &namespace)?,
+    ))
+}
+
+/// Start a CronJob run now (`kubectl create job --from=cronjob/<name>`).
+/// Returns the name of the created Job.
+/// Protected contexts/namespaces require a token from plan_trigger_cronjob.
+#[tauri::command]
+fn trigger_cronjob(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    namespace: String,
+    name: String,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    check_confirmation(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &trigger_cronjob_key(&namespace, &name),
+        &namespace,
+        confirmation_token.as_deref(),
+        || affected_object("CronJob", &name, &namespace),
+    )?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    // Job names are limited to 63 chars; keep the suffix intact
+    let suffix = format!("-manual-{}", now);
+    let base: String = name.chars().take(63 - suffix.len()).collect();
+    let job_name = format!("{}{}", base.trim_end_matches('-'), suffix);
+    let from = format!("--from=cronjob/{}", name);
+    let result = run_kubectl(&["create", "job", &job_name, &from, "-n", &namespace]);
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "trigger_cronjob",
+        &namespace,
+        &format!("cronjob/{} → job/{}", name, job_name),
+        format!("kubectl create job {} {} -n {}", job_name, from, namespace),
+        &result,
+    );
+    result.map(|_| job_name)
+}
+
+/// Recent Job runs in `namespace`, newest first; only those owned by
+/// `cronjob` when given.
+#[tauri::command]
+fn list_job_runs(namespace: String, cronjob: Option<String>) -> Result<Vec<JobRun>, String> {
+    let raw = run_kubectl(&[
+        "get", "jobs", "-n", &namespace, "--no-headers",
+        "-o", "custom-columns=NAME:.metadata.name,OWNER:.metadata.ownerReferences[0].name,\
+SUCCEEDED:.status.succeeded,FAILED:.status.failed,ACTIVE:.status.active,\
+START:.status.startTime,END:.status.completionTime",
+    ])?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let value = |s: &str| (s != "<none>").then(|| s.to_string());
+    let count = |s: &str| s.parse::<u32>().unwrap_or(0);
+
+    let mut runs: Vec<JobRun> = raw
+        .lines()
+        .filter_map(|line| {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            if p.len() < 7 {
+                return None;
+            }
+            let owner = value(p[1]);
+            if cronjob.is_some() && owner != cronjob {
+                return None;
+            }
+            let (succeeded, failed, active) = (count(p[2]), count(p[3]), count(p[4]));
+            let start_time = value(p[5]);
+            let completion_time = value(p[6]);
+            let status = if completion_time.is_some() {
+                "Succeeded"
+            } else if active > 0 {
+                "Running"
+            } else if failed > 0 {
+                "Failed"
+            } else {
+                "Pending"
+            };
+            let start = start_time.as_deref().and_then(parse_k8s_timestamp);
+            let end = completion_time.as_deref().and_then(parse_k8s_timestamp);
+            let duration_secs = match (start, end) {
+                (Some(s), Some(e)) => Some(e.saturating_sub(s)),
+                (Some(s), None) if active > 0 => Some(now.saturating_sub(s)),
+                _ => None,
+            };
+            Some(JobRun {
+                name: p[0].to_string(),
+                namespace: namespace.clone(),
+                cronjob: owner,
+                status: status.to_string(),
+                succeeded,
+                failed,
+                active,
+                start_time,
+                completion_time,
+                duration_secs,
+            })
+        })
+        .collect();
+    runs.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    Ok(runs)
+}
+
+/// Logs of every pod of one Job run (retries included), oldest first.
+#[tauri::command]
+fn get_job_run_logs(namespace: String, job_name: String, tail: u32) -> Result<String, String> {
+    let pods_raw = run_kubectl(&[
+        "get", "pods", "-n", &namespace,
+        "-l", &format!("job-name={}", job_name),
+        "--sort-by=.metadata.creationTimestamp",
+        "--no-headers",
+        "-o", "custom-columns=NAME:.metadata.name,STATUS:.status.phase",
+    ])?;
+    let pods: Vec<(&str, &str)> = pods_raw
+        .lines()
+        .filter_map(|l| {
+            let mut p = l.split_whitespace();
+            Some((p.next()?, p.next().unwrap_or("")))
+        })
+        .collect();
+    if pods.is_empty() {
+        return Err(format!(
+            "No pods left for job {} in {} (deleted after ttlSecondsAfterFinished?)",
+            job_name, namespace
+        ));
+    }
+    let tail_arg = format!("--tail={}", tail);
+    let sections: Vec<String> = pods
+        .iter()
+        .map(|(pod, phase)| {
+            let logs = run_kubectl(&["logs", "-n", &namespace, pod, "--all-containers", &tail_arg])
+                .unwrap_or_else(|e| format!("(logs unavailable: {})", e.trim()));
+            format!("=== {} ({}) ===\n{}", pod, phase, logs)
+        })
+        .collect();
+    Ok(sections.join("\n"))
+}
+
+// ─── Scan all project files (for Explorer file tree) ─────────────────────────
+
+/// Returns all .yaml/.yml file paths under a directory recursively,
+/// without any kind filtering — used by the Explorer file tree.
+fn scan_all_yaml_paths(dir: &Path, result: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if name.starts_with('.') || name == "node_modules" || name == "vendor" {
+            continue;
+        }
+        if path.is_dir() {
+            scan_all_yaml_paths(&path, result);
+        } else if path.is_file() {
+            result.push(path.to_string_lossy().to_string());
+        }
+    }
+}
+
+#[tauri::command]
+fn scan_project_files(
+    roots: tauri::State<ProjectRootState>,
+    folder_path: String,
+) -> Result<Vec<String>, String> {
+    let folder = confine_path(&roots, &folder_path)?;
+    let mut files = Vec::new();
+    scan_all_yaml_paths(&folder, &mut files);
+    Ok(files)
+}
+
+
+
+/// Native folder picker. The picked folder is remembered as the one path
+/// allowed to replace the active project root.
+#[tauri::command]
+async fn open_folder_dialog(app: tauri::AppHandle) -> Option<String> {
+    let picked = app
+        .dialog()
+        .file()
+        .set_title("Select Kubernetes config folder")
+        .blocking_pick_folder()
+        .map(|p: tauri_plugin_dialog::FilePath| p.to_string())?;
+    *app.state::<ProjectRootState>().picked.lock().unwrap() = Path::new(&picked).canonicalize().ok();
+    Some(picked)
+}
+
+/// Scan a project folder for workloads. The folder becomes the active
+/// project root that all file-touching commands are confined to.
+#[tauri::command]
+fn scan_yaml_files(roots: tauri::State<ProjectRootState>, folder_path: String) -> ScanResult {
+    let path = Path::new(&folder_path);
+    let mut nodes: Vec<YamlNode> = Vec::new();
+    let mut errors = Vec::new();
+
+    if !path.exists() || !path.is_dir() {
+        errors.push(format!("Path does not exist: {}", folder_path));
+        return ScanResult {
+            nodes,
+            project_path: folder_path,
+            errors,
+            warnings: vec![],
+        };
+    }
+
+    if let Err(e) = register_project_root(&roots, &folder_path) {
+        errors.push(e.to_string());
+        return ScanResult {
+            nodes,
+            project_path: folder_path,
+            errors,
+            warnings: vec![],
+        };
+    }
+
+    scan_dir(path, &mut nodes, &mut errors);
+
+    let priority = |kind: &str, source: &str| {
+        if source == "helm" {
+            return 0u32;
+        }
+        match kind {
+            "StatefulSet" => 1,
+            "Deployment" => 2,
+            "DaemonSet" => 3,
+            "ReplicaSet" => 4,
+            "Job" => 5,
+            "CronJob" => 6,
+            "Pod" => 7,
+            _ => 8,
+        }
+    };
+
+    let mut seen: std::collections::HashMap<String, usize> =
+        std::collections::HashMap::new();
+    let mut deduped: Vec<YamlNode> = Vec::new();
+
+    for node in nodes {
+        // For workloads: deduplicate by label+namespace (prefer StatefulSet over Deployment etc.)
+        // For configs/services/etc.: use kind+label+namespace so they always show separately
+        let workload_kinds = ["Deployment", "StatefulSet", "DaemonSet", "ReplicaSet", "Job", "CronJob", "Pod"];
+        let key = if workload_kinds.contains(&node.kind.as_str()) {
+            format!("{}::{}", node.label, node.namespace)
+        } else {
+            format!("{}::{}::{}", node.kind, node.label, node.namespace)
+        };
+
+        if let Some(&existing_idx) = seen.get(&key) {
+            if priority(&node.kind, &node.source)
+                < priority(&deduped[existing_idx].kind, &deduped[existing_idx].source)
+            {
+                deduped[existing_idx] = node;
+            }
+        } else {
+            seen.insert(key, deduped.len());
+            deduped.push(node);
+        }
+    }
+
+    for (i, node) in deduped.iter_mut().enumerate() {
+        node.id = format!("{}-{}", node.id, i);
+    }
+
+    let docs = project_yaml_docs(path);
+    link_secrets(&docs, &mut deduped);
+    link_autoscalers(&docs, &mut deduped);
+    let warnings = link_disruption_budgets(&docs, &mut deduped);
+
+    ScanResult {
+        nodes: deduped,
+        project_path: folder_path,
+        errors,
+        warnings,
+    }
+}
+
+/// Every YAML document in the project as (file, doc), for cross-referencing
+/// objects the workload graph doesn't show (Secrets, HPAs, …).
+fn project_yaml_docs(dir: &Path) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    scan_all_yaml_paths(dir, &mut files);
+    files.retain(|f| f.ends_with(".yaml") || f.ends_with(".yml"));
+    // charts/ holds exported and downloaded charts, not project manifests
+    files.retain(|f| {
+        !Path::new(f).strip_prefix(dir).is_ok_and(|rel| rel.components().any(|c| c.as_os_str() == "charts"))
+    });
+    files
+        .iter()
+        .filter_map(|f| fs::read_to_string(f).ok().map(|c| (f.clone(), c)))
+        .flat_map(|(f, content)| {
+            content
+                .split("\n---")
+                .map(|doc| (f.clone(), doc.trim().to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Resolve each workload's secret references to the project file that
+/// produces the Secret (plain/SOPS Secret, ExternalSecret or SealedSecret)
+/// in the same namespace.
+fn link_secrets(docs: &[(String, String)], nodes: &mut [YamlNode]) {
+    // (namespace, secret name) → (kind, file)
+    let mut producers: std::collections::HashMap<(String, String), (String, String)> =
+        std::collections::HashMap::new();
+    for (file, doc) in docs {
+        let Some(secret_name) = produced_secret_name(doc) else { continue };
+        let kind = extract_yaml_field(doc, "kind").unwrap_or_default().to_string();
+        let ns = extract_metadata_field(doc, "namespace").unwrap_or("default").to_string();
+        // an ExternalSecret/SealedSecret wins over a Secret it generates
+        let entry = producers.entry((ns, secret_name)).or_insert((kind.clone(), file.clone()));
+        if entry.0 == "Secret" && kind != "Secret" {
+            *entry = (kind, file.clone());
+        }
+    }
+
+    for node in nodes.iter_mut() {
+        for secret in node.secrets.iter_mut() {
+            if let Some((kind, file)) = producers.get(&(node.namespace.clone(), secret.name.clone())) {
+                secret.kind = kind.clone();
+                secret.file_path = file.clone();
+            }
+        }
+    }
+}
+
+/// HorizontalPodAutoscaler in `doc`, if it is one.
+fn parse_hpa_doc(doc: &str, file: &str) -> Option<HpaInfo> {
+    if extract_yaml_field(doc, "kind")? != "HorizontalPodAutoscaler" {
+        return None;
+    }
+    let field = |key: &str| {
+        doc.lines()
+            .find_map(|l| l.trim().strip_prefix(key).and_then(|v| v.trim().parse::<u32>().ok()))
+    };
+    Some(HpaInfo {
+        name: extract_metadata_field(doc, "name")?.to_string(),
+        namespace: extract_metadata_field(doc, "namespace").unwrap_or("default").to_string(),
+        target_kind: extract_block_field(doc, "scaleTargetRef", "kind").unwrap_or_default(),
+        target_name: extract_block_field(doc, "scaleTargetRef", "name")?,
+        min_replicas: field("minReplicas:").unwrap_or(1),
+        max_replicas: field("maxReplicas:").unwrap_or(1),
+        file_path: file.to_string(),
+    })
+}
+
+/// Attach the HPA scaling each workload, matched by scaleTargetRef.
+fn link_autoscalers(docs: &[(String, String)], nodes: &mut [YamlNode]) {
+    let hpas: Vec<HpaInfo> = docs
+        .iter()
+        .filter_map(|(file, doc)| parse_hpa_doc(doc, file))
+        .collect();
+    for node in nodes.iter_mut() {
+        node.hpa = hpas
+            .iter()
+            .find(|hpa| {
+                hpa.namespace == node.namespace
+                    && hpa.target_name == node.label
+                    && (hpa.target_kind.is_empty() || hpa.target_kind == node.kind)
+            })
+            .cloned();
+    }
+}
+
+/// Attach the PDB whose selector matches each workload's pod labels, and
+/// return a warning for every multi-replica workload left without one.
+fn link_disruption_budgets(docs: &[(String, String)], nodes: &mut [YamlNode]) -> Vec<String> {
+    // (namespace, name, matchLabels)
+    let pdbs: Vec<(String, String, Labels)> = docs
+        .iter()
+        .filter(|(_, doc)| extract_yaml_field(doc, "kind") == Some("PodDisruptionBudget"))
+        .filter_map(|(_, doc)| {
+            Some((
+                extract_metadata_field(doc, "namespace").unwrap_or("default").to_string(),
+                extract_metadata_field(doc, "name")?.to_string(),
+                extract_block_map(doc, "selector", "matchLabels"),
+            ))
+        })
+        .collect();
+
+    let mut warnings = Vec::new();
+    for node in nodes.iter_mut() {
+        if node.source == "helm" {
+            continue;
+        }
+        let Some((_, doc)) = docs.iter().find(|(file, doc)| {
+            *file == node.file_path
+                && extract_yaml_field(doc, "kind") == Some(node.kind.as_str())
+                && extract_metadata_field(doc, "name") == Some(node.label.as_str())
+        }) else {
+            continue;
+        };
+        let pod_labels = extract_block_map(doc, "template", "labels");
+        node.pdb = pdbs
+            .iter()
+            .find(|(ns, _, selector)| {
+                *ns == node.namespace
+                    && !selector.is_empty()
+                    && selector.iter().all(|l| pod_labels.contains(l))
+            })
+            .map(|(_, name, _)| name.clone());
+
+        let max_replicas = node
+            .hpa
+            .as_ref()
+            .map(|h| h.max_replicas)
+            .unwrap_or(node.replicas.unwrap_or(1));
+        if max_replicas > 1 && node.pdb.is_none() {
+            warnings.push(format!(
+                "{} {}/{} runs up to {} replicas but has no PodDisruptionBudget — a node drain can take all of them down at once",
+                node.kind, node.namespace, node.label, max_replicas
+            ));
+        }
+    }
+    warnings
+}
+
+#[tauri::command]
+fn read_yaml_file(roots: tauri::State<ProjectRootState>, file_path: String) -> Result<String, String> {
+    let path = confine_path(&roots, &file_path)?;
+    fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read {}: {}", file_path, e))
+}
+
+#[tauri::command]
+fn save_yaml_file(
+    roots: tauri::State<ProjectRootState>,
+    file_path: String,
+    content: String,
+) -> Result<(), String> {
+    let path = confine_subpath(&roots, &file_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Cannot create dir {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, content)
+        .map_err(|e| format!("Cannot write {}: {}", file_path, e))
+}
+
+fn delete_field_files_key(file_paths: &[String], namespace: &str) -> String {
+    format!("delete_field_files|{}|{}", namespace, file_paths.join(","))
+}
+
+fn affected_by_files(file_paths: &[String]) -> Result<Vec<String>, String> {
+    let mut affected = Vec::new();
+    for p in file_paths {
+        affected.extend(kubectl_object_names(&[
+            "get", "-f", p, "--recursive", "-o", "name", "--ignore-not-found=true",
+        ])?);
+    }
+    Ok(affected)
+}
+
+/// Preview the cluster objects `delete_field_files` would delete.
+#[tauri::command]
+fn plan_delete_field_files(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    file_paths: Vec<String>,
+    namespace: String,
+) -> Result<DestructivePlan, String> {
+    let file_paths = file_paths
+        .iter()
+        .map(|p| confine_subpath(&roots, p).map(|r| r.to_string_lossy().to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let config = active_project_config(&roots)?;
+    let affected = affected_by_files(&file_paths)?;
+    Ok(plan_destructive(
+        &confirmations,
+        &config,
+        "delete_field_files",
+        delete_field_files_key(&file_paths, &namespace),
+        &namespace,
+        affected,
+    ))
+}
+
+#[tauri::command]
+async fn delete_field_files(
+    app: tauri::AppHandle,
+    file_paths: Vec<String>,
+    namespace: String,
+    confirmation_token: Option<String>,
+) -> DeleteResult {
+    // Resolve everything up front: a single path outside the project
+    // aborts the whole batch before anything is touched.
+    let roots = app.state::<ProjectRootState>();
+    let resolved: Result<Vec<String>, PathGuardError> = file_paths
+        .iter()
+        .map(|p| confine_subpath(&roots, p).map(|r| r.to_string_lossy().to_string()))
+        .collect();
+    let file_paths = match resolved {
+        Ok(paths) => paths,
+        Err(e) => return DeleteResult {
+            deleted_files: vec![],
+            missing_files: vec![],
+            file_errors: vec![e.to_string()],
+            kubectl_output: None,
+            kubectl_error: None,
+            commands_run: vec![],
+        },
+    };
+    let config = active_project_config(&roots);
+    let root = project_root(&roots).ok();
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let gate = config.and_then(|config| {
+            check_confirmation(
+                &app_handle.state::<ConfirmationState>(),
+                &config,
+                &delete_field_files_key(&file_paths, &namespace),
+                &namespace,
+                confirmation_token.as_deref(),
+                || affected_by_files(&file_paths),
+            )
+        });
+        if let Err(e) = gate {
+            return DeleteResult {
+                deleted_files: vec![],
+                missing_files: vec![],
+                file_errors: vec![e],
+                kubectl_output: None,
+                kubectl_error: None,
+                commands_run: vec![],
+            };
+        }
+        let result = delete_field_files_inner(file_paths.clone(), namespace.clone());
+        let output = [
+            result.kubectl_output.clone().unwrap_or_default(),
+            result.kubectl_error.clone().unwrap_or_default(),
+            result.file_errors.join("\n"),
+        ]
+        .join("\n");
+        record_audit(
+            root.as_deref(),
+            "delete_field_files",
+            &namespace,
+            &file_paths.join(", "),
+            &result.commands_run,
+            result.file_errors.is_empty() && result.kubectl_error.is_none(),
+            &output,
+        );
+        result
+    }).await.unwrap_or_else(|e| DeleteResult {
+        deleted_files: vec![],
+        missing_files: vec![],
+        file_errors: vec![format!("spawn error: {}", e)],
+        kubectl_output: None,
+        kubectl_error: None,
+        commands_run: vec![],
+    })
+}
+
+fn delete_field_files_inner(file_paths: Vec<String>, namespace: String) -> DeleteResult {
+    let mut result = DeleteResult {
+        deleted_files: vec![],
+        missing_files: vec![],
+        file_errors: vec![],
+        kubectl_output: None,
+        kubectl_error: None,
+        commands_run: vec![],
+    };
+
+    let mut kubectl_out_lines: Vec<String> = vec![];
+    let mut kubectl_err_lines: Vec<String> = vec![];
+
+    for file_path in &file_paths {
+        let p = Path::new(file_path);
+        if !p.exists() {
+            result.missing_files.push(file_path.clone());
+            continue;
+        }
+
+        // Step 1: kubectl delete from cluster
+        // Directories (helm component dirs or raw dirs) need --recursive
+        let kubectl_args: Vec<&str> = if p.is_dir() {
+            vec!["delete", "-f", file_path, "--recursive", "--ignore-not-found=true", "--wait=false"]
+        } else {
+            vec!["delete", "-f", file_path, "--ignore-not-found=true", "--wait=false"]
+        };
+
+        result.commands_run.push(format!("kubectl {}", kubectl_args.join(" ")));
+        match run_kubectl(&kubectl_args) {
+            Ok(out) => {
+                if !out.trim().is_empty() {
+                    kubectl_out_lines.push(format!("✓ {} → {}", file_path, out.trim()));
+                }
+            }
+            Err(e) => {
+                kubectl_err_lines.push(format!("✗ {} → {}", file_path, e.trim()));
+            }
+        }
+
+        // Step 2: Delete from disk — handle both files and directories
+        let remove_result = if p.is_dir() {
+            result.commands_run.push(format!("remove directory {}", file_path));
+            fs::remove_dir_all(p)
+        } else {
+            result.commands_run.push(format!("remove file {}", file_path));
+            fs::remove_file(p)
+        };
+
+        match remove_result {
+            Ok(_) => {
+                result.deleted_files.push(file_path.clone());
+                // For single files: clean up empty parent dir
+                if p.is_file() {
+                    if let Some(parent) = p.parent() {
+                        if parent.is_dir() {
+                            let is_empty = fs::read_dir(parent)
+                                .map(|mut d| d.next().is_none())
+                                .unwrap_or(false);
+                            if is_empty {
+                                let _ = fs::remove_dir(parent);
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                result.file_errors.push(format!("{}: {}", file_path, e));
+            }
+        }
+    }
+
+    if !kubectl_out_lines.is_empty() {
+        result.kubectl_output = Some(kubectl_out_lines.join("\n"));
+    }
+    if !kubectl_err_lines.is_empty() {
+        result.kubectl_error = Some(kubectl_err_lines.join("\n"));
+    }
+
+    let _ = namespace;
+    result
+}
+
+fn delete_by_label_key(label: &str, namespace: &str) -> String {
+    format!("delete_by_label|{}|{}", namespace, label)
+}
+
+/// Preview what `kubectl_delete_by_label` would delete.
+#[tauri::command]
+fn plan_delete_by_label(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    label: String,
+    namespace: String,
+) -> Result<DestructivePlan, String> {
+    let config = active_project_config(&roots)?;
+    Ok(plan_destructive(
+        &confirmations,
+        &config,
+        "delete_by_label",
+        delete_by_label_key(&label, &namespace),
+        &namespace,
+        affected_by_label(&label, &namespace)?,
+    ))
+}
+
+#[tauri::command]
+fn kubectl_delete_by_label(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    label: String,
+    namespace: String,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    check_confirmation(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &delete_by_label_key(&label, &namespace),
+        &namespace,
+        confirmation_token.as_deref(),
+        || affected_by_label(&label, &namespace),
+    )?;
+    let result = run_kubectl(&[
+        "delete",
+        "all",
+        "-l",
+        &format!("app={}", label),
+        "-n",
+        &namespace,
+        "--ignore-not-found=true",
+    ]);
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "kubectl_delete_by_label",
+        &namespace,
+        &label,
+        format!("kubectl delete all -l app={} -n {} --ignore-not-found=true", label, namespace),
+        &result,
+    );
+    result
+}
+
+#[tauri::command]
+fn get_cluster_status() -> ClusterStatus {
+    if run_kubectl(&["version", "--client"]).is_err() {
+        return ClusterStatus {
+            fields: vec![],
+            kubectl_available: false,
+            error: Some("kubectl not found".to_string()),
+        };
+    }
+
+    let pods_json = run_kubectl(&["get", "pods", "--all-namespaces", "-o", "json"])
+        .and_then(|raw| {
+            serde_json::from_str::<serde_json::Value>(&raw).map_err(|e| format!("Cannot parse pod list: {}", e))
+        });
+    let pods: Vec<(PodInfo, Option<(String, String)>)> = match pods_json {
+        Ok(list) => list["items"].as_array().map(|items| items.iter().map(parse_pod).collect()).unwrap_or_default(),
+        Err(e) => {
+            return ClusterStatus {
+                fields: vec![],
+                kubectl_available: true,
+                error: Some(e),
+            }
+        }
+    };
+
+    // (namespace, target kind, target name) → HPA status
+    let hpas: Vec<(String, String, String, AutoscalerStatus)> = run_kubectl(&[
+        "get", "hpa", "--all-namespaces", "--no-headers",
+        "-o", "custom-columns=NS:.metadata.namespace,NAME:.metadata.name,\
+KIND:.spec.scaleTargetRef.kind,TARGET:.spec.scaleTargetRef.name,\
+MIN:.spec.minReplicas,MAX:.spec.maxReplicas,\
+CURRENT:.status.currentReplicas,DESIRED:.status.desiredReplicas",
+    ])
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|line| {
+        let p: Vec<&str> = line.split_whitespace().collect();
+        if p.len() < 8 {
+            return None;
+        }
+        Some((
+            p[0].to_string(),
+            p[2].to_string(),
+            p[3].to_string(),
+            AutoscalerStatus {
+                name: p[1].to_string(),
+                min_replicas: p[4].parse().unwrap_or(1),
+                max_replicas: p[5].parse().unwrap_or(0),
+                current_replicas: p[6].parse().unwrap_or(0),
+                desired_replicas: p[7].parse().unwrap_or(0),
+            },
+        ))
+    })
+    .collect();
+
+    let mut fields: Vec<FieldStatus> = Vec::new();
+
+    for resource in &["deployments", "statefulsets", "daemonsets"] {
+        let raw = run_kubectl(&["get", resource, "--all-namespaces", "--no-headers"])
+            .unwrap_or_default();
+        for line in raw.lines() {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            if p.len() < 4 {
+                continue;
+            }
+            let ns = p[0].to_string();
+            let name = p[1].to_string();
+
+            // daemonsets: NAMESPACE NAME DESIRED CURRENT READY UP-TO-DATE AVAILABLE …
+            let mut current = None;
+            let (desired, ready, available) =
+                if *resource == "daemonsets" && p.len() >= 7 {
+                    current = p[3].parse().ok();
+                    (p[2].parse().unwrap_or(0), p[4].parse().unwrap_or(0), p[6].parse().unwrap_or(0))
+                } else if *resource == "deployments" && p.len() >= 5 {
+                    let (r, d) = parse_ready(p[2]);
+                    let avail: u32 = p[4].parse().unwrap_or(r);
+                    (d, r, avail)
+                } else {
+                    let (r, d) = parse_ready(p[2]);
+                    (d, r, r)
+                };
+
+            let target_kind = match *resource {
+                "deployments" => "Deployment",
+                "statefulsets" => "StatefulSet",
+                _ => "DaemonSet",
+            };
+            // match by owner — a name prefix would also catch "api-gateway" pods for "api"
+            let my_pods: Vec<PodInfo> = pods
+                .iter()
+                .filter(|(pod, owner)| {
+                    pod.namespace == ns
+                        && owner.as_ref().is_some_and(|(kind, owner)| match target_kind {
+                            "Deployment" => {
+                                kind == "ReplicaSet" && owner.rsplit_once('-').map(|(d, _)| d) == Some(name.as_str())
+                            }
+                            _ => kind == target_kind && *owner == name,
+                        })
+                })
+                .map(|(pod, _)| pod.clone())
+                .collect();
+            let autoscaler = hpas
+                .iter()
+                .find(|(hns, kind, target, _)| *hns == ns && kind == target_kind && *target == name)
+                .map(|(_, _, _, hpa)| hpa.clone());
+
+            let status = compute_status(ready, desired).to_string();
+            fields.push(FieldStatus {
+                label: name,
+                namespace: ns,
+                desired,
+                ready,
+                available,
+                status,
+                pods: my_pods,
+                kind: target_kind.to_string(),
+                current,
+                autoscaler,
+            });
+        }
+    }
+
+    ClusterStatus {
+        fields,
+        kubectl_available: true,
+        error: None,
+    }
+}
+
+#[tauri::command]
+fn apply_replicas(
+    roots: tauri::State<ProjectRootState>,
+    file_path: String,
+    node_label: String,
+    replicas: u32,
+    hpa_max_replicas: Option<u32>,
+) -> Result<String, String> {
+    let file_path = confine_subpath(&roots, &file_path)?.to_string_lossy().to_string();
+    let root = project_root(&roots)?;
+
+    // Autoscaled: `replicas` becomes the HPA's minReplicas
+    if let Some(hpa) = workload_autoscaler(&root, &file_path, &node_label) {
+        let max = hpa_max_replicas.ok_or_else(|| autoscaled_replicas_error(&node_label, &hpa))?;
+        let patched = patch_hpa_bounds_in_content(&hpa.file_path, &hpa.name, replicas, max)?;
+        fs::write(&hpa.file_path, patched)
+            .map_err(|e| format!("Cannot write {}: {}", hpa.file_path, e))?;
+        let result = run_kubectl(&["apply", "-f", &hpa.file_path]);
+        record_result_audit(
+            Some(&root),
+            "apply_replicas",
+            &hpa.namespace,
+            &format!("hpa/{} (min={}, max={})", hpa.name, replicas, max),
+            format!("kubectl apply -f {}", hpa.file_path),
+            &result,
+        );
+        return Ok(format!("✓ {}", result?.trim()));
+    }
+
+    patch_replicas_in_file(&file_path, &node_label, replicas)?;
+    let namespace = fs::read_to_string(&file_path)
+        .ok()
+        .and_then(|content| {
+            content
+                .split("\n---")
+                .find(|doc| extract_metadata_field(doc, "name") == Some(node_label.as_str()))
+                .and_then(|doc| extract_metadata_field(doc, "namespace"))
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    let result = run_kubectl(&["apply", "-f", &file_path]);
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "apply_replicas",
+        &namespace,
+        &format!("{} (replicas={})", node_label, replicas),
+        format!("kubectl apply -f {}", file_path),
+        &result,
+    );
+    Ok(format!("✓ {}", result?.trim()))
+}
+
+/// Preview `apply_replicas`: the patched file is sent to the server via stdin
+/// and nothing is written to disk.
+#[tauri::command]
+fn apply_replicas_dry_run(
+    roots: tauri::State<ProjectRootState>,
+    file_path: String,
+    node_label: String,
+    replicas: u32,
+    hpa_max_replicas: Option<u32>,
+) -> DryRunResult {
+    let mut result = DryRunResult::default();
+    let patched = confine_subpath(&roots, &file_path)
+        .map_err(String::from)
+        .and_then(|p| {
+            let p = p.to_string_lossy().to_string();
+            let root = project_root(&roots)?;
+            match workload_autoscaler(&root, &p, &node_label) {
+                Some(hpa) => {
+                    let max = hpa_max_replicas
+                        .ok_or_else(|| autoscaled_replicas_error(&node_label, &hpa))?;
+                    patch_hpa_bounds_in_content(&hpa.file_path, &hpa.name, replicas, max)
+                }
+                None => patch_replicas_in_content(&p, &node_label, replicas),
+            }
+        });
+    let patched = match patched {
+        Ok(p) => p,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.commands_run.push("kubectl apply --dry-run=server -f -".to_string());
+    let (stdout, stderr, _) = run_kubectl_stdin(&["apply", "--dry-run=server", "-f", "-"], &patched);
+    collect_dry_run_output(&stdout, &stderr, &mut result);
+    result.success = result.rejected.is_empty();
+    result
+}
+
+/// `kubectl apply` a project file or dir, decrypting SOPS Secrets in memory.
+/// Returns stdout; the audit entry gets the commands that actually ran.
+fn kubectl_apply_inner(root: Option<&Path>, path: &Path, config: &ProjectConfig) -> Result<String, String> {
+    let mut commands = Vec::new();
+    let (stdout, stderr, success) = kubectl_apply_project_path(path, &[], config, &mut commands);
+    let result = if success { Ok(stdout) } else { Err(stderr) };
+    let output = match &result {
+        Ok(out) => out.as_str(),
+        Err(err) => err.as_str(),
+    };
+    record_audit(
+        root,
+        "kubectl_apply",
+        "",
+        &path.to_string_lossy(),
+        &commands,
+        result.is_ok(),
+        output,
+    );
+    result
+}
+
+#[tauri::command]
+fn kubectl_apply(roots: tauri::State<ProjectRootState>, path: String) -> Result<String, String> {
+    let path = confine_subpath(&roots, &path)?;
+    let config = active_project_config(&roots)?;
+    kubectl_apply_inner(project_root(&roots).ok().as_deref(), &path, &config)
+}
+
+fn kubectl_apply_dry_run_inner(path: Result<(PathBuf, ProjectConfig), String>) -> DryRunResult {
+    let mut result = DryRunResult::default();
+    let (path, config) = match path {
+        Ok(p) => p,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    let (stdout, stderr, _) =
+        kubectl_apply_project_path(&path, &["--dry-run=server"], &config, &mut result.commands_run);
+    collect_dry_run_output(&stdout, &stderr, &mut result);
+    result.success = result.rejected.is_empty();
+    result
+}
+
+fn confine_apply_path(roots: &ProjectRootState, path: &str) -> Result<(PathBuf, ProjectConfig), String> {
+    Ok((confine_subpath(roots, path)?, active_project_config(roots)?))
+}
+
+/// Preview `kubectl_apply` with `--dry-run=server`.
+#[tauri::command]
+fn kubectl_apply_dry_run(roots: tauri::State<ProjectRootState>, path: String) -> DryRunResult {
+    kubectl_apply_dry_run_inner(confine_apply_path(&roots, &path))
+}
+
+/// Preview `kubectl_apply_async`, off the UI thread.
+#[tauri::command]
+async fn kubectl_apply_async_dry_run(app: tauri::AppHandle, path: String) -> DryRunResult {
+    let path = confine_apply_path(&app.state::<ProjectRootState>(), &path);
+    tauri::async_runtime::spawn_blocking(move || kubectl_apply_dry_run_inner(path))
+        .await
+        .unwrap_or_else(|e| DryRunResult {
+            error: Some(format!("spawn error: {}", e)),
+            ..Default::default()
+        })
+}
+
+#[tauri::command]
+fn get_pod_logs(namespace: String, pod_name: String, tail: u32) -> Result<String, String> {
+    run_kubectl(&[
+        "logs",
+        "-n",
+        &namespace,
+        &pod_name,
+        &format!("--tail={}", tail),
+    ])
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ClusterEvent {
+    pub namespace: String,
+    /// "Normal" | "Warning"
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub reason: String,
+    pub object_kind: String,
+    pub object_name: String,
+    pub message: String,
+    pub count: u32,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    /// Field (workload / app label) the involved object belongs to, if known
+    pub field: Option<String>,
+}
+
+/// Event columns; the message may contain spaces, so it goes last.
+/// Newer events only carry `eventTime`, older ones only the timestamps.
+const EVENT_COLUMNS: &str = "custom-columns=NAMESPACE:.metadata.namespace,TYPE:.type,REASON:.reason,\
+KIND:.involvedObject.kind,NAME:.involvedObject.name,COUNT:.count,FIRST:.firstTimestamp,\
+LAST:.lastTimestamp,TIME:.eventTime,MESSAGE:.message";
+
+fn parse_event_line(line: &str) -> Option<ClusterEvent> {
+    let p: Vec<&str> = line.split_whitespace().collect();
+    if p.len() < 10 {
+        return None;
+    }
+    let value = |s: &str| (s != "<none>").then(|| s.to_string());
+    let event_time = value(p[8]);
+    Some(ClusterEvent {
+        namespace: p[0].to_string(),
+        event_type: p[1].to_string(),
+        reason: p[2].to_string(),
+        object_kind: p[3].to_string(),
+        object_name: p[4].to_string(),
+        message: p[9..].join(" "),
+        count: p[5].parse().unwrap_or(1),
+        first_seen: value(p[6]).or_else(|| event_time.clone()),
+        last_seen: value(p[7]).or(event_time),
+        field: None,
+    })
+}
+
+/// (namespace, pod) → app label of every pod, to attribute pod events to
+/// fields. Image deploys label with app.kubernetes.io/name instead of app.
+type PodApps = std::collections::HashMap<(String, String), String>;
+
+fn pod_apps(namespace: &str) -> PodApps {
+    let scope = if namespace == "all" { vec!["--all-namespaces"] } else { vec!["-n", namespace] };
+    let mut args = vec!["get", "pods"];
+    args.extend(scope);
+    args.extend([
+        "--no-headers",
+        "-o",
+        "custom-columns=NS:.metadata.namespace,NAME:.metadata.name,APP:.metadata.labels.app,\
+APP_NAME:.metadata.labels.app\\.kubernetes\\.io/name",
+    ]);
+    run_kubectl(&args)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            let app = p.get(2..4)?.iter().find(|a| **a != "<none>")?;
+            Some(((p[0].to_string(), p[1].to_string()), app.to_string()))
+        })
+        .collect()
+}
+
+/// Field an event's object belongs to: a pod's app label, a ReplicaSet's
+/// Deployment, a CronJob run's CronJob, or the workload / HPA / PDB /
+/// Service itself (named after the field).
+fn event_field(event: &ClusterEvent, pods: &PodApps) -> Option<String> {
+    let name = event.object_name.as_str();
+    match event.object_kind.as_str() {
+        "Pod" => pods.get(&(event.namespace.clone(), name.to_string())).cloned(),
+        "ReplicaSet" => name.rsplit_once('-').map(|(base, _)| base.to_string()),
+        "Job" => {
+            // <cronjob>-<schedule time> or <cronjob>-manual-<timestamp> (trigger_cronjob)
+            let base = name
+                .rsplit_once('-')
+                .filter(|(_, suffix)| !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()))
+                .map(|(base, _)| base.strip_suffix("-manual").unwrap_or(base));
+            Some(base.unwrap_or(name).to_string())
+        }
+        "Deployment" | "StatefulSet" | "DaemonSet" | "CronJob" | "HorizontalPodAutoscaler"
+        | "PodDisruptionBudget" | "Service" => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+/// Events in `namespace` ("all" for every namespace), oldest first, each
+/// attributed to a field where possible. With `field`, only events of
+/// objects belonging to that field.
+#[tauri::command]
+fn get_events(namespace: String, field: Option<String>) -> Result<Vec<ClusterEvent>, String> {
+    let mut args = vec!["get", "events"];
+    if namespace == "all" {
+        args.push("--all-namespaces");
+    } else {
+        args.extend(["-n", namespace.as_str()]);
+    }
+    args.extend(["--no-headers", "-o", EVENT_COLUMNS]);
+    let raw = run_kubectl(&args)?;
+    let pods = pod_apps(&namespace);
+
+    let mut events: Vec<ClusterEvent> = raw
+        .lines()
+        .filter_map(parse_event_line)
+        .map(|mut e| {
+            e.field = event_field(&e, &pods);
+            e
+        })
+        .filter(|e| field.is_none() || e.field == field)
+        .collect();
+    // RFC 3339 UTC timestamps sort lexically
+    events.sort_by(|a, b| a.last_seen.cmp(&b.last_seen));
+    Ok(events)
+}
+
+/// Running `kubectl get events --watch` process, if any, and a generation
+/// bumped whenever the watch is replaced or stopped.
+pub struct EventWatchState(pub Mutex<(u64, Option<std::process::Child>)>);
+
+/// Stream new events of `namespace` (optionally only `field`'s) as
+/// `cluster-event` Tauri events. Replaces a previous watch. Emits
+/// `cluster-event-watch-ended` when kubectl exits on its own — the API
+/// server closes watches after a while, so the UI should restart it.
+/// Replacing or stopping the watch doesn't emit it.
+#[tauri::command]
+fn watch_events(
+    app: tauri::AppHandle,
+    state: tauri::State<EventWatchState>,
+    namespace: String,
+    field: Option<String>,
+) -> Result<(), String> {
+    let mut args = vec!["get", "events", "--watch-only"];
+    if namespace == "all" {
+        args.push("--all-namespaces");
+    } else {
+        args.extend(["-n", namespace.as_str()]);
+    }
+    args.extend(["--no-headers", "-o", EVENT_COLUMNS]);
+    let mut child = Command::new("kubectl")
+        .args(&args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("kubectl not found: {}", e))?;
+    let stdout = child.stdout.take().ok_or("Cannot read kubectl output")?;
+
+    let generation = {
+        let mut guard = state.0.lock().unwrap();
+        if let Some(mut previous) = guard.1.take() {
+            let _ = previous.kill();
+            let _ = previous.wait();
+        }
+        guard.0 += 1;
+        guard.1 = Some(child);
+        guard.0
+    };
+
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        let mut pods = pod_apps(&namespace);
+        let mut pods_loaded = Instant::now();
+        for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+            let Some(mut event) = parse_event_line(&line) else { continue };
+            // new pods show up in events before we know them; refresh at most every 5s
+            let key = (event.namespace.clone(), event.object_name.clone());
+            if event.object_kind == "Pod" && !pods.contains_key(&key) && pods_loaded.elapsed() > Duration::from_secs(5) {
+                pods = pod_apps(&namespace);
+                pods_loaded = Instant::now();
+            }
+            event.field = event_field(&event, &pods);
+            if field.is_some() && event.field != field {
+                continue;
+            }
+            let _ = app.emit("cluster-event", &event);
+        }
+        // Still the current watch: kubectl exited on its own. Reap it and
+        // tell the UI; a replaced or stopped watch was reaped by whoever
+        // killed it.
+        let state = app.state::<EventWatchState>();
+        let mut guard = state.0.lock().unwrap();
+        if guard.0 == generation {
+            if let Some(mut child) = guard.1.take() {
+                let _ = child.wait();
+            }
+            drop(guard);
+            let _ = app.emit("cluster-event-watch-ended", &namespace);
+        }
+    });
+    Ok(())
+}
+
+/// Stop the event watch, if any.
+#[tauri::command]
+fn unwatch_events(state: tauri::State<EventWatchState>) {
+    let mut guard = state.0.lock().unwrap();
+    guard.0 += 1;
+    if let Some(mut child) = guard.1.take() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+// ─── Resource metrics (metrics-server) ────────────────────────────────────────
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ResourceAmounts {
+    pub cpu_millicores: Option<u64>,
+    pub memory_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeMetrics {
+    pub name: String,
+    pub cpu_millicores: u64,
+    pub cpu_percent: Option<u32>,
+    pub memory_bytes: u64,
+    pub memory_percent: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldMetrics {
+    pub field: String,
+    pub namespace: String,
+    pub pods: u32,
+    /// Current usage summed over the field's pods
+    pub usage: ResourceAmounts,
+    /// Per pod, from the project manifests (all containers summed)
+    pub requests: ResourceAmounts,
+    pub limits: ResourceAmounts,
+    /// Busiest pod's usage in percent of the per-pod request / limit
+    pub cpu_request_percent: Option<u32>,
+    pub memory_request_percent: Option<u32>,
+    pub cpu_limit_percent: Option<u32>,
+    pub memory_limit_percent: Option<u32>,
+    /// "ok" | "over-provisioned" | "under-provisioned" | "unknown" (no
+    /// requests found in the project)
+    pub status: String,
+    pub findings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsReport {
+    pub nodes: Vec<NodeMetrics>,
+    pub fields: Vec<FieldMetrics>,
+}
+
+/// Busiest pod below this share of its request → requests can come down.
+const OVERPROVISIONED_PERCENT: u32 = 20;
+/// Busiest pod above this share of its limit → throttling / OOM kill ahead.
+const NEAR_LIMIT_PERCENT: u32 = 90;
+
+/// CPU quantity ("250m", "0.5", "2", "1500000n") in millicores.
+fn parse_cpu_quantity(q: &str) -> Option<u64> {
+    let q = q.trim().trim_matches('"');
+    let (number, scale) = match q.char_indices().last()? {
+        (i, 'm') => (&q[..i], 1.0),
+        (i, 'u') => (&q[..i], 0.001),
+        (i, 'n') => (&q[..i], 0.000_001),
+        _ => (q, 1000.0),
+    };
+    number.parse::<f64>().ok().map(|n| (n * scale).round() as u64)
+}
+
+/// Memory quantity ("128Mi", "1G", "512000", "1e9") in bytes.
+fn parse_memory_quantity(q: &str) -> Option<u64> {
+    let q = q.trim().trim_matches('"');
+    const SUFFIXES: &[(&str, f64)] = &[
+        ("Ki", 1024.0), ("Mi", 1048576.0), ("Gi", 1073741824.0), ("Ti", 1099511627776.0),
+        ("k", 1e3), ("M", 1e6), ("G", 1e9), ("T", 1e12),
+    ];
+    let (number, scale) = SUFFIXES
+        .iter()
+        .find_map(|(suffix, scale)| q.strip_suffix(suffix).map(|n| (n, *scale)))
+        .unwrap_or((q, 1.0));
+    number.parse::<f64>().ok().map(|n| (n * scale).round() as u64)
+}
+
+fn format_millicores(m: u64) -> String {
+    format!("{}m", m)
+}
+
+fn format_bytes(b: u64) -> String {
+    format!("{}Mi", b / 1048576)
+}
+
+/// Per-pod requests and limits of every workload in the project, keyed by
+/// (namespace, name): the sum over its containers (init containers excluded).
+fn manifest_resources(root: &Path) -> std::collections::HashMap<(String, String), (ResourceAmounts, ResourceAmounts)> {
+    let workloads = ["Deployment", "StatefulSet", "DaemonSet", "Job", "CronJob"];
+    let mut out = std::collections::HashMap::new();
+    for (_, doc) in project_yaml_docs(root) {
+        let Some(kind) = extract_yaml_field(&doc, "kind") else { continue };
+        if !workloads.contains(&kind) {
+            continue;
+        }
+        let Some(name) = extract_metadata_field(&doc, "name") else { continue };
+        let ns = extract_metadata_field(&doc, "namespace").unwrap_or("default").to_string();
+        let containers = block_list_items(&doc, "containers");
+        let sum = |block: &str, field: &str, parse: fn(&str) -> Option<u64>| -> Option<u64> {
+            let values: Vec<u64> = containers
+                .iter()
+                .filter_map(|c| extract_block_field(c, block, field).and_then(|v| parse(&v)))
+                .collect();
+            (!values.is_empty()).then(|| values.iter().sum())
+        };
+        let requests = ResourceAmounts {
+            cpu_millicores: sum("requests", "cpu", parse_cpu_quantity),
+            memory_bytes: sum("requests", "memory", parse_memory_quantity),
+        };
+        let limits = ResourceAmounts {
+            cpu_millicores: sum("limits", "cpu", parse_cpu_quantity),
+            memory_bytes: sum("limits", "memory", parse_memory_quantity),
+        };
+        out.entry((ns, name.to_string())).or_insert((requests, limits));
+    }
+    out
+}
+
+fn percent_of(used: u64, of: Option<u64>) -> Option<u32> {
+    of.filter(|&o| o > 0).map(|o| (used * 100 / o) as u32)
+}
+
+/// `kubectl top` for nodes and pods, with pod usage aggregated per field
+/// (app label) and compared against the requests/limits in the project's
+/// manifests. Needs metrics-server (the metrics.k8s.io API).
+#[tauri::command]
+fn get_resource_metrics(
+    roots: tauri::State<ProjectRootState>,
+    namespace: Option<String>,
+) -> Result<MetricsReport, String> {
+    let scope = namespace.clone().unwrap_or_else(|| "all".to_string());
+    let mut top_pods = vec!["top", "pods", "--no-headers"];
+    if scope == "all" {
+        top_pods.push("--all-namespaces");
+    } else {
+        top_pods.extend(["-n", scope.as_str()]);
+    }
+    let pods_raw = run_kubectl(&top_pods).map_err(|e| {
+        if e.contains("Metrics API not available") || e.contains("metrics.k8s.io") {
+            "Metrics API not available — install metrics-server in the cluster".to_string()
+        } else {
+            e
+        }
+    })?;
+    let nodes_raw = run_kubectl(&["top", "nodes", "--no-headers"]).unwrap_or_default();
+
+    let nodes = nodes_raw
+        .lines()
+        .filter_map(|line| {
+            let p: Vec<&str> = line.split_whitespace().collect();
+            if p.len() < 5 {
+                return None;
+            }
+            let percent = |s: &str| s.trim_end_matches('%').parse::<u32>().ok();
+            Some(NodeMetrics {
+                name: p[0].to_string(),
+                cpu_millicores: parse_cpu_quantity(p[1])?,
+                cpu_percent: percent(p[2]),
+                memory_bytes: parse_memory_quantity(p[3])?,
+                memory_percent: percent(p[4]),
+            })
+        })
+        .collect();
+
+    // (namespace, field) → per-pod (cpu, memory) usage
+    let apps = pod_apps(&scope);
+    let mut usage: std::collections::BTreeMap<(String, String), Vec<(u64, u64)>> =
+        std::collections::BTreeMap::new();
+    for line in pods_raw.lines() {
+        let p: Vec<&str> = line.split_whitespace().collect();
+        let (ns, pod, cpu, memory) = match p.as_slice() {
+            [ns, pod, cpu, memory] => (ns.to_string(), *pod, *cpu, *memory),
+            [pod, cpu, memory] => (scope.clone(), *pod, *cpu, *memory),
+            _ => continue,
+        };
+        let Some(field) = apps.get(&(ns.clone(), pod.to_string())) else { continue };
+        let (Some(cpu), Some(memory)) = (parse_cpu_quantity(cpu), parse_memory_quantity(memory)) else { continue };
+        usage.entry((ns, field.clone())).or_default().push((cpu, memory));
+    }
+
+    let manifests = project_root(&roots)
+        .map(|root| manifest_resources(&root))
+        .unwrap_or_default();
+    let fields = usage
+        .into_iter()
+        .map(|((ns, field), pods)| {
+            let (requests, limits) = manifests.get(&(ns.clone(), field.clone())).cloned().unwrap_or_default();
+            let max_cpu = pods.iter().map(|p| p.0).max().unwrap_or(0);
+            let max_memory = pods.iter().map(|p| p.1).max().unwrap_or(0);
+            let cpu_request_percent = percent_of(max_cpu, requests.cpu_millicores);
+            let memory_request_percent = percent_of(max_memory, requests.memory_bytes);
+            let cpu_limit_percent = percent_of(max_cpu, limits.cpu_millicores);
+            let memory_limit_percent = percent_of(max_memory, limits.memory_bytes);
+
+            let mut findings = Vec::new();
+            let mut under = false;
+            let mut over = false;
+            if let Some(pct) = memory_limit_percent.filter(|&p| p >= NEAR_LIMIT_PERCENT) {
+                under = true;
+                findings.push(format!("Memory at {}% of the limit — OOM kill risk", pct));
+            }
+            if let Some(pct) = cpu_limit_percent.filter(|&p| p >= NEAR_LIMIT_PERCENT) {
+                under = true;
+                findings.push(format!("CPU at {}% of the limit — likely throttled", pct));
+            }
+            for (resource, pct, request, used) in [
+                ("CPU", cpu_request_percent, requests.cpu_millicores.map(format_millicores), format_millicores(max_cpu)),
+                ("Memory", memory_request_percent, requests.memory_bytes.map(format_bytes), format_bytes(max_memory)),
+            ] {
+                match (pct, request) {
+                    (Some(p), Some(request)) if p > 100 => {
+                        under = true;
+                        findings.push(format!("{} use {} exceeds the request of {} — raise the request", resource, used, request));
+                    }
+                    (Some(p), Some(request)) if p < OVERPROVISIONED_PERCENT => {
+                        over = true;
+                        findings.push(format!("{} use {} is {}% of the request of {} — the request can come down", resource, used, p, request));
+                    }
+                    (None, _) => findings.push(format!("No {} request in the project manifests", resource.to_lowercase())),
+                    _ => {}
+                }
+            }
+            let status = if requests.cpu_millicores.is_none() && requests.memory_bytes.is_none() {
+                "unknown"
+            } else if under {
+                "under-provisioned"
+            } else if over {
+                "over-provisioned"
+            } else {
+                "ok"
+            };
+            FieldMetrics {
+                field,
+                namespace: ns,
+                pods: pods.len() as u32,
+                usage: ResourceAmounts {
+                    cpu_millicores: Some(pods.iter().map(|p| p.0).sum()),
+                    memory_bytes: Some(pods.iter().map(|p| p.1).sum()),
+                },
+                requests,
+                limits,
+                cpu_request_percent,
+                memory_request_percent,
+                cpu_limit_percent,
+                memory_limit_percent,
+                status: status.to_string(),
+                findings,
+            }
+        })
+        .collect();
+
+    Ok(MetricsReport { nodes, fields })
+}
+
+// ─── Helm commands ────────────────────────────────────────────────────────────
+
+#[tauri::command]
+fn helm_template(
+    roots: tauri::State<ProjectRootState>,
+    component_dir: String,
+    release_name: String,
+    namespace: String,
+    values_file: Option<String>,
+) -> HelmRenderResult {
+    let confined = confine_subpath(&roots, &component_dir).and_then(|dir| {
+        let values = values_file.map(|v| confine_path(&roots, &v)).transpose()?;
+        Ok((dir, values.map(|p| p.to_string_lossy().to_string())))
+    });
+    let (dir, values_file) = match confined {
+        Ok(c) => c,
+        Err(e) => {
+            return HelmRenderResult {
+                rendered_files: vec![],
+                warnings: vec![],
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    let helm_dir = dir.join("helm");
+    let rendered_dir = dir.join("rendered");
+
+    if run_helm(&["version", "--short"], &dir).is_err() {
+        return HelmRenderResult {
+            rendered_files: vec![],
+            warnings: vec![],
+            error: Some(
+                "helm CLI not found — install helm 3 to render templates".to_string(),
+            ),
+        };
+    }
+
+    if let Err(e) = run_helm(&["dependency", "update", "."], &helm_dir) {
+        return HelmRenderResult {
+            rendered_files: vec![],
+            warnings: vec![],
+            error: Some(format!("helm dependency update failed: {}", e)),
+        };
+    }
+
+    let values_path = values_file
+        .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+
+    let raw = match run_helm(
+        &[
+            "template",
+            &release_name,
+            ".",
+            "--namespace",
+            &namespace,
+            "--values",
+            &values_path,
+            "--include-crds",
+        ],
+        &helm_dir,
+    ) {
+        Ok(out) => out,
+        Err(e) => {
+            return HelmRenderResult {
+                rendered_files: vec![],
+                warnings: vec![],
+                error: Some(format!("helm template failed: {}", e)),
+            }
+        }
+    };
+
+    if !rendered_dir.exists() {
+        if let Err(e) = fs::create_dir_all(&rendered_dir) {
+            return HelmRenderResult {
+                rendered_files: vec![],
+                warnings: vec![],
+                error: Some(format!("Cannot create rendered/: {}", e)),
+            };
+        }
+    } else if let Ok(entries) = fs::read_dir(&rendered_dir) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            if p.file_name().and_then(|n| n.to_str()) != Some(".gitkeep") {
+                let _ = fs::remove_file(&p);
+            }
+        }
+    }
+
+    let manifests = split_rendered_manifests(&raw);
+    let mut rendered_files = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (filename, content) in &manifests {
+        let out_path = rendered_dir.join(filename);
+        match fs::write(&out_path, content) {
+            Ok(_) => rendered_files.push(out_path.to_string_lossy().to_string()),
+            Err(e) => warnings.push(format!("Failed to write {}: {}", filename, e)),
+        }
+    }
+
+    HelmRenderResult {
+        rendered_files,
+        warnings,
+        error: None,
+    }
+}
+
+#[tauri::command]
+fn helm_install(
+    roots: tauri::State<ProjectRootState>,
+    component_dir: String,
+    release_name: String,
+    namespace: String,
+    values_file: Option<String>,
+) -> Result<String, String> {
+    let (dir, values_file, config) = confine_helm_install(&roots, &component_dir, values_file)?;
+    let helm_dir = dir.join("helm");
+
+    run_helm(&["dependency", "update", "."], &helm_dir)?;
+
+    let values_path = values_file
+        .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+
+    let (stdout, stderr, success) = run_helm_with_values(
+        &[
+            "upgrade",
+            "--install",
+            &release_name,
+            ".",
+            "--namespace",
+            &namespace,
+            "--create-namespace",
+            "--atomic=false",
+        ],
+        &values_path,
+        &helm_dir,
+        &config,
+    );
+    let result = if success { Ok(stdout) } else { Err(stderr) };
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "helm_install",
+        &namespace,
+        &release_name,
+        format!(
+            "helm upgrade --install {} . --namespace {} --create-namespace --values {} --atomic=false",
+            release_name, namespace, values_path
+        ),
+        &result,
+    );
+
+    Ok(format!("✓ {}", result?.trim()))
+}
+
+fn helm_install_dry_run_inner(
+    dir: Result<(PathBuf, Option<String>, ProjectConfig), String>,
+    release_name: &str,
+    namespace: &str,
+) -> DryRunResult {
+    let mut result = DryRunResult::default();
+    let (dir, values_file, config) = match dir {
+        Ok(d) => d,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    let helm_dir = dir.join("helm");
+    let values_path = values_file
+        .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+    dry_run_missing_namespace(namespace, &mut result);
+    if let Some((stdout, stderr)) =
+        helm_release_dry_run(&helm_dir, release_name, namespace, &values_path, &config, &mut result)
+    {
+        collect_dry_run_output(&stdout, &stderr, &mut result);
+        result.success = result.rejected.is_empty();
+    }
+    result
+}
+
+fn confine_helm_install(
+    roots: &ProjectRootState,
+    component_dir: &str,
+    values_file: Option<String>,
+) -> Result<(PathBuf, Option<String>, ProjectConfig), String> {
+    let dir = confine_subpath(roots, component_dir)?;
+    let values_file = values_file
+        .map(|v| confine_path(roots, &v).map(|p| p.to_string_lossy().to_string()))
+        .transpose()?;
+    Ok((dir, values_file, active_project_config(roots)?))
+}
+
+/// Preview `helm_install` with a helm dry-run checked by the API server.
+#[tauri::command]
+fn helm_install_dry_run(
+    roots: tauri::State<ProjectRootState>,
+    component_dir: String,
+    release_name: String,
+    namespace: String,
+    values_file: Option<String>,
+) -> DryRunResult {
+    let dir = confine_helm_install(&roots, &component_dir, values_file);
+    helm_install_dry_run_inner(dir, &release_name, &namespace)
+}
+
+/// Preview `helm_install_async`, off the UI thread.
+#[tauri::command]
+async fn helm_install_async_dry_run(
+    app: tauri::AppHandle,
+    component_dir: String,
+    release_name: String,
+    namespace: String,
+    values_file: Option<String>,
+) -> DryRunResult {
+    let dir = confine_helm_install(&app.state::<ProjectRootState>(), &component_dir, values_file);
+    tauri::async_runtime::spawn_blocking(move || helm_install_dry_run_inner(dir, &release_name, &namespace))
+        .await
+        .unwrap_or_else(|e| DryRunResult {
+            error: Some(format!("spawn error: {}", e)),
+            ..Default::default()
+        })
+}
+
+fn helm_uninstall_key(release_name: &str, namespace: &str) -> String {
+    format!("helm_uninstall|{}|{}", namespace, release_name)
+}
+
+/// Preview the objects `helm_uninstall` would delete.
+#[tauri::command]
+fn plan_helm_uninstall(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    release_name: String,
+    namespace: String,
+) -> Result<DestructivePlan, String> {
+    Ok(plan_destructive(
+        &confirmations,
+        &active_project_config(&roots)?,
+        "helm_uninstall",
+        helm_uninstall_key(&release_name, &namespace),
+        &namespace,
+        affected_by_release(&release_name, &namespace)?,
+    ))
+}
+
+/// Protected contexts/namespaces require a token from plan_helm_uninstall.
+#[tauri::command]
+fn helm_uninstall(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    release_name: String,
+    namespace: String,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    check_confirmation(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &helm_uninstall_key(&release_name, &namespace),
+        &namespace,
+        confirmation_token.as_deref(),
+        || affected_by_release(&release_name, &namespace),
+    )?;
+    let result = run_helm(
+        &[
+            "uninstall",
+            &release_name,
+            "--namespace",
+            &namespace,
+            "--ignore-not-found",
+        ],
+        Path::new("."),
+    );
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "helm_uninstall",
+        &namespace,
+        &release_name,
+        format!("helm uninstall {} --namespace {} --ignore-not-found", release_name, namespace),
+        &result,
+    );
+    result
+}
+
+#[tauri::command]
+fn helm_available() -> bool {
+    Command::new("helm")
+        .arg("version")
+        .arg("--short")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+fn helm_template_async(
+    roots: tauri::State<ProjectRootState>,
+    component_dir: String,
+    release_name: String,
+    namespace: String,
+    values_file: Option<String>,
+) -> Result<String, String> {
+    let component_dir = confine_subpath(&roots, &component_dir)?;
+    let values_file = values_file
+        .map(|v| confine_path(&roots, &v).map(|p| p.to_string_lossy().to_string()))
+        .transpose()?;
+    std::thread::spawn(move || {
+        let dir = component_dir.as_path();
+        let helm_dir = dir.join("helm");
+        let rendered_dir = dir.join("rendered");
+        if run_helm(&["version", "--short"], dir).is_err() {
+            return;
+        }
+        if run_helm(&["dependency", "update", "."], &helm_dir).is_err() {
+            return;
+        }
+        let values_path = values_file
+            .unwrap_or_else(|| helm_dir.join("values.yaml").to_string_lossy().to_string());
+        let raw = match run_helm(
+            &[
+                "template",
+                &release_name,
+                ".",
+                "--namespace",
+                &namespace,
+                "--values",
+                &values_path,
+                "--include-crds",
+            ],
+            &helm_dir,
+        ) {
+            Ok(out) => out,
+            Err(_) => return,
+        };
+        if !rendered_dir.exists() {
+            if std::fs::create_dir_all(&rendered_dir).is_err() { return; }
+        } else if let Ok(entries) = std::fs::read_dir(&rendered_dir) {
+            for entry in entries.flatten() {
+                let p = entry.path();
+                if p.file_name().and_then(|n| n.to_str()) != Some(".gitkeep") {
+                    let _ = std::fs::remove_file(&p);
+                }
+            }
+        }
+        let manifests = split_rendered_manifests(&raw);
+        for (filename, content) in &manifests {
+            let out_path = rendered_dir.join(filename);
+            let _ = std::fs::write(&out_path, content);
+        }
+    });
+    Ok("started".to_string())
+}
+
+#[tauri::command]
+fn helm_install_async(
+    roots: tauri::State<ProjectRootState>,
+    component_dir: String,
+    release_name: String,
+    namespace: String,
+    values_file: Option<String>,
+) -> Result<String, String> {
+    let (component_dir, values_file, config) = confine_helm_install(&roots, &component_dir, values_file)?;
+    let root = project_root(&roots).ok();
+    std::thread::spawn(move || {
+        let dir = component_dir.as_path();
+        let helm_dir = dir.join("helm");
+        if run_helm(&["dependency", "update", "."], &helm_dir).is_err() {
+            return;
+        }
+        let values_path = values_file
+            .unwrap_or_else(|| helm_dir.join(  "values.yaml").to_string_lossy().to_string());
+        let (stdout, stderr, success) = run_helm_with_values(
+            &[
+                "upgrade",
+                "--install",
+                &release_name,
+                ".",
+                "--namespace",
+                &namespace,
+                "--create-namespace",
+            ],
+            &values_path,
+            &helm_dir,
+            &config,
+        );
+        let result = if success { Ok(stdout) } else { Err(stderr) };
+        record_result_audit(
+            root.as_deref(),
+            "helm_install",
+            &namespace,
+            &release_name,
+            format!(
+                "helm upgrade --install {} . --namespace {} --create-namespace --values {}",
+                release_name, namespace, values_path
+            ),
+            &result,
+        );
+    });
+    Ok("started".to_string())
+}
+
+#[tauri::command]
+fn kubectl_apply_async(roots: tauri::State<ProjectRootState>, path: String) -> Result<String, String> {
+    let path = confine_subpath(&roots, &path)?;
+    let config = active_project_config(&roots)?;
+    let root = project_root(&roots).ok();
+    std::thread::spawn(move || {
+        let _ = kubectl_apply_inner(root.as_deref(), &path, &config);
+    });
+    Ok("started".to_string())
+}
+
+// ─── Helm chart export ────────────────────────────────────────────────────────
+//
+// Packs the project into one umbrella chart under charts/<name>/: every raw
+// component becomes templates/<id>/ with its main container's image, env
+// and resources, the workload's replicas and the namespace lifted into
+// values.yaml under the component's key; Helm components become
+// dependencies of the umbrella chart, aliased by their release name.
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HelmExportResult {
+    pub chart_dir: String,
+    pub generated_files: Vec<String>,
+    pub warnings: Vec<String>,
+    /// `helm template` rendered the exported chart without errors
+    pub validated: bool,
+    pub error: Option<String>,
+}
+
+/// Annotation in the Chart.yaml of an exported chart. Only charts that
+/// carry it are replaced by a new export.
+const EXPORTED_CHART_ANNOTATION: &str = "endfield.io/exported-chart";
+
+/// Values of one workload of a raw component.
+#[derive(Debug, Default)]
+struct ExportedWorkload {
+    /// Key under the component's values, `None` for the workload named
+    /// after the component (its values sit on the component itself)
+    key: Option<String>,
+    image: Option<String>,
+    replicas: Option<String>,
+    /// (name, YAML scalar as written in the manifest)
+    env: Vec<(String, String)>,
+    /// `resources` block of the main container, dedented
+    resources: Vec<String>,
+}
+
+/// Values collected from one raw component while templating it.
+#[derive(Debug, Default)]
+struct ExportedComponent {
+    /// values.yaml key (camelCase of the component id)
+    key: String,
+    namespace: String,
+    workloads: Vec<ExportedWorkload>,
+    /// Keys of SOPS-encrypted Secrets, left empty in values.yaml
+    secret_env: Vec<String>,
+}
+
+/// Keys of a component's own values, which a workload key must not shadow.
+const COMPONENT_VALUE_KEYS: &[&str] = &["namespace", "image", "replicas", "env", "resources", "secretEnv"];
+
+/// Component id → values key: "web-app" → "webApp".
+fn values_key(id: &str) -> String {
+    let mut key = String::new();
+    let mut upper = false;
+    for c in id.chars() {
+        if c.is_ascii_alphanumeric() {
+            key.push(if upper { c.to_ascii_uppercase() } else { c });
+            upper = false;
+        } else {
+            upper = !key.is_empty();
+        }
+    }
+    if key.starts_with(|c: char| c.is_ascii_digit()) {
+        key.insert(0, 'c');
+    }
+    key
+}
+
+fn line_indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Lines of the first `containers` item: (first line, end, indent of its keys).
+fn main_container_span(lines: &[&str]) -> Option<(usize, usize, usize)> {
+    let list = lines.iter().position(|l| l.trim_end().trim_start() == "containers:")?;
+    let list_indent = line_indent(lines[list]);
+    let start = (list + 1..lines.len()).find(|&i| !lines[i].trim().is_empty())?;
+    if !lines[start].trim_start().starts_with("- ") || line_indent(lines[start]) < list_indent {
+        return None;
+    }
+    let field_indent = line_indent(lines[start]) + 2;
+    let end = (start + 1..lines.len())
+        .find(|&i| !lines[i].trim().is_empty() && line_indent(lines[i]) < field_indent)
+        .unwrap_or(lines.len());
+    Some((start, end, field_indent))
+}
+
+/// Turn one manifest of a raw component into a template. Existing `{{` is
+/// escaped first so only the parameters added here are template actions.
+/// A workload's values are keyed by its name within the component; one
+/// whose key is taken keeps its image, env and replicas as written, and
+/// the second value is a warning.
+fn template_component_doc(doc: &str, component: &mut ExportedComponent) -> (String, Option<String>) {
+    let escaped = doc.replace("{{", "{{ \"{{\" }}");
+    let lines: Vec<&str> = escaped.lines().collect();
+    // values.yaml is not rendered, so it keeps the original text
+    let unescape = |s: &str| s.replace("{{ \"{{\" }}", "{{");
+    let kind = extract_yaml_field(doc, "kind").unwrap_or_default();
+    let name = extract_metadata_field(doc, "name").unwrap_or_default();
+    let sub_key = Some(values_key(name)).filter(|k| *k != component.key);
+    let mut values = ExportedWorkload { key: sub_key.clone(), ..Default::default() };
+    let key = match &sub_key {
+        Some(sub) => format!("{}.{}", component.key, sub),
+        None => component.key.clone(),
+    };
+    let mut warning = None;
+    let mut workload = matches!(kind, "Deployment" | "StatefulSet" | "DaemonSet" | "Job" | "CronJob");
+    if workload
+        && (component.workloads.iter().any(|w| w.key == sub_key)
+            || sub_key.as_deref().is_some_and(|k| COMPONENT_VALUE_KEYS.contains(&k)))
+    {
+        warning = Some(format!(
+            "{} {}: values key {} is already used — its image, env and replicas are not parameterized",
+            kind, name, key
+        ));
+        workload = false;
+    }
+    let main = if workload { main_container_span(&lines) } else { None };
+    let namespace_line = format!("namespace: {}", component.namespace);
+    let component_key = component.key.clone();
+
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let t = line.trim_start().trim_end();
+        let indent = line_indent(line);
+
+        if let Some((start, end, field_indent)) = main.filter(|(s, e, _)| i >= *s && i < *e) {
+            // the item's first line carries the list dash: `- image: …`
+            let field = if i == start { t.strip_prefix("- ").unwrap_or(t) } else { t };
+            let prefix = &line[..indent + (t.len() - field.len())];
+            if i == start || indent == field_indent {
+                if let Some(image) = field.strip_prefix("image:") {
+                    values.image = Some(unescape(image.trim().trim_matches('"').trim_matches('\'')));
+                    out.push(format!("{}image: {{{{ .Values.{}.image | quote }}}}", prefix, key));
+                    i += 1;
+                    continue;
+                }
+                if field == "env:" {
+                    out.push(line.to_string());
+                    i += 1;
+                    let mut name: Option<String> = None;
+                    while i < end && (lines[i].trim().is_empty() || line_indent(lines[i]) > field_indent) {
+                        let entry = lines[i].trim_start();
+                        let entry_field = entry.strip_prefix("- ").unwrap_or(entry);
+                        if let Some(n) = entry_field.strip_prefix("name:") {
+                            name = Some(n.trim().trim_matches('"').to_string());
+                        }
+                        match (entry_field.strip_prefix("value:"), &name) {
+                            (Some(value), Some(n)) => {
+                                let value = value.trim();
+                                values.env.push((n.clone(), if value.is_empty() { "\"\"".to_string() } else { unescape(value) }));
+                                let prefix = &lines[i][..lines[i].len() - entry_field.len()];
+                                out.push(format!("{}value: {{{{ index .Values.{}.env {:?} | quote }}}}", prefix, key, n));
+                            }
+                            _ => out.push(lines[i].to_string()),
+                        }
+                        i += 1;
+                    }
+                    continue;
+                }
+                if field == "resources:" {
+                    let block_end = (i + 1..end)
+                        .find(|&j| !lines[j].trim().is_empty() && line_indent(lines[j]) <= field_indent)
+                        .unwrap_or(end);
+                    values.resources = lines[i + 1..block_end]
+                        .iter()
+                        .filter(|l| !l.trim().is_empty())
+                        .map(|l| unescape(&l[(field_indent + 2).min(line_indent(l))..]))
+                        .collect();
+                    out.push(line.to_string());
+                    out.push(format!("{{{{- toYaml .Values.{}.resources | nindent {} }}}}", key, field_indent + 2));
+                    i = block_end;
+                    continue;
+                }
+            }
+        }
+
+        if workload && indent == 2 && t.starts_with("replicas:") && matches!(kind, "Deployment" | "StatefulSet") {
+            values.replicas = Some(t["replicas:".len()..].trim().to_string());
+            out.push(format!("  replicas: {{{{ .Values.{}.replicas }}}}", key));
+        } else if t.strip_prefix("- ").unwrap_or(t) == namespace_line {
+            let dash = if t.starts_with("- ") { "- " } else { "" };
+            out.push(format!("{}{}namespace: {{{{ .Values.{}.namespace }}}}", &line[..indent], dash, component_key));
+        } else {
+            out.push(line.to_string());
+        }
+        i += 1;
+    }
+    if workload {
+        component.workloads.push(values);
+    }
+    let mut templated = out.join("\n");
+    templated.push('\n');
+    (templated, warning)
+}
+
+/// Template for a SOPS-encrypted Secret: the keys stay, the values come
+/// from values.yaml (`<key>.secretEnv`) — ciphertext cannot go into a chart.
+fn template_encrypted_secret(doc: &str, component: &mut ExportedComponent) -> String {
+    let name = extract_metadata_field(doc, "name").unwrap_or("secret").to_string();
+    let mut in_data = false;
+    for line in doc.lines() {
+        let t = line.trim_end();
+        if t == "data:" || t == "stringData:" {
+            in_data = true;
+            continue;
+        }
+        if in_data {
+            match t.strip_prefix("  ").and_then(|l| l.split_once(':')) {
+                Some((k, _)) if !k.starts_with(' ') => component.secret_env.push(k.to_string()),
+                _ => in_data = t.starts_with("   "),
+            }
+        }
+    }
+    format!(
+        r#"apiVersion: v1
+kind: Secret
+metadata:
+  name: {name}
+  namespace: {{{{ .Values.{key}.namespace }}}}
+  labels:
+    managed-by: endfield
+type: Opaque
+stringData:
+{{{{- range $k, $v := .Values.{key}.secretEnv }}}}
+  {{{{ $k }}}}: {{{{ $v | quote }}}}
+{{{{- end }}}}
+"#,
+        name = name,
+        key = component.key,
+    )
+}
+
+fn exported_component_values(c: &ExportedComponent) -> String {
+    let mut out = format!("{}:\n  namespace: {}\n", c.key, c.namespace);
+    for w in &c.workloads {
+        let pad = match &w.key {
+            Some(key) => {
+                out.push_str(&format!("  {}:\n", key));
+                "    "
+            }
+            None => "  ",
+        };
+        if let Some(image) = &w.image {
+            out.push_str(&format!("{}image: {}\n", pad, serde_json::to_string(image).unwrap_or_default()));
+        }
+        if let Some(replicas) = &w.replicas {
+            out.push_str(&format!("{}replicas: {}\n", pad, replicas));
+        }
+        if !w.env.is_empty() {
+            out.push_str(&format!("{}env:\n", pad));
+            for (k, v) in &w.env {
+                out.push_str(&format!("{}  {}: {}\n", pad, serde_json::to_string(k).unwrap_or_default(), v));
+            }
+        }
+        if !w.resources.is_empty() {
+            out.push_str(&format!("{}resources:\n", pad));
+            for line in &w.resources {
+                out.push_str(&format!("{}  {}\n", pad, line));
+            }
+        }
+    }
+    if !c.secret_env.is_empty() {
+        out.push_str(&format!("  # set at install time: --set {}.secretEnv.<NAME>=…\n  secretEnv:\n", c.key));
+        for k in &c.secret_env {
+            out.push_str(&format!("    {}: \"\"\n", k));
+        }
+    }
+    out
+}
+
+/// SOPS-encrypted values file with its keys kept: every `ENC[…]` value
+/// becomes `""` and the `sops:` metadata block is dropped.
+fn blank_encrypted_values(content: &str) -> String {
+    let mut out = String::new();
+    let mut in_sops = false;
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.trim().is_empty() {
+            in_sops = line.starts_with("sops:");
+        }
+        if in_sops {
+            continue;
+        }
+        match line.find("ENC[") {
+            Some(pos) => {
+                out.push_str(&line[..pos]);
+                out.push_str("\"\"");
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Values of a Helm component re-keyed under its alias: the block under the
+/// chart's own key (how generate_infra nests them), else the whole file.
+fn dependency_values(values: &str, chart: &str, alias: &str) -> String {
+    let header = format!("{}:", chart);
+    let lines: Vec<&str> = values.lines().collect();
+    let body: Vec<String> = match lines.iter().position(|l| l.trim_end() == header) {
+        Some(start) => lines[start + 1..]
+            .iter()
+            .take_while(|l| l.trim().is_empty() || l.starts_with(' ') || l.starts_with('#'))
+            .map(|l| l.to_string())
+            .collect(),
+        None => lines.iter()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| format!("  {}", l))
+            .collect(),
+    };
+    let body = body.join("\n");
+    if body.trim().is_empty() {
+        format!("{}: {{}}\n", alias)
+    } else {
+        format!("{}:\n{}\n", alias, body.trim_end())
+    }
+}
+
+fn write_export_file(path: &Path, content: &str, generated: &mut Vec<String>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    generated.push(path.to_string_lossy().to_string());
+    Ok(())
+}
+
+fn export_helm_chart_inner(root: &Path, chart_name: &str, chart_dir: &Path) -> HelmExportResult {
+    let mut result = HelmExportResult {
+        chart_dir: chart_dir.to_string_lossy().to_string(),
+        generated_files: vec![],
+        warnings: vec![],
+        validated: false,
+        error: None,
+    };
+    let fail = |mut result: HelmExportResult, e: String| {
+        result.error = Some(e);
+        result
+    };
+
+    // a previous export is replaced; anything else in the way is not touched
+    if chart_dir.exists() {
+        // exports from before the annotation are recognized by their description
+        let exported = fs::read_to_string(chart_dir.join("Chart.yaml")).is_ok_and(|c| {
+            c.contains(EXPORTED_CHART_ANNOTATION)
+                || c.lines().any(|l| l.trim_end() == "description: Umbrella chart exported from an Endfield project")
+        });
+        if !exported {
+            return fail(result, format!(
+                "{} exists and is not a chart exported by Endfield — choose another name",
+                chart_dir.display()
+            ));
+        }
+        if let Err(e) = fs::remove_dir_all(chart_dir.join("templates")) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return fail(result, format!("Cannot clear old templates: {}", e));
+            }
+        }
+    }
+
+    let mut components: Vec<PathBuf> = ["apps", "infra"]
+        .iter()
+        .filter_map(|d| fs::read_dir(root.join(d)).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .collect();
+    components.sort();
+
+    let mut values = format!("# Values for {} — exported by Endfield.\n", chart_name);
+    let mut dependencies = String::new();
+    let mut namespaces: Vec<String> = Vec::new();
+    for dir in &components {
+        let id = dir.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+
+        if let Some(helm) = try_parse_helm_node(dir).and_then(|n| n.helm) {
+            dependencies.push_str(&format!(
+                "  - name: {}\n    version: \"{}\"\n    repository: \"{}\"\n    alias: {}\n",
+                helm.chart_name, helm.chart_version, helm.repo, id
+            ));
+            let mut component_values = fs::read_to_string(&helm.values_path).unwrap_or_default();
+            if is_sops_encrypted(&component_values) {
+                component_values = blank_encrypted_values(&component_values);
+                result.warnings.push(format!(
+                    "{}: values are SOPS-encrypted — exported with empty values, set {}.* at install time",
+                    helm.values_path, id
+                ));
+            }
+            values.push_str(&dependency_values(&component_values, &helm.chart_name, &id));
+            if helm.namespace != "default" {
+                namespaces.push(format!("{} (helm {})", helm.namespace, id));
+            }
+            continue;
+        }
+
+        let docs: Vec<(String, String)> = project_yaml_docs(dir)
+            .into_iter()
+            .filter(|(_, doc)| extract_yaml_field(doc, "kind").is_some())
+            .collect();
+        if docs.is_empty() {
+            continue;
+        }
+        let namespace = docs.iter()
+            .find_map(|(_, doc)| {
+                if extract_yaml_field(doc, "kind") == Some("Namespace") {
+                    extract_metadata_field(doc, "name")
+                } else {
+                    extract_metadata_field(doc, "namespace")
+                }
+            })
+            .unwrap_or("default")
+            .to_string();
+        if !namespaces.contains(&namespace) {
+            namespaces.push(namespace.clone());
+        }
+        let mut component = ExportedComponent { key: values_key(&id), namespace, ..Default::default() };
+        let mut templates: std::collections::BTreeMap<String, Vec<String>> = std::collections::BTreeMap::new();
+        for (file, doc) in &docs {
+            let kind = extract_yaml_field(doc, "kind").unwrap_or_default();
+            if kind == "Namespace" {
+                continue;
+            }
+            let name = Path::new(file).file_name().and_then(|n| n.to_str()).unwrap_or("manifest.yaml").to_string();
+            let templated = if is_sops_encrypted(doc) {
+                if kind != "Secret" {
+                    result.warnings.push(format!("{}: encrypted {} skipped", file, kind));
+                    continue;
+                }
+                template_encrypted_secret(doc, &mut component)
+            } else {
+                let (templated, warning) = template_component_doc(doc, &mut component);
+                result.warnings.extend(warning.map(|w| format!("{}: {}", file, w)));
+                templated
+            };
+            templates.entry(name).or_default().push(templated);
+        }
+        for (name, docs) in templates {
+            let path = chart_dir.join("templates").join(&id).join(&name);
+            if let Err(e) = write_export_file(&path, &docs.join("---\n"), &mut result.generated_files) {
+                return fail(result, e);
+            }
+        }
+        if !component.secret_env.is_empty() {
+            result.warnings.push(format!(
+                "{}: secret values are not exported — set {}.secretEnv.* at install time",
+                id, component.key
+            ));
+        }
+        values.push_str(&exported_component_values(&component));
+    }
+    if components.is_empty() {
+        return fail(result, "No apps/ or infra/ components to export".to_string());
+    }
+    if namespaces.len() > 1 {
+        result.warnings.push(format!(
+            "Components target several namespaces ({}) — they must exist before installing; Helm dependencies install into the release namespace",
+            namespaces.join(", ")
+        ));
+    }
+
+    let chart_yaml = format!(
+        "apiVersion: v2\nname: {}\ndescription: Umbrella chart exported from an Endfield project\ntype: application\nversion: 0.1.0\n\
+annotations:\n  {}: \"true\"\n{}",
+        chart_name,
+        EXPORTED_CHART_ANNOTATION,
+        if dependencies.is_empty() { String::new() } else { format!("dependencies:\n{}", dependencies) }
+    );
+    for (file, content) in [("Chart.yaml", chart_yaml.as_str()), ("values.yaml", values.as_str())] {
+        if let Err(e) = write_export_file(&chart_dir.join(file), content, &mut result.generated_files) {
+            return fail(result, e);
+        }
+    }
+
+    // validate: fetch dependencies, then render everything once
+    if !dependencies.is_empty() {
+        if let Err(e) = run_helm(&["dependency", "update", "."], chart_dir) {
+            return fail(result, format!("helm dependency update failed: {}", e.trim()));
+        }
+    }
+    match run_helm(&["template", chart_name, "."], chart_dir) {
+        Ok(_) => result.validated = true,
+        Err(e) => result.error = Some(format!("helm template failed: {}", e.trim())),
+    }
+    result
+}
+
+/// Export the project as one umbrella Helm chart in charts/<chart_name>/
+/// and validate it with `helm template`. A previous export of the same name
+/// is replaced. The project's own files are not modified.
+#[tauri::command]
+async fn export_helm_chart(app: tauri::AppHandle, chart_name: String) -> HelmExportResult {
+    let failed = |error: String| HelmExportResult {
+        chart_dir: String::new(),
+        generated_files: vec![],
+        warnings: vec![],
+        validated: false,
+        error: Some(error),
+    };
+    let valid_name = !chart_name.is_empty()
+        && chart_name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !chart_name.starts_with('-');
+    if !valid_name {
+        return failed(format!("Invalid chart name {:?} — use lowercase letters, digits and '-'", chart_name));
+    }
+    let root = match project_root(&app.state::<ProjectRootState>()) {
+        Ok(r) => r,
+        Err(e) => return failed(e.to_string()),
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let chart_dir = root.join("charts").join(&chart_name);
+        export_helm_chart_inner(&root, &chart_name, &chart_dir)
+    })
+    .await
+    .unwrap_or_else(|e| failed(format!("spawn error: {}", e)))
+}
+
+// ─── Deploy Image ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeployEnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeployPort {
+    #[serde(rename = "containerPort")]
+    pub container_port: u16,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeployResources {
+    #[serde(rename = "cpuRequest")]
+    pub cpu_request: Option<String>,
+    #[serde(rename = "memRequest")]
+    pub mem_request: Option<String>,
+    #[serde(rename = "cpuLimit")]
+    pub cpu_limit: Option<String>,
+    #[serde(rename = "memLimit")]
+    pub mem_limit: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeployImageRequest {
+    pub namespace: String,
+    pub name: String,
+    pub image: String,
+    pub replicas: u32,
+    pub env: Vec<DeployEnvVar>,
+    #[serde(rename = "secretEnv")]
+    pub secret_env: Vec<DeployEnvVar>,
+    pub ports: Vec<DeployPort>,
+    #[serde(rename = "serviceType", default = "default_service_type")]
+    pub service_type: String,
+    pub resources: Option<DeployResources>,
+    #[serde(rename = "imagePullSecret")]
+    pub image_pull_secret: Option<String>,
+    #[serde(rename = "createNamespace", default)]
+    pub create_namespace: bool,
+    /// Same values as FieldConfig::secret_backend
+    #[serde(rename = "secretBackend", default)]
+    pub secret_backend: Option<String>,
+    /// Same as FieldConfig::runtime
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    /// Same as FieldConfig::volumes
+    #[serde(default)]
+    pub volumes: Vec<VolumeConfig>,
+    /// Same as FieldConfig::init_containers; their env is passed as is
+    #[serde(rename = "initContainers", default)]
+    pub init_containers: Vec<ExtraContainer>,
+    /// Same as FieldConfig::sidecars
+    #[serde(default)]
+    pub sidecars: Vec<ExtraContainer>,
+}
+
+fn default_service_type() -> String { "ClusterIP".to_string() }
+
+#[derive(Debug, Serialize)]
+pub struct DeployImageManifests {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// SOPS-encrypted (values redacted if encryption isn't set up), sealed,
+    /// or an ExternalSecret — depending on the secret backend. A plaintext
+    /// Secret is only ever sent to the cluster.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub deployment: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeployImageResult {
+    pub success: bool,
+    #[serde(rename = "deploymentName")]
+    pub deployment_name: String,
+    #[serde(rename = "secretName")]
+    pub secret_name: Option<String>,
+    #[serde(rename = "serviceName")]
+    pub service_name: Option<String>,
+    pub namespace: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub manifests: DeployImageManifests,
+}
+
+// ── Manifest generators ────────────────────────────────────────────────────────
+
+fn gen_image_namespace(ns: &str) -> String {
+    format!(
+"apiVersion: v1
+kind: Namespace
+metadata:
+  name: {ns}
+  labels:
+    app.kubernetes.io/managed-by: endfield
+    endfield/type: image-deploy
+"
+    )
+}
+
+fn gen_image_secret(name: &str, ns: &str, vars: &[DeployEnvVar]) -> String {
+    let secret_name = format!("{}-secrets", name);
+    let data: String = vars.iter()
+        .map(|e| format!("  {}: \"{}\"\n", e.key, e.value.replace('"', "\\\"")))
+        .collect();
+    format!(
+"apiVersion: v1
+kind: Secret
+metadata:
+  name: {secret_name}
+  namespace: {ns}
+  labels:
+    app.kubernetes.io/name: {name}
+    app.kubernetes.io/managed-by: endfield
+    endfield/type: image-deploy
+    endfield/namespace: {ns}
+type: Opaque
+stringData:
+{data}"
+    )
+}
+
+fn plain_env_line(key: &str, value: &str) -> String {
+    format!("            - name: {}\n              value: \"{}\"\n", key, value.replace('"', "\\\""))
+}
+
+fn plain_env_lines(vars: &[EnvVar]) -> String {
+    vars.iter().map(|e| plain_env_line(&e.key, &e.value)).collect()
+}
+
+fn gen_image_deployment(req: &DeployImageRequest) -> String {
+    let name = &req.name;
+    let ns = &req.namespace;
+    let secret_name = format!("{}-secrets", name);
+
+    // ports block — always include name (required by K8s when >1 port)
+    let ports_yaml = if req.ports.is_empty() {
+        String::new()
+    } else {
+        let lines: String = req.ports.iter().map(|p| {
+            let port_name = match &p.name {
+                Some(n) if !n.is_empty() => n.clone(),
+                _ => format!("port-{}", p.container_port),
+            };
+            format!("            - containerPort: {port}\n              name: {name}\n",
+                port = p.container_port,
+                name = port_name,
+            )
+        }).collect();
+        format!("          ports:\n{}", lines)
+    };
+
+    // plain env
+    let plain_env: String = req.env.iter().map(|e| plain_env_line(&e.key, &e.value)).collect();
+
+    // secret env via secretKeyRef
+    let secret_env: String = req.secret_env.iter().map(|e| {
+        format!(
+"            - name: {key}
+              valueFrom:
+                secretKeyRef:
+                  name: {secret_name}
+                  key: {key}
+",
+            key = e.key,
+            secret_name = secret_name,
+        )
+    }).collect();
+
+    let env_block = if plain_env.is_empty() && secret_env.is_empty() {
+        String::new()
+    } else {
+        format!("          env:\n{}{}", plain_env, secret_env)
+    };
+
+    // resources block
+    let resources_block = match &req.resources {
+        Some(r) => {
+            let cpu_req = r.cpu_request.as_deref().unwrap_or("100m");
+            let mem_req = r.mem_request.as_deref().unwrap_or("128Mi");
+            let cpu_lim = r.cpu_limit.as_deref().unwrap_or("500m");
+            let mem_lim = r.mem_limit.as_deref().unwrap_or("512Mi");
+            format!(
+"          resources:
+            requests:
+              cpu: \"{cpu_req}\"
+              memory: \"{mem_req}\"
+            limits:
+              cpu: \"{cpu_lim}\"
+              memory: \"{mem_lim}\"
+"
+            )
+        }
+        None => String::new(),
+    };
+
+    // probes / lifecycle / securityContext, defaulted by image type
+    let rt = resolve_container_runtime(&req.runtime, image_to_type_id(&req.image));
+    let first_port = req.ports.first().map(|p| p.container_port as u32);
+    let runtime_block = container_runtime_block(&rt, first_port);
+
+    // extra volumes, init containers and sidecars
+    let mount_lines = volume_mount_lines(&req.volumes);
+    let mounts_block = if mount_lines.is_empty() {
+        String::new()
+    } else {
+        format!("          volumeMounts:\n{}", mount_lines)
+    };
+    let (init_block, sidecars_block) = extra_containers_blocks(
+        &req.init_containers,
+        &req.sidecars,
+        plain_env_lines,
+        &secret_name,
+        &pod_volume_names(&req.volumes),
+        false,
+    );
+    let pod_block = pod_runtime_block(&rt) + &init_block;
+
+    // imagePullSecrets block
+    let pull_secrets_block = match &req.image_pull_secret {
+        Some(s) if !s.is_empty() => format!(
+"      imagePullSecrets:
+        - name: {s}
+"
+        ),
+        _ => String::new(),
+    };
+
+    format!(
+"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app.kubernetes.io/name: {name}
+    app.kubernetes.io/managed-by: endfield
+    endfield/type: image-deploy
+    endfield/namespace: {ns}
+spec:
+  replicas: {replicas}
+  selector:
+    matchLabels:
+      app.kubernetes.io/name: {name}
+  template:
+    metadata:
+      labels:
+        app.kubernetes.io/name: {name}
+        app.kubernetes.io/managed-by: endfield
+    spec:
+{pod_block}{pull_secrets_block}      containers:
+        - name: {name}
+          image: {image}
+{ports_yaml}{env_block}{resources_block}{runtime_block}{mounts_block}{sidecars_block}{volumes_block}",
+        name = name,
+        ns = ns,
+        replicas = req.replicas,
+        image = req.image,
+        pod_block = pod_block,
+        pull_secrets_block = pull_secrets_block,
+        ports_yaml = ports_yaml,
+        env_block = env_block,
+        resources_block = resources_block,
+        runtime_block = runtime_block,
+        mounts_block = mounts_block,
+        sidecars_block = sidecars_block,
+        volumes_block = pod_volumes_block(&req.volumes),
+    )
+}
+
+fn gen_image_service(name: &str, ns: &str, ports: &[DeployPort], service_type: &str) -> String {
+    let port_lines: String = ports.iter().map(|p| {
+        let port_name = match &p.name {
+            Some(n) if !n.is_empty() => n.clone(),
+            _ => format!("port-{}", p.container_port),
+        };
+        format!(
+"    - name: {name}
+      port: {port}
+      targetPort: {port}
+      protocol: TCP
+",
+            name = port_name,
+            port = p.container_port,
+        )
+    }).collect();
+
+    format!(
+"apiVersion: v1
+kind: Service
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    app.kubernetes.io/name: {name}
+    app.kubernetes.io/managed-by: endfield
+    endfield/type: image-deploy
+    endfield/namespace: {ns}
+spec:
+  selector:
+    app.kubernetes.io/name: {name}
+  type: {service_type}
+  ports:
+{port_lines}"
+    )
+}
+
+/// Deploy a custom Docker image to Kubernetes.
+/// Generates manifests in-memory and applies them via kubectl apply --server-side.
+/// Idempotent: re-running updates image/env/replicas.
+#[tauri::command]
+async fn deploy_image(app: tauri::AppHandle, request: DeployImageRequest) -> DeployImageResult {
+    let roots = app.state::<ProjectRootState>();
+    let root = project_root(&roots).ok();
+    let project = active_project_config(&roots).unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = deploy_image_inner(request, &project);
+        record_audit(
+            root.as_deref(),
+            "deploy_image",
+            &result.namespace,
+            &result.deployment_name,
+            &["kubectl apply --server-side --field-manager=endfield --force-conflicts -f -".to_string()],
+            result.success,
+            &format!("{}{}", result.stdout, result.stderr),
+        );
+        result
+    }).await.unwrap_or_else(|e| DeployImageResult {
+        success: false,
+        deployment_name: String::new(),
+        secret_name: None,
+        service_name: None,
+        namespace: String::new(),
+        stdout: String::new(),
+        stderr: format!("spawn error: {}", e),
+        error: Some(format!("spawn error: {}", e)),
+        manifests: DeployImageManifests {
+            namespace: None,
+            secret: None,
+            deployment: String::new(),
+            service: None,
+        },
+    })
+}
+
+/// Preview `deploy_image`: the same manifests go through a server-side
+/// dry-run, in the same order. Nothing is created, including the namespace.
+#[tauri::command]
+async fn deploy_image_dry_run(app: tauri::AppHandle, request: DeployImageRequest) -> DryRunResult {
+    let project = active_project_config(&app.state::<ProjectRootState>()).unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let req = request;
+        let ns = req.namespace.clone();
+        let mut result = DryRunResult::default();
+
+        if run_kubectl(&["get", "namespace", &ns]).is_err() {
+            if req.create_namespace {
+                result.created.push(format!("namespace/{}", ns));
+                result.warnings.push(format!(
+                    "Namespace {} does not exist yet — it will be created on deploy",
+                    ns
+                ));
+            } else {
+                result.warnings.push(format!(
+                    "Namespace {} does not exist and create_namespace is off — the deploy will fail",
+                    ns
+                ));
+            }
+        }
+
+        let mut manifests: Vec<String> = Vec::new();
+        match image_secret_manifests(&req, &project) {
+            Ok(Some((apply, _))) => manifests.push(apply),
+            Ok(None) => {}
+            Err(e) => {
+                result.error = Some(e);
+                return result;
+            }
+        }
+        result.warnings.extend(req.volumes.iter().filter_map(|v| check_volume(v).err()));
+        result.warnings.extend(extra_container_warnings(
+            &req.name,
+            &req.init_containers,
+            &req.sidecars,
+            &pod_volume_names(&req.volumes),
+            &req.secret_env.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+        ));
+        manifests.push(gen_image_deployment(&req));
+        if !req.ports.is_empty() {
+            manifests.push(gen_image_service(&req.name, &ns, &req.ports, &req.service_type));
+        }
+        for yaml in &manifests {
+            kubectl_dry_run_manifest(yaml, &ns, &mut result);
+        }
+
+        result.success = result.rejected.is_empty();
+        result
+    }).await.unwrap_or_else(|e| DryRunResult {
+        error: Some(format!("spawn error: {}", e)),
+        ..Default::default()
+    })
+}
+
+/// Secret manifests for an image deploy in the chosen backend:
+/// (what is applied, what is safe to hand back to the UI, which may save it).
+/// SOPS applies the plaintext and shares it encrypted (or redacted if
+/// encryption isn't set up); sealed and external secrets are the same
+/// manifest both ways.
+fn image_secret_manifests(
+    req: &DeployImageRequest,
+    project: &ProjectConfig,
+) -> Result<Option<(String, String)>, String> {
+    if req.secret_env.is_empty() {
+        return Ok(None);
+    }
+    let plain = gen_image_secret(&req.name, &req.namespace, &req.secret_env);
+    match secret_backend(req.secret_backend.as_deref(), project)? {
+        SecretBackend::Sops => {
+            let shared = encrypt_secret_manifest(&plain, project).unwrap_or_else(|_| {
+                let redacted: Vec<DeployEnvVar> = req.secret_env
+                    .iter()
+                    .map(|v| DeployEnvVar { key: v.key.clone(), value: "<redacted>".to_string() })
+                    .collect();
+                gen_image_secret(&req.name, &req.namespace, &redacted)
+            });
+            Ok(Some((plain, shared)))
+        }
+        SecretBackend::SealedSecrets => {
+            let sealed = seal_secret_manifest(&plain, project)?;
+            Ok(Some((sealed.clone(), sealed)))
+        }
+        SecretBackend::ExternalSecrets => {
+            let keys: Vec<&str> = req.secret_env.iter().map(|v| v.key.as_str()).collect();
+            let yaml = generate_external_secret_yaml(
+                &format!("{}-secrets", req.name),
+                &req.name,
+                &req.namespace,
+                &keys,
+                &project.secrets.external_secrets,
+            );
+            Ok(Some((yaml.clone(), yaml)))
+        }
+    }
+}
+
+fn deploy_image_inner(req: DeployImageRequest, project: &ProjectConfig) -> DeployImageResult {
+    let name = req.name.clone();
+    let ns = req.namespace.clone();
+    let has_secret = !req.secret_env.is_empty();
+    let has_service = !req.ports.is_empty();
+    let secret_name = if has_secret { Some(format!("{}-secrets", name)) } else { None };
+    let service_name = if has_service { Some(name.clone()) } else { None };
+
+    // Build manifests
+    let ns_manifest = if req.create_namespace {
+        Some(gen_image_namespace(&ns))
+    } else {
+        None
+    };
+    let (secret_manifest, shared_secret) = match image_secret_manifests(&req, project) {
+        Ok(Some((apply, share))) => (Some(apply), Some(share)),
+        Ok(None) => (None, None),
+        Err(e) => {
+            return DeployImageResult {
+                success: false,
+                deployment_name: name,
+                secret_name,
+                service_name,
+                namespace: ns,
+                stdout: String::new(),
+                stderr: e.clone(),
+                error: Some(e),
+                manifests: DeployImageManifests {
+                    namespace: ns_manifest,
+                    secret: None,
+                    deployment: gen_image_deployment(&req),
+                    service: None,
+                },
+            }
+        }
+    };
+    let deploy_manifest = gen_image_deployment(&req);
+    let service_manifest = if has_service {
+        Some(gen_image_service(&name, &ns, &req.ports, &req.service_type))
+    } else {
+        None
+    };
+
+    // Apply order: Namespace → Secret → Deployment → Service
+    let mut all_stdout = Vec::<String>::new();
+    let mut all_stderr = Vec::<String>::new();
+    let mut overall_success = true;
+
+    // Ensure namespace
+    if req.create_namespace {
+        let yaml = ns_manifest.as_deref().unwrap();
+        match kubectl_apply_manifest(yaml, &ns) {
+            Ok(out) => all_stdout.push(out),
+            Err(e) => { all_stderr.push(e.clone()); overall_success = false; }
+        }
+    } else {
+        // Just ensure it exists (non-fatal)
+        let _ = ensure_namespace(&ns);
+    }
+
+    if !overall_success {
+        return DeployImageResult {
+            success: false,
+            deployment_name: name,
+            secret_name,
+            service_name,
+            namespace: ns,
+            stdout: all_stdout.join("\n"),
+            stderr: all_stderr.join("\n"),
+            error: Some(all_stderr.join("\n")),
+            manifests: DeployImageManifests {
+                namespace: ns_manifest,
+                secret: shared_secret,
+                deployment: deploy_manifest,
+                service: service_manifest,
+            },
+        };
+    }
+
+    // Apply Secret
+    if let Some(ref yaml) = secret_manifest {
+        match kubectl_apply_manifest(yaml, &ns) {
+            Ok(out) => all_stdout.push(out),
+            Err(e) => { all_stderr.push(e); overall_success = false; }
+        }
+    }
+
+    // Apply Deployment
+    match kubectl_apply_manifest(&deploy_manifest, &ns) {
+        Ok(out) => all_stdout.push(out),
+        Err(e) => { all_stderr.push(e); overall_success = false; }
+    }
+
+    // Apply Service
+    if let Some(ref yaml) = service_manifest {
+        match kubectl_apply_manifest(yaml, &ns) {
+            Ok(out) => all_stdout.push(out),
+            Err(e) => { all_stderr.push(e); overall_success = false; }
+        }
+    }
+
+    let err = if overall_success { None } else { Some(all_stderr.join("\n")) };
+
+    DeployImageResult {
+        success: overall_success,
+        deployment_name: name,
+        secret_name,
+        service_name,
+        namespace: ns,
+        stdout: all_stdout.join("\n"),
+        stderr: all_stderr.join("\n"),
+        error: err,
+        manifests: DeployImageManifests {
+            namespace: ns_manifest,
+            secret: shared_secret,
+            deployment: deploy_manifest,
+            service: service_manifest,
+        },
+    }
+}
+
+/// Apply a YAML string via kubectl apply --server-side (stdin).
+fn kubectl_apply_manifest(yaml: &str, _namespace: &str) -> Result<String, String> {
+    let (stdout, stderr, success) = run_kubectl_stdin(
+        &["apply", "--server-side", "--field-manager=endfield", "--force-conflicts", "-f", "-"],
+        yaml,
+    );
+    if success {
+        Ok(stdout)
+    } else {
+        Err(stderr)
+    }
+}
+
+/// Server-side dry-run of a single-object manifest with the same apply flags
+/// as `kubectl_apply_manifest`. Server-side apply reports every object as
+/// "serverside-applied", so existing objects are re-classified with
+/// `kubectl diff` (exit 0 = unchanged) and missing ones count as created.
+fn kubectl_dry_run_manifest(yaml: &str, namespace: &str, result: &mut DryRunResult) {
+    let (stdout, stderr, _) = run_kubectl_stdin(
+        &[
+            "apply", "--server-side", "--field-manager=endfield", "--force-conflicts",
+            "--dry-run=server", "-f", "-",
+        ],
+        yaml,
+    );
+    result.commands_run.push(
+        "kubectl apply --server-side --field-manager=endfield --force-conflicts --dry-run=server -f -"
+            .to_string(),
+    );
+    let mut applied = DryRunResult::default();
+    collect_dry_run_output(&stdout, &stderr, &mut applied);
+    result.rejected.extend(applied.rejected);
+    result.warnings.extend(applied.warnings);
+
+    for object in applied.configured {
+        let exists = run_kubectl(&["get", &object, "-n", namespace, "-o", "name", "--ignore-not-found=true"])
+            .map(|o| !o.trim().is_empty())
+            .unwrap_or(false);
+        if !exists {
+            result.created.push(object);
+            continue;
+        }
+        let (_, diff_err, same) = run_kubectl_stdin(
+            &["diff", "--server-side", "--field-manager=endfield", "--force-conflicts", "-f", "-"],
+            yaml,
+        );
+        if same {
+            result.unchanged.push(object);
+        } else {
+            if !diff_err.trim().is_empty() {
+                result.warnings.push(diff_err.trim().to_string());
+            }
+            result.configured.push(object);
+        }
+    }
+}
+
+// ─── Container registries (Registry HTTP API v2) ──────────────────────────────
+//
+// Tag listing and digest lookups go through `curl`, the same way the app
+// shells out to kubectl and helm. Credentials come from the workload's
+// pull secrets or the local docker config and are handed to curl on stdin
+// (`-H @-`), so they never show up in the process list.
+
+const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+
+/// Manifest types accepted when resolving a digest. Indexes come first so a
+/// multi-arch tag resolves to its index, not to one platform's manifest.
+const MANIFEST_ACCEPT: &[&str] = &[
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+    "application/vnd.oci.image.manifest.v1+json",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct ImageRef {
+    /// Registry API host (and port); Docker Hub images map to registry-1.docker.io
+    registry: String,
+    /// Repository path, `library/` included for official Docker Hub images
+    repository: String,
+    tag: Option<String>,
+    digest: Option<String>,
+}
+
+impl ImageRef {
+    /// Image name as written in manifests — Docker Hub prefixes stay implicit.
+    fn name(&self) -> String {
+        if self.registry == DOCKER_HUB_REGISTRY {
+            self.repository.strip_prefix("library/").unwrap_or(&self.repository).to_string()
+        } else {
+            format!("{}/{}", self.registry, self.repository)
+        }
+    }
+
+    /// Reference the registry resolves: the digest if pinned, else the tag.
+    fn reference(&self) -> &str {
+        self.digest.as_deref().or(self.tag.as_deref()).unwrap_or("latest")
+    }
+}
+
+fn parse_image_ref(image: &str) -> Result<ImageRef, String> {
+    let image = image.trim();
+    let (rest, digest) = match image.split_once('@') {
+        Some((rest, digest)) => (rest, Some(digest.to_string())),
+        None => (image, None),
+    };
+    // a tag colon sits after the last slash; an earlier one is a registry port
+    let (name, tag) = match rest.rfind(':') {
+        Some(i) if !rest[i..].contains('/') => (&rest[..i], Some(rest[i + 1..].to_string())),
+        _ => (rest, None),
+    };
+    let (registry, repository) = match name.split_once('/') {
+        Some((host, path)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+            (registry_host_key(host), path.to_string())
+        }
+        _ => (DOCKER_HUB_REGISTRY.to_string(), name.to_string()),
+    };
+    if repository.is_empty() || tag.as_deref() == Some("") || digest.as_deref() == Some("") {
+        return Err(format!("Invalid image reference: {:?}", image));
+    }
+    let repository = if registry == DOCKER_HUB_REGISTRY && !repository.contains('/') {
+        format!("library/{}", repository)
+    } else {
+        repository
+    };
+    Ok(ImageRef { registry, repository, tag, digest })
+}
+
+/// Registry host of a docker config key (`https://index.docker.io/v1/`,
+/// `ghcr.io`, `localhost:5000`…), with Docker Hub aliases folded together.
+fn registry_host_key(key: &str) -> String {
+    let host = key.trim_start_matches("https://").trim_start_matches("http://");
+    let host = host.split('/').next().unwrap_or(host);
+    match host {
+        "docker.io" | "index.docker.io" | "registry.hub.docker.com" => DOCKER_HUB_REGISTRY.to_string(),
+        h => h.to_string(),
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let digits: Vec<u8> = input.trim_end_matches('=').bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(value)
+        .collect::<Option<_>>()?;
+    let mut out = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, d)| n | (*d as u32) << (18 - 6 * i));
+        out.extend(n.to_be_bytes()[1..chunk.len()].iter());
+    }
+    Some(out)
+}
+
+/// Basic credential (base64 `user:password`) for a registry from a docker
+/// config: `auths.<host>.auth`, a username/password pair, or the configured
+/// credential helper (`credHelpers` / `credsStore`).
+fn docker_config_auth(config: &serde_json::Value, registry: &str) -> Option<String> {
+    if let Some(auths) = config["auths"].as_object() {
+        for (key, entry) in auths {
+            if registry_host_key(key) != registry {
+                continue;
+            }
+            if let Some(auth) = entry["auth"].as_str().filter(|a| !a.is_empty()) {
+                return Some(auth.to_string());
+            }
+            if let (Some(user), Some(password)) = (entry["username"].as_str(), entry["password"].as_str()) {
+                return Some(base64_encode(format!("{}:{}", user, password).as_bytes()));
+            }
+        }
+    }
+    let helper = config["credHelpers"]
+        .as_object()
+        .and_then(|helpers| helpers.iter().find(|(k, _)| registry_host_key(k) == registry))
+        .and_then(|(_, v)| v.as_str())
+        .or_else(|| config["credsStore"].as_str())?;
+    credential_helper_auth(helper, registry)
+}
+
+/// Ask `docker-credential-<helper> get` for a registry's credentials.
+/// Identity tokens (`<token>` user) need an OAuth exchange and are skipped.
+fn credential_helper_auth(helper: &str, registry: &str) -> Option<String> {
+    use std::io::Write;
+    let server = if registry == DOCKER_HUB_REGISTRY { "https://index.docker.io/v1/" } else { registry };
+    let mut child = Command::new(format!("docker-credential-{}", helper))
+        .arg("get")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.as_mut()?.write_all(server.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let creds: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let user = creds["Username"].as_str()?;
+    let secret = creds["Secret"].as_str()?;
+    (user != "<token>").then(|| base64_encode(format!("{}:{}", user, secret).as_bytes()))
+}
+
+/// `~/.docker/config.json`, or `$DOCKER_CONFIG/config.json`.
+fn local_docker_config() -> Option<serde_json::Value> {
+    let dir = match std::env::var("DOCKER_CONFIG") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => {
+            let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).ok()?;
+            Path::new(&home).join(".docker")
+        }
+    };
+    let content = fs::read_to_string(dir.join("config.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Docker config held by a pull secret. Legacy `.dockercfg` secrets store
+/// the `auths` map directly and are wrapped to the same shape; secrets of
+/// any other type yield None.
+fn pull_secret_docker_config(namespace: &str, name: &str) -> Option<serde_json::Value> {
+    let template = "go-template=\
+{{if eq .type \"kubernetes.io/dockerconfigjson\"}}{{index .data \".dockerconfigjson\" | base64decode}}{{end}}\
+{{if eq .type \"kubernetes.io/dockercfg\"}}{\"auths\":{{index .data \".dockercfg\" | base64decode}}}{{end}}";
+    let out = run_kubectl(&["get", "secret", name, "-n", namespace, "-o", template]).ok()?;
+    serde_json::from_str(out.trim()).ok()
+}
+
+/// Credential for an image: the first pull secret that has one, then the
+/// local docker config. None → anonymous access.
+fn registry_auth(image: &ImageRef, namespace: &str, pull_secrets: &[String]) -> Option<String> {
+    pull_secrets
+        .iter()
+        .filter_map(|name| pull_secret_docker_config(namespace, name))
+        .chain(local_docker_config())
+        .find_map(|config| docker_config_auth(&config, &image.registry))
+}
+
+struct RegistryResponse {
+    status: u32,
+    /// Header names lowercased
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RegistryResponse {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Split `curl -i` output into status, headers and body. Proxy `CONNECT`
+/// and `100 Continue` blocks before the real response are skipped.
+fn parse_http_response(raw: &str) -> Result<RegistryResponse, String> {
+    let mut rest = raw;
+    loop {
+        let (head, body) = rest.split_once("\r\n\r\n").unwrap_or((rest, ""));
+        if body.starts_with("HTTP/") {
+            rest = body;
+            continue;
+        }
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|s| s.parse().ok())
+            .ok_or("Malformed HTTP response from registry")?;
+        let headers = lines
+            .filter_map(|l| l.split_once(':'))
+            .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+            .collect();
+        return Ok(RegistryResponse { status, headers, body: body.to_string() });
+    }
+}
+
+/// Run curl with extra request headers fed through stdin. Only http(s)
+/// URLs are accepted; pass them with `--url`, never as a bare argument.
+fn run_curl(args: &[&str], headers: &[String]) -> Result<RegistryResponse, String> {
+    use std::io::Write;
+    let mut child = Command::new("curl")
+        .args(["-sS", "-i", "--max-time", "30", "--proto", "=https,http", "-H", "@-"])
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl not found: {}", e))?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(headers.join("\n").as_bytes())
+            .map_err(|e| format!("stdin write error: {}", e))?;
+    }
+    let output = child.wait_with_output()
+        .map_err(|e| format!("curl wait error: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Registry request failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    parse_http_response(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `key="value"` parameters of a `WWW-Authenticate` challenge. Values may
+/// contain commas (`scope="repository:a:pull,push"`), so this is not a split.
+fn parse_auth_challenge(challenge: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = challenge.split_once(' ').map(|(_, p)| p).unwrap_or("");
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_ascii_lowercase();
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+            }
+            None => {
+                let end = after.find(',').unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        params.push((key, value.to_string()));
+        rest = next;
+    }
+    params
+}
+
+/// Client for one repository. Sends Basic credentials up front (plain
+/// `registry:2` with htpasswd) and switches to a Bearer token when the
+/// registry answers with a token challenge (Docker Hub, GHCR, Harbor…).
+struct RegistryClient {
+    image: ImageRef,
+    base_url: String,
+    basic: Option<String>,
+    bearer: Option<String>,
+}
+
+fn registry_client(image: ImageRef, basic: Option<String>, insecure_registries: &[String]) -> RegistryClient {
+    let host = image.registry.split(':').next().unwrap_or("");
+    let plain = host == "localhost"
+        || host == "127.0.0.1"
+        || insecure_registries.iter().any(|r| registry_host_key(r) == image.registry);
+    let scheme = if plain { "http" } else { "https" };
+    RegistryClient {
+        base_url: format!("{}://{}", scheme, image.registry),
+        image,
+        basic,
+        bearer: None,
+    }
+}
+
+impl RegistryClient {
+    fn authorization(&self) -> Option<String> {
+        match (&self.bearer, &self.basic) {
+            (Some(token), _) => Some(format!("Authorization: Bearer {}", token)),
+            (None, Some(basic)) => Some(format!("Authorization: Basic {}", basic)),
+            (None, None) => None,
+        }
+    }
+
+    fn send(&self, head: bool, url: &str, accept: &[&str]) -> Result<RegistryResponse, String> {
+        let mut headers: Vec<String> = accept.iter().map(|a| format!("Accept: {}", a)).collect();
+        headers.extend(self.authorization());
+        let args: &[&str] = if head { &["-I", "--url", url] } else { &["--url", url] };
+        run_curl(args, &headers)
+    }
+
+    /// Request `path` (relative to the registry root), fetching a Bearer
+    /// token on the first 401 that asks for one.
+    fn request(&mut self, head: bool, path: &str, accept: &[&str]) -> Result<RegistryResponse, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let resp = self.send(head, &url, accept)?;
+        let challenge = resp.header("www-authenticate").unwrap_or_default();
+        if resp.status != 401 || self.bearer.is_some() || !challenge.to_ascii_lowercase().starts_with("bearer ") {
+            return Ok(resp);
+        }
+        self.bearer = Some(self.fetch_token(challenge)?);
+        self.send(head, &url, accept)
+    }
+
+    fn fetch_token(&self, challenge: &str) -> Result<String, String> {
+        let params = parse_auth_challenge(challenge);
+        let param = |k: &str| params.iter().find(|(key, _)| key == k).map(|(_, v)| v.clone());
+        let realm = param("realm").ok_or("Registry token challenge has no realm")?;
+        if !realm.starts_with("https://") && !realm.starts_with("http://") {
+            return Err(format!("Registry token realm is not an http(s) URL: {}", realm));
+        }
+        let service = format!("service={}", param("service").unwrap_or_default());
+        let scope = format!("scope={}", param("scope")
+            .unwrap_or_else(|| format!("repository:{}:pull", self.image.repository)));
+        let headers: Vec<String> = self.basic.iter().map(|b| format!("Authorization: Basic {}", b)).collect();
+        let resp = run_curl(
+            &["-G", "--url", &realm, "--data-urlencode", &service, "--data-urlencode", &scope],
+            &headers,
+        )?;
+        if resp.status != 200 {
+            return Err(self.error(&resp));
+        }
+        let json: serde_json::Value = serde_json::from_str(&resp.body)
+            .map_err(|e| format!("Invalid token response from {}: {}", realm, e))?;
+        json["token"].as_str()
+            .or_else(|| json["access_token"].as_str())
+            .map(|t| t.to_string())
+            .ok_or_else(|| format!("No token in response from {}", realm))
+    }
+
+    fn error(&self, resp: &RegistryResponse) -> String {
+        let image = self.image.name();
+        match resp.status {
+            401 if self.basic.is_some() => format!("{}: credentials rejected by {}", image, self.image.registry),
+            401 | 403 => format!(
+                "{}: access denied by {} — no credentials in the pull secrets or the docker config",
+                image, self.image.registry
+            ),
+            404 => format!("{}:{} not found on {}", image, self.image.reference(), self.image.registry),
+            status => {
+                let detail = serde_json::from_str::<serde_json::Value>(&resp.body)
+                    .ok()
+                    .and_then(|j| j["errors"][0]["message"].as_str().map(|m| m.to_string()))
+                    .unwrap_or_else(|| resp.body.trim().chars().take(200).collect());
+                format!("{}: {} returned HTTP {} {}", image, self.image.registry, status, detail)
+            }
+        }
+    }
+
+    /// Every tag of the repository, following `Link` pagination.
+    fn list_tags(&mut self) -> Result<Vec<String>, String> {
+        let mut tags = Vec::new();
+        let mut path = format!("/v2/{}/tags/list?n=1000", self.image.repository);
+        for _ in 0..50 {
+            let resp = self.request(false, &path, &["application/json"])?;
+            if resp.status != 200 {
+                return Err(self.error(&resp));
+            }
+            let json: serde_json::Value = serde_json::from_str(&resp.body)
+                .map_err(|e| format!("Invalid tag list from {}: {}", self.image.registry, e))?;
+            tags.extend(json["tags"].as_array().into_iter().flatten()
+                .filter_map(|t| t.as_str().map(|s| s.to_string())));
+            // Link: </v2/<repo>/tags/list?last=x&n=1000>; rel="next" — the
+            // target may also be an absolute URL on the same registry
+            match resp.header("link").and_then(|l| l.split_once('<')).and_then(|(_, l)| l.split_once('>')) {
+                Some((next, _)) if next.starts_with('/') => path = next.to_string(),
+                Some((next, _)) => match next.strip_prefix(&self.base_url).filter(|p| p.starts_with('/')) {
+                    Some(next) => path = next.to_string(),
+                    None => return Err(format!("{} paginates tags to another host: {}", self.image.registry, next)),
+                },
+                None => break,
+            }
+        }
+        Ok(tags)
+    }
+
+    /// `sha256:…` digest the image's tag currently points at.
+    fn resolve_digest(&mut self) -> Result<String, String> {
+        let path = format!("/v2/{}/manifests/{}", self.image.repository, self.image.reference());
+        let resp = self.request(true, &path, MANIFEST_ACCEPT)?;
+        if resp.status != 200 {
+            return Err(self.error(&resp));
+        }
+        resp.header("docker-content-digest")
+            .map(|d| d.to_string())
+            .ok_or_else(|| format!("{} did not return a digest for {}", self.image.registry, self.image.name()))
+    }
+}
+
+/// Client for `image` with credentials from the given pull secrets in
+/// `namespace`, or the local docker config.
+fn image_registry_client(
+    image: &str,
+    namespace: &str,
+    pull_secrets: &[String],
+    project: &ProjectConfig,
+) -> Result<RegistryClient, String> {
+    let image = parse_image_ref(image)?;
+    let auth = registry_auth(&image, namespace, pull_secrets);
+    Ok(registry_client(image, auth, &project.insecure_registries))
+}
+
+fn pinned_reference(image: &ImageRef, digest: &str) -> String {
+    match &image.tag {
+        Some(tag) => format!("{}:{}@{}", image.name(), tag, digest),
+        None => format!("{}@{}", image.name(), digest),
+    }
+}
+
+/// Version-like tag split into its numbers and a shape key: an optional
+/// `v`, 1–3 numeric parts and an optional `-variant`. Tags only compare
+/// within the same shape, so `1.25-alpine` is never "updated" to `1.27`
+/// or `1.27.1-alpine`.
+fn parse_tag_version(tag: &str) -> Option<(Vec<u64>, String)> {
+    let (prefix, rest) = match tag.strip_prefix('v') {
+        Some(rest) => ("v", rest),
+        None => ("", tag),
+    };
+    let (version, variant) = rest.split_once('-').unwrap_or((rest, ""));
+    let numbers: Vec<u64> = version.split('.').map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    if numbers.len() > 3 {
+        return None;
+    }
+    Some((numbers.clone(), format!("{}{}-{}", prefix, numbers.len(), variant)))
+}
+
+/// Tags newer than `current` with the same shape, newest first.
+fn newer_tags(current: &str, tags: &[String]) -> Vec<String> {
+    let Some((current_version, shape)) = parse_tag_version(current) else {
+        return vec![];
+    };
+    let mut newer: Vec<(Vec<u64>, &String)> = tags
+        .iter()
+        .filter_map(|t| parse_tag_version(t).map(|(v, s)| (v, s, t)))
+        .filter(|(v, s, _)| *s == shape && *v > current_version)
+        .map(|(v, _, t)| (v, t))
+        .collect();
+    newer.sort_by(|a, b| b.0.cmp(&a.0));
+    newer.into_iter().map(|(_, t)| t.clone()).collect()
+}
+
+/// Version-like tags newest first, then the rest alphabetically.
+fn sort_tags(tags: &mut [String]) {
+    tags.sort_by(|a, b| match (parse_tag_version(a), parse_tag_version(b)) {
+        (Some((va, _)), Some((vb, _))) => vb.cmp(&va).then_with(|| a.cmp(b)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.cmp(b),
+    });
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImageDigest {
+    pub image: String,
+    pub digest: String,
+    /// `name:tag@sha256:…` reference to put in a manifest — the tag stays
+    /// for readability, the runtime pulls by digest
+    pub pinned: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImageUpdate {
+    pub node_id: String,
+    pub node_label: String,
+    pub namespace: String,
+    pub file_path: String,
+    pub container: String,
+    pub image: String,
+    pub current_tag: Option<String>,
+    /// Newest tag of the same shape, if newer than the current one
+    pub latest_tag: Option<String>,
+    pub newer_tags: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Tags of an image's repository, newest versions first. `pull_secret`
+/// (in `namespace`) supplies credentials; otherwise the local docker config.
+#[tauri::command]
+async fn list_image_tags(
+    app: tauri::AppHandle,
+    image: String,
+    namespace: Option<String>,
+    pull_secret: Option<String>,
+) -> Result<Vec<String>, String> {
+    let project = active_project_config(&app.state::<ProjectRootState>())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let namespace = namespace.unwrap_or_else(|| "default".to_string());
+        let secrets: Vec<String> = pull_secret.into_iter().collect();
+        let mut client = image_registry_client(&image, &namespace, &secrets, &project)?;
+        let mut tags = client.list_tags()?;
+        sort_tags(&mut tags);
+        Ok(tags)
+    })
+    .await
+    .map_err(|e| format!("spawn error: {}", e))?
+}
+
+/// Digest an image's tag currently points at.
+#[tauri::command]
+async fn resolve_image_digest(
+    app: tauri::AppHandle,
+    image: String,
+    namespace: Option<String>,
+    pull_secret: Option<String>,
+) -> Result<ImageDigest, String> {
+    let project = active_project_config(&app.state::<ProjectRootState>())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let namespace = namespace.unwrap_or_else(|| "default".to_string());
+        let secrets: Vec<String> = pull_secret.into_iter().collect();
+        let mut client = image_registry_client(&image, &namespace, &secrets, &project)?;
+        let digest = client.resolve_digest()?;
+        Ok(ImageDigest { pinned: pinned_reference(&client.image, &digest), image, digest })
+    })
+    .await
+    .map_err(|e| format!("spawn error: {}", e))?
+}
+
+/// For every container image of the project's workloads, the tags newer
+/// than the one in use. Credentials come from the secrets each workload
+/// references (non-docker ones are skipped) or the local docker config;
+/// each repository is queried once.
+#[tauri::command]
+async fn check_image_updates(app: tauri::AppHandle) -> Result<Vec<ImageUpdate>, String> {
+    let roots = app.state::<ProjectRootState>();
+    let root = project_root(&roots)?;
+    let project = active_project_config(&roots)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut nodes = Vec::new();
+        let mut errors = Vec::new();
+        scan_dir(&root, &mut nodes, &mut errors);
+
+        let mut tag_cache: std::collections::HashMap<(String, String), Result<Vec<String>, String>> = std::collections::HashMap::new();
+        let mut updates = Vec::new();
+        for node in &nodes {
+            let containers: Vec<(String, String)> = if node.containers.is_empty() {
+                vec![(node.label.clone(), node.image.clone())]
+            } else {
+                node.containers.iter().map(|c| (c.name.clone(), c.image.clone())).collect()
+            };
+            let secrets: Vec<String> = node.secrets.iter().map(|s| s.name.clone()).collect();
+            for (container, image) in containers {
+                // helm nodes carry `helm:<chart>/<version>` instead of an image
+                if image.is_empty() || image.starts_with("helm:") || image.contains("{{") {
+                    continue;
+                }
+                let mut update = ImageUpdate {
+                    node_id: node.id.clone(),
+                    node_label: node.label.clone(),
+                    namespace: node.namespace.clone(),
+                    file_path: node.file_path.clone(),
+                    container,
+                    image: image.clone(),
+                    current_tag: None,
+                    latest_tag: None,
+                    newer_tags: vec![],
+                    error: None,
+                };
+                let parsed = match parse_image_ref(&image) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        update.error = Some(e);
+                        updates.push(update);
+                        continue;
+                    }
+                };
+                update.current_tag = parsed.tag.clone();
+                // digest-pinned or non-version tags have nothing to compare against
+                let Some(tag) = parsed.tag.clone().filter(|t| parse_tag_version(t).is_some()) else {
+                    updates.push(update);
+                    continue;
+                };
+                let key = (parsed.registry.clone(), parsed.repository.clone());
+                let tags = tag_cache.entry(key).or_insert_with(|| {
+                    let auth = registry_auth(&parsed, &node.namespace, &secrets);
+                    registry_client(parsed, auth, &project.insecure_registries).list_tags()
+                });
+                match tags {
+                    Ok(tags) => {
+                        update.newer_tags = newer_tags(&tag, tags);
+                        update.latest_tag = update.newer_tags.first().cloned();
+                    }
+                    Err(e) => update.error = Some(e.clone()),
+                }
+                updates.push(update);
+            }
+        }
+        Ok(updates)
+    })
+    .await
+    .map_err(|e| format!("spawn error: {}", e))?
+}
+
+// ── Digest pinning ──
+
+/// Resolve every tag-referenced image in a manifest stream to its digest.
+/// Pull secrets referenced by the manifests (in `namespace`) and the local
+/// docker config supply credentials. Any image that cannot be resolved fails
+/// the whole pin — a partly pinned deploy is not reproducible either.
+fn resolve_image_pins(yaml: &str, namespace: &str, project: &ProjectConfig) -> Result<Vec<ImageDigest>, String> {
+    let mut images = extract_images(yaml);
+    images.retain(|i| !i.contains('@'));
+    images.sort();
+    images.dedup();
+    let secrets = extract_secret_refs(yaml);
+    let mut pins = Vec::new();
+    let mut errors = Vec::new();
+    for image in images {
+        let resolved = image_registry_client(&image, namespace, &secrets, project)
+            .and_then(|mut client| client.resolve_digest().map(|d| (pinned_reference(&client.image, &d), d)));
+        match resolved {
+            Ok((pinned, digest)) => pins.push(ImageDigest { image, digest, pinned }),
+            Err(e) => errors.push(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(pins)
+    } else {
+        Err(format!("Cannot pin images to digests:\n{}", errors.join("\n")))
+    }
+}
+
+/// Rewrite `image:` lines whose value has a pin, keeping indent and quotes.
+fn pin_manifest_images(yaml: &str, pins: &[ImageDigest]) -> String {
+    let mut out: Vec<String> = yaml
+        .lines()
+        .map(|line| {
+            let Some(pos) = line.find("image:") else {
+                return line.to_string();
+            };
+            let (head, value) = line.split_at(pos + "image:".len());
+            if !head[..pos].trim().trim_start_matches('-').trim().is_empty() {
+                return line.to_string();
+            }
+            let value = value.trim();
+            let quote = if value.starts_with('"') || value.starts_with('\'') { &value[..1] } else { "" };
+            let bare = value.trim_matches('"').trim_matches('\'');
+            match pins.iter().find(|p| p.image == bare) {
+                Some(pin) => format!("{} {}{}{}", head, quote, pin.pinned, quote),
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    if yaml.ends_with('\n') {
+        out.push(String::new());
+    }
+    out.join("\n")
+}
+
+/// Write pinned image references into the plain (not SOPS-encrypted)
+/// manifests under `dir`. Returns the files that changed.
+fn pin_manifest_files(dir: &Path, pins: &[ImageDigest]) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    scan_all_yaml_paths(dir, &mut files);
+    files.retain(|f| f.ends_with(".yaml") || f.ends_with(".yml"));
+    let mut changed = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file)
+            .map_err(|e| format!("Cannot read {}: {}", file, e))?;
+        if is_sops_encrypted(&content) {
+            continue;
+        }
+        let pinned = pin_manifest_images(&content, pins);
+        if pinned != content {
+            fs::write(&file, pinned).map_err(|e| format!("Cannot write {}: {}", file, e))?;
+            changed.push(file);
+        }
+    }
+    Ok(changed)
+}
+
+/// Merge `over` into `base` the way Helm coalesces values: maps merge key
+/// by key, anything else replaces, and null deletes.
+fn merge_helm_values(base: &mut serde_json::Value, over: &serde_json::Value) {
+    let (Some(base_map), Some(over_map)) = (base.as_object_mut(), over.as_object()) else {
+        if !over.is_null() {
+            *base = over.clone();
+        }
+        return;
+    };
+    for (key, value) in over_map {
+        match base_map.get_mut(key) {
+            _ if value.is_null() => {
+                base_map.remove(key);
+            }
+            Some(existing) if existing.is_object() && value.is_object() => merge_helm_values(existing, value),
+            _ => {
+                base_map.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Image fields of `values` that produce one of the pinned images, as
+/// (path, pinned value): `*image*: "name:tag"` strings get the pinned
+/// reference, `repository`/`tag` (+ `registry`) maps a `tag@sha256:…` tag.
+/// Lists are not searched — `--set` on a list item replaces the whole list.
+fn collect_image_overrides(
+    values: &serde_json::Value,
+    path: &mut Vec<String>,
+    pins: &[ImageDigest],
+    out: &mut Vec<(Vec<String>, String)>,
+) {
+    let Some(map) = values.as_object() else { return };
+    if let Some(repository) = map.get("repository").and_then(|r| r.as_str()) {
+        let tag = map.get("tag").and_then(|t| t.as_str()).unwrap_or("");
+        let registry = map.get("registry").and_then(|r| r.as_str()).filter(|r| !r.is_empty());
+        let name = match registry {
+            Some(registry) => format!("{}/{}", registry, repository),
+            None => repository.to_string(),
+        };
+        // an empty tag falls back to the chart's appVersion — take the rendered one
+        let pin = pins.iter().find(|p| {
+            p.image.strip_prefix(&name).and_then(|t| t.strip_prefix(':')).is_some_and(|t| tag.is_empty() || t == tag)
+        });
+        if let Some(pin) = pin {
+            let rendered_tag = &pin.image[name.len() + 1..];
+            path.push("tag".to_string());
+            out.push((path.clone(), format!("{}@{}", rendered_tag, pin.digest)));
+            path.pop();
+            return;
+        }
+    }
+    for (key, value) in map {
+        path.push(key.clone());
+        match value.as_str() {
+            Some(image) if key.to_ascii_lowercase().contains("image") => {
+                if let Some(pin) = pins.iter().find(|p| p.image == image) {
+                    out.push((path.clone(), pin.pinned.clone()));
+                }
+            }
+            _ => collect_image_overrides(value, path, pins, out),
+        }
+        path.pop();
+    }
+}
+
+/// Pins for the chart's images and the `--set-json` flags that make Helm
+/// render them. The chart is rendered with its values (decrypted in memory)
+/// to find and resolve the images, its values — defaults merged with the
+/// release's — are read from a `helm upgrade --dry-run`, and the overrides
+/// are checked by rendering again: a chart that hard-codes an image or
+/// builds it in a way the values don't cover is refused, not half pinned.
+/// `--set-json` needs Helm 3.10+.
+fn helm_image_overrides(
+    release: &str,
+    namespace: &str,
+    values_path: &str,
+    helm_dir: &Path,
+    project: &ProjectConfig,
+) -> Result<(Vec<ImageDigest>, Vec<String>), String> {
+    let render = |extra: &[String]| {
+        let mut args = vec!["template", release, ".", "--namespace", namespace, "--include-crds"];
+        args.extend(extra.iter().map(|a| a.as_str()));
+        match run_helm_with_values(&args, values_path, helm_dir, project) {
+            (out, _, true) => Ok(out),
+            (_, err, false) => Err(format!("helm template failed, cannot pin images: {}", err.trim())),
+        }
+    };
+    let pins = resolve_image_pins(&render(&[])?, namespace, project)?;
+    if pins.is_empty() {
+        return Ok((pins, vec![]));
+    }
+
+    let (out, err, ok) = run_helm_with_values(
+        &["upgrade", "--install", release, ".", "--namespace", namespace, "--dry-run", "--output", "json"],
+        values_path,
+        helm_dir,
+        project,
+    );
+    if !ok {
+        return Err(format!("Cannot read the chart's values to pin images: {}", err.trim()));
+    }
+    let dry_run: serde_json::Value = serde_json::from_str(&out)
+        .map_err(|e| format!("Cannot parse helm dry-run output: {}", e))?;
+    let mut values = dry_run["chart"]["values"].clone();
+    merge_helm_values(&mut values, &dry_run["config"]);
+
+    let mut found = Vec::new();
+    collect_image_overrides(&values, &mut Vec::new(), &pins, &mut found);
+    // --set paths split on `.`, `[`, `,` and `=`; a backslash escapes them
+    let escape = |key: &str| {
+        key.chars().fold(String::new(), |mut out, c| {
+            if "\\.[,=".contains(c) {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+    };
+    let overrides: Vec<String> = found
+        .iter()
+        .flat_map(|(path, value)| {
+            let path: Vec<String> = path.iter().map(|k| escape(k)).collect();
+            ["--set-json".to_string(), format!("{}={}", path.join("."), serde_json::Value::from(value.as_str()))]
+        })
+        .collect();
+
+    let still_unpinned: Vec<String> = extract_images(&render(&overrides)?)
+        .into_iter()
+        .filter(|image| pins.iter().any(|p| p.image == *image))
+        .collect();
+    if !still_unpinned.is_empty() {
+        return Err(format!(
+            "Cannot pin {} — the chart doesn't take {} from its values; deploy it without pinning",
+            still_unpinned.join(", "),
+            if still_unpinned.len() == 1 { "it" } else { "them" }
+        ));
+    }
+    Ok((pins, overrides))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnpinnedImage {
+    pub node_id: String,
+    pub node_label: String,
+    pub namespace: String,
+    pub file_path: String,
+    pub container: String,
+    pub image: String,
+}
+
+/// Containers of the project's workloads whose image is referenced by tag
+/// rather than digest. Helm releases are checked through their rendered/
+/// manifests (as of the last deploy or render).
+#[tauri::command]
+fn list_unpinned_images(roots: tauri::State<ProjectRootState>) -> Result<Vec<UnpinnedImage>, String> {
+    let root = project_root(&roots)?;
+    let mut nodes = Vec::new();
+    let mut errors = Vec::new();
+    scan_dir(&root, &mut nodes, &mut errors);
+
+    let mut unpinned = Vec::new();
+    for node in &nodes {
+        let mut containers: Vec<(String, String, String)> = node.containers.iter()
+            .map(|c| (node.file_path.clone(), c.name.clone(), c.image.clone()))
+            .collect();
+        if let Some(helm) = &node.helm {
+            for (file, doc) in project_yaml_docs(Path::new(&helm.rendered_dir)) {
+                containers.extend(extract_containers(&doc).into_iter().map(|c| (file.clone(), c.name, c.image)));
+            }
+        }
+        unpinned.extend(containers.into_iter()
+            .filter(|(_, _, image)| !image.is_empty() && !image.contains('@') && !image.contains("{{"))
+            .map(|(file_path, container, image)| UnpinnedImage {
+                node_id: node.id.clone(),
+                node_label: node.label.clone(),
+                namespace: node.namespace.clone(),
+                file_path,
+                container,
+                image,
+            }));
+    }
+    Ok(unpinned)
+}
+
+// ── Image pull secrets ──
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PullSecretInfo {
+    pub name: String,
+    pub namespace: String,
+    /// "kubernetes.io/dockerconfigjson" | "kubernetes.io/dockercfg"
+    pub secret_type: String,
+    /// Registry hosts the secret has credentials for
+    pub registries: Vec<String>,
+    /// Listed in `imagePullSecrets` of the namespace's default ServiceAccount
+    pub default_service_account: bool,
+}
+
+/// Docker config key for a registry. Docker Hub must be keyed by its v1
+/// index URL, which is what the kubelet looks up.
+fn docker_config_key(registry: &str) -> String {
+    let host = registry_host_key(registry);
+    if host == DOCKER_HUB_REGISTRY {
+        "https://index.docker.io/v1/".to_string()
+    } else {
+        host
+    }
+}
+
+fn pull_secret_yaml(namespace: &str, name: &str, registry: &str, auth: &str) -> String {
+    let config = serde_json::json!({ "auths": { docker_config_key(registry): { "auth": auth } } });
+    format!(
+        r#"apiVersion: v1
+kind: Secret
+metadata:
+  name: {name}
+  namespace: {ns}
+  labels:
+    managed-by: endfield
+type: kubernetes.io/dockerconfigjson
+data:
+  .dockerconfigjson: {data}
+"#,
+        name = name,
+        ns = namespace,
+        data = base64_encode(config.to_string().as_bytes()),
+    )
+}
+
+/// Create or update a docker-registry Secret for one registry. Credentials
+/// are `username` + `token`, or — with both omitted — the local docker
+/// config's entry for that registry (credential helpers included). Only that
+/// registry's credential is copied, never the whole config. The manifest is
+/// applied via stdin so the token does not appear in the process list.
+#[tauri::command]
+fn upsert_pull_secret(
+    roots: tauri::State<ProjectRootState>,
+    namespace: String,
+    name: String,
+    registry: String,
+    username: Option<String>,
+    token: Option<String>,
+) -> Result<String, String> {
+    let auth = match (username.filter(|u| !u.is_empty()), token.filter(|t| !t.is_empty())) {
+        (Some(user), Some(token)) => base64_encode(format!("{}:{}", user, token).as_bytes()),
+        (None, None) => local_docker_config()
+            .and_then(|config| docker_config_auth(&config, &registry_host_key(&registry)))
+            .ok_or_else(|| format!("No credentials for {} in the local docker config — run docker login first", registry))?,
+        _ => return Err("Both username and token are required".to_string()),
+    };
+    ensure_namespace(&namespace)?;
+    let (stdout, stderr, ok) = run_kubectl_stdin(
+        &["apply", "-f", "-"],
+        &pull_secret_yaml(&namespace, &name, &registry, &auth),
+    );
+    let result = if ok { Ok(stdout) } else { Err(stderr) };
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "upsert_pull_secret",
+        &namespace,
+        &format!("secret/{}", name),
+        format!("kubectl apply -f -  # docker-registry secret {} for {}", name, registry),
+        &result,
+    );
+    result
+}
+
+/// `imagePullSecrets` names of a ServiceAccount.
+fn service_account_pull_secrets(namespace: &str, service_account: &str) -> Result<Vec<String>, String> {
+    let out = run_kubectl(&[
+        "get", "serviceaccount", service_account, "-n", namespace,
+        "-o", "jsonpath={.imagePullSecrets[*].name}",
+    ])?;
+    Ok(out.split_whitespace().map(|s| s.to_string()).collect())
+}
+
+/// Docker-registry Secrets in a namespace with the registries they cover.
+#[tauri::command]
+fn list_pull_secrets(namespace: String) -> Result<Vec<PullSecretInfo>, String> {
+    let raw = run_kubectl(&["get", "secrets", "-n", &namespace, "-o", "json"])?;
+    let json: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| format!("Cannot parse secrets: {}", e))?;
+    let attached = service_account_pull_secrets(&namespace, "default").unwrap_or_default();
+    let mut secrets: Vec<PullSecretInfo> = json["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let secret_type = item["type"].as_str()?;
+            let key = match secret_type {
+                "kubernetes.io/dockerconfigjson" => ".dockerconfigjson",
+                "kubernetes.io/dockercfg" => ".dockercfg",
+                _ => return None,
+            };
+            let config: serde_json::Value = item["data"][key]
+                .as_str()
+                .and_then(base64_decode)
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .unwrap_or_default();
+            // .dockercfg is the bare auths map
+            let auths = if key == ".dockercfg" { &config } else { &config["auths"] };
+            let mut registries: Vec<String> = auths
+                .as_object()
+                .map(|a| a.keys().map(|k| registry_host_key(k)).collect())
+                .unwrap_or_default();
+            registries.sort();
+            registries.dedup();
+            let name = item["metadata"]["name"].as_str()?.to_string();
+            Some(PullSecretInfo {
+                default_service_account: attached.contains(&name),
+                name,
+                namespace: namespace.clone(),
+                secret_type: secret_type.to_string(),
+                registries,
+            })
+        })
+        .collect();
+    secrets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(secrets)
+}
+
+/// Add a pull secret to a ServiceAccount's `imagePullSecrets` (the
+/// namespace's `default` one unless given), so pods that don't list their
+/// own pull secrets can still pull from the registry.
+#[tauri::command]
+fn attach_pull_secret(
+    roots: tauri::State<ProjectRootState>,
+    namespace: String,
+    name: String,
+    service_account: Option<String>,
+) -> Result<String, String> {
+    let service_account = service_account.unwrap_or_else(|| "default".to_string());
+    run_kubectl(&["get", "secret", &name, "-n", &namespace, "-o", "name"])
+        .map_err(|_| format!("Secret {} not found in {}", name, namespace))?;
+    let mut names = service_account_pull_secrets(&namespace, &service_account)?;
+    if names.contains(&name) {
+        return Ok(format!("serviceaccount/{} already uses {}", service_account, name));
+    }
+    names.push(name.clone());
+    // imagePullSecrets has no merge key, so the whole list is patched in
+    let patch = serde_json::json!({
+        "imagePullSecrets": names.iter().map(|n| serde_json::json!({ "name": n })).collect::<Vec<_>>()
+    })
+    .to_string();
+    let result = run_kubectl(&["patch", "serviceaccount", &service_account, "-n", &namespace, "-p", &patch]);
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "attach_pull_secret",
+        &namespace,
+        &format!("serviceaccount/{}", service_account),
+        format!("kubectl patch serviceaccount {} -n {} -p '{}'", service_account, namespace, patch),
+        &result,
+    );
+    result
+}
+
+// ─── Ingress Nginx Types ──────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IngressNginxStatus {
+    pub ingress_class_name: String,
+    pub controller_service_name: String,
+    pub endpoint: Option<String>,
+    pub ready: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IngressRoute {
+    pub route_id: String,
+    pub field_id: String,
+    pub target_namespace: String,
+    pub target_service: String,
+    pub target_port_number: Option<u32>,
+    pub target_port_name: Option<String>,
+    pub host: Option<String>,
+    pub path: String,
+    pub path_type: String,
+    pub tls_secret: Option<String>,
+    pub tls_hosts: Option<Vec<String>>,
+    pub annotations: Option<Vec<(String, String)>>,
+    pub ingress_class_name: String,
+    pub ingress_name: String,
+    pub ingress_namespace: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngressRouteResult {
+    pub route_id: String,
+    pub ingress_name: String,
+    pub namespace: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiscoveredRoute {
+    pub route_id: String,
+    pub field_id: String,
+    pub ingress_name: String,
+    pub ingress_namespace: String,
+    pub host: Option<String>,
+    pub path: String,
+    pub path_type: String,
+    pub target_service: String,
+    pub target_namespace: String,
+    pub target_port_number: Option<u32>,
+    pub target_port_name: Option<String>,
+    pub ingress_class_name: String,
+    pub tls_secret: Option<String>,
+    pub address: Option<String>,
 }
 
+// ─── Ingress Nginx Commands ───────────────────────────────────────────────────
+
 #[tauri::command]
-fn kubectl_apply_async(path: String) -> Result<String, String> {
-    std::thread::spawn(move || {
-        let _ = run_kubectl(&["apply", "-f", &path]);
-    });
-    Ok("started".to_string())
+fn detect_ingress_nginx(namespace: String, release_name: String) -> IngressNginxStatus {
+    let ingress_class = run_kubectl(&[
+        "get", "ingressclass",
+        "-l", &format!("app.kubernetes.io/instance={}", release_name),
+        "-o", "jsonpath={.items[0].metadata.name}",
+    ])
+    .unwrap_or_else(|_| "nginx".to_string());
+    let ingress_class = if ingress_class.is_empty() { "nginx".to_string() } else { ingress_class };
+
+    let svc_name = run_kubectl(&[
+        "get", "service", "-n", &namespace,
+        "-l", &format!("app.kubernetes.io/instance={}", release_name),
+        "-o", "jsonpath={.items[0].metadata.name}",
+    ])
+    .unwrap_or_default();
+
+    let endpoint = if !svc_name.is_empty() {
+        let lb = run_kubectl(&[
+            "get", "service", &svc_name, "-n", &namespace,
+            "-o", "jsonpath={.status.loadBalancer.ingress[0].ip}",
+        ]).ok().filter(|s| !s.is_empty());
+        if lb.is_some() { lb } else {
+            run_kubectl(&[
+                "get", "service", &svc_name, "-n", &namespace,
+                "-o", "jsonpath={.status.loadBalancer.ingress[0].hostname}",
+            ]).ok().filter(|s| !s.is_empty())
+        }
+    } else { None };
+
+    IngressNginxStatus {
+        ingress_class_name: ingress_class,
+        controller_service_name: svc_name.clone(),
+        endpoint,
+        ready: !svc_name.is_empty(),
+    }
 }
 
-// ─── Deploy Image ─────────────────────────────────────────────────────────────
+fn generate_ingress_yaml(route: &IngressRoute) -> String {
+    let port_spec = if let Some(n) = route.target_port_number {
+        format!("number: {}", n)
+    } else if let Some(name) = &route.target_port_name {
+        format!("name: {}", name)
+    } else {
+        "number: 80".to_string()
+    };
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct DeployEnvVar {
-    pub key: String,
-    pub value: String,
+    let host_rules = if let Some(host) = &route.host {
+        format!(
+"  rules:\n    - host: {host}\n      http:\n        paths:\n          - path: {path}\n            pathType: {pt}\n            backend:\n              service:\n                name: {svc}\n                port:\n                  {port}\n",
+            host=host, path=route.path, pt=route.path_type,
+            svc=route.target_service, port=port_spec)
+    } else {
+        format!(
+"  rules:\n    - http:\n        paths:\n          - path: {path}\n            pathType: {pt}\n            backend:\n              service:\n                name: {svc}\n                port:\n                  {port}\n",
+            path=route.path, pt=route.path_type,
+            svc=route.target_service, port=port_spec)
+    };
+
+    let tls_block = match (&route.tls_secret, &route.tls_hosts) {
+        (Some(secret), Some(hosts)) if !hosts.is_empty() => {
+            let hl: String = hosts.iter().map(|h| format!("        - {}\n", h)).collect();
+            format!("  tls:\n    - hosts:\n{}      secretName: {}\n", hl, secret)
+        }
+        _ => String::new(),
+    };
+
+    let mut ann = format!(
+        "    app.kubernetes.io/managed-by: endfield\n    endfield.io/fieldId: {}\n    endfield.io/routeId: {}\n",
+        route.field_id, route.route_id
+    );
+    if let Some(anns) = &route.annotations {
+        for (k, v) in anns {
+            // Always quote values — prevents YAML from parsing "true"/"false"/numbers as non-strings
+            ann.push_str(&format!("    {}: \"{}\"\n", k, v.replace('"', "\\\"")));
+        }
+    }
+
+    format!(
+"apiVersion: networking.k8s.io/v1\nkind: Ingress\nmetadata:\n  name: {name}\n  namespace: {ns}\n  labels:\n    app.kubernetes.io/managed-by: endfield\n    endfield.io/fieldId: {fid}\n    endfield.io/routeId: {rid}\n  annotations:\n{ann}spec:\n  ingressClassName: {class}\n{tls}{rules}",
+        name=route.ingress_name, ns=route.ingress_namespace,
+        fid=route.field_id, rid=route.route_id, ann=ann,
+        class=route.ingress_class_name, tls=tls_block, rules=host_rules)
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct DeployPort {
-    #[serde(rename = "containerPort")]
-    pub container_port: u16,
-    pub name: Option<String>,
+#[tauri::command]
+async fn apply_ingress_route(app: tauri::AppHandle, route: IngressRoute) -> IngressRouteResult {
+    let root = project_root(&app.state::<ProjectRootState>()).ok();
+    tauri::async_runtime::spawn_blocking(move || {
+        let yaml = generate_ingress_yaml(&route);
+        let _ = ensure_namespace(&route.ingress_namespace);
+        let applied = kubectl_apply_manifest(&yaml, &route.ingress_namespace);
+        record_result_audit(
+            root.as_deref(),
+            "apply_ingress_route",
+            &route.ingress_namespace,
+            &route.ingress_name,
+            "kubectl apply --server-side --field-manager=endfield --force-conflicts -f -".to_string(),
+            &applied,
+        );
+        match applied {
+            Ok(out) => IngressRouteResult {
+                route_id: route.route_id, ingress_name: route.ingress_name,
+                namespace: route.ingress_namespace, stdout: out,
+                stderr: String::new(), success: true,
+            },
+            Err(e) => IngressRouteResult {
+                route_id: route.route_id, ingress_name: route.ingress_name,
+                namespace: route.ingress_namespace, stdout: String::new(),
+                stderr: e, success: false,
+            },
+        }
+    }).await.unwrap_or_else(|e| IngressRouteResult {
+        route_id: String::new(), ingress_name: String::new(),
+        namespace: String::new(), stdout: String::new(),
+        stderr: format!("spawn error: {}", e), success: false,
+    })
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct DeployResources {
-    #[serde(rename = "cpuRequest")]
-    pub cpu_request: Option<String>,
-    #[serde(rename = "memRequest")]
-    pub mem_request: Option<String>,
-    #[serde(rename = "cpuLimit")]
-    pub cpu_limit: Option<String>,
-    #[serde(rename = "memLimit")]
-    pub mem_limit: Option<String>,
+/// Preview `apply_ingress_route` with the same server-side apply flags.
+#[tauri::command]
+async fn apply_ingress_route_dry_run(route: IngressRoute) -> DryRunResult {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut result = DryRunResult::default();
+        dry_run_missing_namespace(&route.ingress_namespace, &mut result);
+        kubectl_dry_run_manifest(&generate_ingress_yaml(&route), &route.ingress_namespace, &mut result);
+        result.success = result.rejected.is_empty();
+        result
+    }).await.unwrap_or_else(|e| DryRunResult {
+        error: Some(format!("spawn error: {}", e)),
+        ..Default::default()
+    })
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeployImageRequest {
-    pub namespace: String,
-    pub name: String,
-    pub image: String,
-    pub replicas: u32,
-    pub env: Vec<DeployEnvVar>,
-    #[serde(rename = "secretEnv")]
-    pub secret_env: Vec<DeployEnvVar>,
-    pub ports: Vec<DeployPort>,
-    #[serde(rename = "serviceType", default = "default_service_type")]
-    pub service_type: String,
-    pub resources: Option<DeployResources>,
-    #[serde(rename = "imagePullSecret")]
-    pub image_pull_secret: Option<String>,
-    #[serde(rename = "createNamespace", default)]
-    pub create_namespace: bool,
+#[tauri::command]
+fn get_ingress_route_yaml(route: IngressRoute) -> String {
+    generate_ingress_yaml(&route)
 }
 
-fn default_service_type() -> String { "ClusterIP".to_string() }
+fn delete_ingress_route_key(ingress_name: &str, namespace: &str) -> String {
+    format!("delete_ingress_route|{}|{}", namespace, ingress_name)
+}
 
-#[derive(Debug, Serialize)]
-pub struct DeployImageManifests {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub namespace: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub secret: Option<String>,
-    pub deployment: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub service: Option<String>,
+/// Preview what `delete_ingress_route` would delete.
+#[tauri::command]
+fn plan_delete_ingress_route(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    ingress_name: String,
+    namespace: String,
+) -> Result<DestructivePlan, String> {
+    Ok(plan_destructive(
+        &confirmations,
+        &active_project_config(&roots)?,
+        "delete_ingress_route",
+        delete_ingress_route_key(&ingress_name, &namespace),
+        &namespace,
+        affected_object("Ingress", &ingress_name, &namespace)?,
+    ))
 }
 
-#[derive(Debug, Serialize)]
-pub struct DeployImageResult {
-    pub success: bool,
-    #[serde(rename = "deploymentName")]
-    pub deployment_name: String,
-    #[serde(rename = "secretName")]
-    pub secret_name: Option<String>,
-    #[serde(rename = "serviceName")]
-    pub service_name: Option<String>,
-    pub namespace: String,
-    pub stdout: String,
-    pub stderr: String,
-    pub error: Option<String>,
-    pub manifests: DeployImageManifests,
+/// Protected contexts/namespaces require a token from plan_delete_ingress_route.
+#[tauri::command]
+fn delete_ingress_route(
+    roots: tauri::State<ProjectRootState>,
+    confirmations: tauri::State<ConfirmationState>,
+    ingress_name: String,
+    namespace: String,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    check_confirmation(
+        &confirmations,
+        &active_project_config(&roots)?,
+        &delete_ingress_route_key(&ingress_name, &namespace),
+        &namespace,
+        confirmation_token.as_deref(),
+        || affected_object("Ingress", &ingress_name, &namespace),
+    )?;
+    let result = run_kubectl(&["delete", "ingress", &ingress_name, "-n", &namespace, "--ignore-not-found=true"]);
+    record_result_audit(
+        project_root(&roots).ok().as_deref(),
+        "delete_ingress_route",
+        &namespace,
+        &ingress_name,
+        format!("kubectl delete ingress {} -n {} --ignore-not-found=true", ingress_name, namespace),
+        &result,
+    );
+    result
 }
 
-// ── Manifest generators ────────────────────────────────────────────────────────
+#[tauri::command]
+fn discover_ingress_routes() -> Vec<DiscoveredRoute> {
+    let items_raw = match run_kubectl(&[
+        "get", "ingress", "--all-namespaces",
+        "-l", "app.kubernetes.io/managed-by=endfield",
+        "--no-headers", "-o",
+        "custom-columns=NAMESPACE:.metadata.namespace,NAME:.metadata.name,CLASS:.spec.ingressClassName",
+    ]) {
+        Ok(o) => o,
+        Err(_) => return vec![],
+    };
 
-fn gen_image_namespace(ns: &str) -> String {
-    format!(
-"apiVersion: v1
-kind: Namespace
-metadata:
-  name: {ns}
-  labels:
-    app.kubernetes.io/managed-by: endfield
-    endfield/type: image-deploy
-"
-    )
+    let mut routes = Vec::new();
+    for line in items_raw.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 { continue; }
+        let ns = parts[0];
+        let name = parts[1];
+        let class = parts.get(2).copied().unwrap_or("nginx");
+
+        let field_id = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.metadata.annotations.endfield\\.io/fieldId}"])
+            .unwrap_or_default();
+        let route_id = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.metadata.annotations.endfield\\.io/routeId}"])
+            .unwrap_or_default();
+        if field_id.is_empty() || route_id.is_empty() { continue; }
+
+        let path = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.rules[0].http.paths[0].path}"])
+            .unwrap_or_else(|_| "/".to_string());
+        let path_type = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.rules[0].http.paths[0].pathType}"])
+            .unwrap_or_else(|_| "Prefix".to_string());
+        let host = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.rules[0].host}"])
+            .ok().filter(|s| !s.is_empty());
+        let svc = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.rules[0].http.paths[0].backend.service.name}"])
+            .unwrap_or_default();
+        let port_num: Option<u32> = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.rules[0].http.paths[0].backend.service.port.number}"])
+            .ok().and_then(|s| s.parse().ok());
+        let port_name = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.rules[0].http.paths[0].backend.service.port.name}"])
+            .ok().filter(|s| !s.is_empty());
+        let tls_secret = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.spec.tls[0].secretName}"])
+            .ok().filter(|s| !s.is_empty());
+        let address = run_kubectl(&["get", "ingress", name, "-n", ns,
+            "-o", "jsonpath={.status.loadBalancer.ingress[0].ip}"])
+            .ok().filter(|s| !s.is_empty());
+
+        routes.push(DiscoveredRoute {
+            route_id, field_id, ingress_name: name.to_string(),
+            ingress_namespace: ns.to_string(), host,
+            path: if path.is_empty() { "/".to_string() } else { path },
+            path_type: if path_type.is_empty() { "Prefix".to_string() } else { path_type },
+            target_service: svc, target_namespace: ns.to_string(),
+            target_port_number: port_num, target_port_name: port_name,
+            ingress_class_name: class.to_string(), tls_secret, address,
+        });
+    }
+    routes
 }
 
-fn gen_image_secret(name: &str, ns: &str, vars: &[DeployEnvVar]) -> String {
-    let secret_name = format!("{}-secrets", name);
-    let data: String = vars.iter()
-        .map(|e| format!("  {}: \"{}\"\n", e.key, e.value.replace('"', "\\\"")))
-        .collect();
-    format!(
-"apiVersion: v1
-kind: Secret
-metadata:
-  name: {secret_name}
-  namespace: {ns}
-  labels:
-    app.kubernetes.io/name: {name}
-    app.kubernetes.io/managed-by: endfield
-    endfield/type: image-deploy
-    endfield/namespace: {ns}
-type: Opaque
-stringData:
-{data}"
-    )
+#[tauri::command]
+fn list_services_in_namespace(namespace: String) -> Vec<(String, Vec<String>)> {
+    let raw = run_kubectl(&[
+        "get", "services", "-n", &namespace, "--no-headers",
+        "-o", "custom-columns=NAME:.metadata.name,PORTS:.spec.ports[*].port",
+    ]).unwrap_or_default();
+    raw.lines().filter_map(|line| {
+        let mut parts = line.splitn(2, char::is_whitespace);
+        let name = parts.next()?.trim().to_string();
+        if name.is_empty() || name == "<none>" { return None; }
+        let ports_str = parts.next().unwrap_or("").trim();
+        let ports: Vec<String> = if ports_str == "<none>" { vec![] } else {
+            ports_str.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+        };
+        Some((name, ports))
+    }).collect()
 }
 
-fn gen_image_deployment(req: &DeployImageRequest) -> String {
-    let name = &req.name;
-    let ns = &req.namespace;
-    let secret_name = format!("{}-secrets", name);
-
-    // ports block — always include name (required by K8s when >1 port)
-    let ports_yaml = if req.ports.is_empty() {
-        String::new()
-    } else {
-        let lines: String = req.ports.iter().map(|p| {
-            let port_name = match &p.name {
-                Some(n) if !n.is_empty() => n.clone(),
-                _ => format!("port-{}", p.container_port),
-            };
-            format!("            - containerPort: {port}\n              name: {name}\n",
-                port = p.container_port,
-                name = port_name,
-            )
-        }).collect();
-        format!("          ports:\n{}", lines)
-    };
-
-    // plain env
-    let plain_env: String = req.env.iter().map(|e| {
-        format!("            - name: {}\n              value: \"{}\"\n", e.key, e.value.replace('"', "\\\""))
-    }).collect();
-
-    // secret env via secretKeyRef
-    let secret_env: String = req.secret_env.iter().map(|e| {
-        format!(
-"            - name: {key}
-              valueFrom:
-                secretKeyRef:
-                  name: {secret_name}
-                  key: {key}
-",
-            key = e.key,
-            secret_name = secret_name,
-        )
-    }).collect();
+#[tauri::command]
+fn list_namespaces() -> Vec<String> {
+    run_kubectl(&["get", "namespaces", "--no-headers",
+        "-o", "custom-columns=NAME:.metadata.name"])
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
 
-    let env_block = if plain_env.is_empty() && secret_env.is_empty() {
-        String::new()
-    } else {
-        format!("          env:\n{}{}", plain_env, secret_env)
-    };
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageClassInfo {
+    pub name: String,
+    pub provisioner: String,
+    pub reclaim_policy: String,
+    pub volume_binding_mode: String,
+    pub allow_volume_expansion: bool,
+    /// Annotated `storageclass.kubernetes.io/is-default-class: "true"`
+    pub is_default: bool,
+}
 
-    // resources block
-    let resources_block = match &req.resources {
-        Some(r) => {
-            let cpu_req = r.cpu_request.as_deref().unwrap_or("100m");
-            let mem_req = r.mem_request.as_deref().unwrap_or("128Mi");
-            let cpu_lim = r.cpu_limit.as_deref().unwrap_or("500m");
-            let mem_lim = r.mem_limit.as_deref().unwrap_or("512Mi");
-            format!(
-"          resources:
-            requests:
-              cpu: \"{cpu_req}\"
-              memory: \"{mem_req}\"
-            limits:
-              cpu: \"{cpu_lim}\"
-              memory: \"{mem_lim}\"
-"
-            )
+/// StorageClasses in the cluster, for picking `storage.storage_class`.
+#[tauri::command]
+fn list_storage_classes() -> Result<Vec<StorageClassInfo>, String> {
+    let raw = run_kubectl(&[
+        "get", "storageclasses", "--no-headers",
+        "-o", "custom-columns=NAME:.metadata.name,PROVISIONER:.provisioner,RECLAIM:.reclaimPolicy,\
+BINDING:.volumeBindingMode,EXPANSION:.allowVolumeExpansion,\
+DEFAULT:.metadata.annotations.storageclass\\.kubernetes\\.io/is-default-class",
+    ])?;
+    Ok(raw.lines().filter_map(|line| {
+        let p: Vec<&str> = line.split_whitespace().collect();
+        if p.len() < 6 {
+            return None;
         }
-        None => String::new(),
-    };
+        Some(StorageClassInfo {
+            name: p[0].to_string(),
+            provisioner: p[1].to_string(),
+            reclaim_policy: p[2].to_string(),
+            volume_binding_mode: p[3].to_string(),
+            allow_volume_expansion: p[4] == "true",
+            is_default: p[5] == "true",
+        })
+    }).collect())
+}
 
-    // imagePullSecrets block
-    let pull_secrets_block = match &req.image_pull_secret {
-        Some(s) if !s.is_empty() => format!(
-"      imagePullSecrets:
-        - name: {s}
-"
-        ),
-        _ => String::new(),
-    };
+// ─── Network policies ─────────────────────────────────────────────────────────
+//
+// Connections are inferred from the project's manifests only: a workload
+// talks to a Service when one of its env values (or a ConfigMap it
+// references) names the Service's DNS name, and an Ingress backend is a
+// connection from the ingress controller. Generated policies are
+// ingress-only — egress stays open so DNS and external APIs keep working.
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ServiceConnection {
+    /// Source workload name, or `ingress/<name>` for Ingress backends
+    pub from: String,
+    pub from_namespace: String,
+    /// Target workload name
+    pub to: String,
+    pub to_namespace: String,
+    /// Service the connection goes through
+    pub service: String,
+    /// Target container ports (numbers or port names); empty = unknown
+    pub ports: Vec<String>,
+    /// "env" | "ingress"
+    pub via: String,
+}
 
-    format!(
-"apiVersion: apps/v1
-kind: Deployment
-metadata:
-  name: {name}
-  namespace: {ns}
-  labels:
-    app.kubernetes.io/name: {name}
-    app.kubernetes.io/managed-by: endfield
-    endfield/type: image-deploy
-    endfield/namespace: {ns}
-spec:
-  replicas: {replicas}
-  selector:
-    matchLabels:
-      app.kubernetes.io/name: {name}
-  template:
-    metadata:
-      labels:
-        app.kubernetes.io/name: {name}
-        app.kubernetes.io/managed-by: endfield
-    spec:
-{pull_secrets_block}      containers:
-        - name: {name}
-          image: {image}
-{ports_yaml}{env_block}{resources_block}",
-        name = name,
-        ns = ns,
-        replicas = req.replicas,
-        image = req.image,
-        pull_secrets_block = pull_secrets_block,
-        ports_yaml = ports_yaml,
-        env_block = env_block,
-        resources_block = resources_block,
-    )
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkPolicyResult {
+    pub generated_files: Vec<String>,
+    pub connections: Vec<ServiceConnection>,
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
 }
 
-fn gen_image_service(name: &str, ns: &str, ports: &[DeployPort], service_type: &str) -> String {
-    let port_lines: String = ports.iter().map(|p| {
-        let port_name = match &p.name {
-            Some(n) if !n.is_empty() => n.clone(),
-            _ => format!("port-{}", p.container_port),
-        };
-        format!(
-"    - name: {name}
-      port: {port}
-      targetPort: {port}
-      protocol: TCP
-",
-            name = port_name,
-            port = p.container_port,
-        )
-    }).collect();
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockedConnection {
+    pub connection: ServiceConnection,
+    /// Policies that select the target pods, none of which admit the source
+    pub policies: Vec<String>,
+}
 
-    format!(
-"apiVersion: v1
-kind: Service
-metadata:
-  name: {name}
-  namespace: {ns}
-  labels:
-    app.kubernetes.io/name: {name}
-    app.kubernetes.io/managed-by: endfield
-    endfield/type: image-deploy
-    endfield/namespace: {ns}
-spec:
-  selector:
-    app.kubernetes.io/name: {name}
-  type: {service_type}
-  ports:
-{port_lines}"
-    )
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkPolicyReport {
+    pub connections: Vec<ServiceConnection>,
+    pub blocked: Vec<BlockedConnection>,
 }
 
-/// Deploy a custom Docker image to Kubernetes.
-/// Generates manifests in-memory and applies them via kubectl apply --server-side.
-/// Idempotent: re-running updates image/env/replicas.
-#[tauri::command]
-async fn deploy_image(request: DeployImageRequest) -> DeployImageResult {
-    tauri::async_runtime::spawn_blocking(move || {
-        deploy_image_inner(request)
-    }).await.unwrap_or_else(|e| DeployImageResult {
-        success: false,
-        deployment_name: String::new(),
-        secret_name: None,
-        service_name: None,
-        namespace: String::new(),
-        stdout: String::new(),
-        stderr: format!("spawn error: {}", e),
-        error: Some(format!("spawn error: {}", e)),
-        manifests: DeployImageManifests {
-            namespace: None,
-            secret: None,
-            deployment: String::new(),
-            service: None,
-        },
-    })
+struct GraphWorkload {
+    name: String,
+    namespace: String,
+    file_path: String,
+    pod_labels: Labels,
+    /// Env values plus data of referenced ConfigMaps
+    values: Vec<String>,
 }
 
-fn deploy_image_inner(req: DeployImageRequest) -> DeployImageResult {
-    let name = req.name.clone();
-    let ns = req.namespace.clone();
-    let has_secret = !req.secret_env.is_empty();
-    let has_service = !req.ports.is_empty();
-    let secret_name = if has_secret { Some(format!("{}-secrets", name)) } else { None };
-    let service_name = if has_service { Some(name.clone()) } else { None };
+struct IngressBackend {
+    namespace: String,
+    ingress: String,
+    service: String,
+}
 
-    // Build manifests
-    let ns_manifest = if req.create_namespace {
-        Some(gen_image_namespace(&ns))
-    } else {
-        None
-    };
-    let secret_manifest = if has_secret {
-        Some(gen_image_secret(&name, &ns, &req.secret_env))
-    } else {
-        None
-    };
-    let deploy_manifest = gen_image_deployment(&req);
-    let service_manifest = if has_service {
-        Some(gen_image_service(&name, &ns, &req.ports, &req.service_type))
-    } else {
-        None
-    };
+struct ServiceGraph {
+    workloads: Vec<GraphWorkload>,
+    services: Vec<GraphService>,
+    backends: Vec<IngressBackend>,
+}
 
-    // Apply order: Namespace → Secret → Deployment → Service
-    let mut all_stdout = Vec::<String>::new();
-    let mut all_stderr = Vec::<String>::new();
-    let mut overall_success = true;
+struct GraphService {
+    name: String,
+    namespace: String,
+    selector: Labels,
+    target_ports: Vec<String>,
+}
 
-    // Ensure namespace
-    if req.create_namespace {
-        let yaml = ns_manifest.as_deref().unwrap();
-        match kubectl_apply_manifest(yaml, &ns) {
-            Ok(out) => all_stdout.push(out),
-            Err(e) => { all_stderr.push(e.clone()); overall_success = false; }
+/// Items of the first `key:` block list, each re-indented as a mapping
+/// (the `- ` marker becomes two spaces) so the other helpers can read it.
+/// Inline lists (`key: []`) yield nothing.
+fn block_list_items(content: &str, key: &str) -> Vec<String> {
+    let key = format!("{}:", key);
+    let mut key_found = false;
+    let mut list_indent: Option<usize> = None;
+    let mut items: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let raw = line.trim_start();
+        let indent = line.len() - raw.len();
+        if !key_found {
+            key_found = raw.trim_start_matches("- ") == key;
+            continue;
         }
-    } else {
-        // Just ensure it exists (non-fatal)
-        let _ = ensure_namespace(&ns);
-    }
-
-    if !overall_success {
-        return DeployImageResult {
-            success: false,
-            deployment_name: name,
-            secret_name,
-            service_name,
-            namespace: ns,
-            stdout: all_stdout.join("\n"),
-            stderr: all_stderr.join("\n"),
-            error: Some(all_stderr.join("\n")),
-            manifests: DeployImageManifests {
-                namespace: ns_manifest,
-                secret: secret_manifest,
-                deployment: deploy_manifest,
-                service: service_manifest,
-            },
+        if raw.is_empty() || raw.starts_with('#') {
+            continue;
+        }
+        let is_item = raw.starts_with("- ") || raw == "-";
+        let li = match list_indent {
+            Some(li) => li,
+            None if is_item => *list_indent.insert(indent),
+            None => break,
         };
-    }
-
-    // Apply Secret
-    if let Some(ref yaml) = secret_manifest {
-        match kubectl_apply_manifest(yaml, &ns) {
-            Ok(out) => all_stdout.push(out),
-            Err(e) => { all_stderr.push(e); overall_success = false; }
+        if indent < li || (indent == li && !is_item) {
+            break;
+        }
+        if is_item && indent == li {
+            items.push(format!("{}  {}\n", " ".repeat(indent), raw[1..].trim_start()));
+        } else if let Some(item) = items.last_mut() {
+            item.push_str(line);
+            item.push('\n');
         }
     }
+    items
+}
 
-    // Apply Deployment
-    match kubectl_apply_manifest(&deploy_manifest, &ns) {
-        Ok(out) => all_stdout.push(out),
-        Err(e) => { all_stderr.push(e); overall_success = false; }
-    }
+/// Scalar field at the top level of a `block_list_items` item, ignoring
+/// same-named keys of nested mappings (e.g. `name:` of env entries).
+fn list_item_field(item: &str, key: &str) -> Option<String> {
+    let indent_of = |l: &str| l.len() - l.trim_start().len();
+    let base = indent_of(item.lines().next()?);
+    item.lines()
+        .filter(|l| indent_of(l) == base)
+        .find_map(|l| l.trim().strip_prefix(key)?.strip_prefix(':').map(|v| {
+            v.trim().trim_matches('"').trim_matches('\'').to_string()
+        }))
+        .filter(|v| !v.is_empty())
+}
 
-    // Apply Service
-    if let Some(ref yaml) = service_manifest {
-        match kubectl_apply_manifest(yaml, &ns) {
-            Ok(out) => all_stdout.push(out),
-            Err(e) => { all_stderr.push(e); overall_success = false; }
-        }
-    }
+/// Workloads, Services and Ingress backends of the project, skipping Helm
+/// chart sources and rendered output.
+fn service_graph(docs: &[(String, String)]) -> ServiceGraph {
+    let docs: Vec<&(String, String)> = docs
+        .iter()
+        .filter(|(file, _)| {
+            !Path::new(file)
+                .components()
+                .any(|c| c.as_os_str() == "rendered" || c.as_os_str() == "charts")
+        })
+        .collect();
+    let kind_of = |doc: &str| extract_yaml_field(doc, "kind").unwrap_or_default().to_string();
+    let ns_of = |doc: &str| extract_metadata_field(doc, "namespace").unwrap_or("default").to_string();
+
+    let config_maps: Vec<(String, String, Vec<String>)> = docs
+        .iter()
+        .filter(|(_, doc)| kind_of(doc) == "ConfigMap")
+        .filter_map(|(_, doc)| {
+            let values = doc
+                .lines()
+                .skip_while(|l| l.trim() != "data:")
+                .skip(1)
+                .take_while(|l| l.starts_with(' ') || l.trim().is_empty())
+                .filter_map(|l| l.split_once(':').map(|(_, v)| v.trim().trim_matches('"').to_string()))
+                .collect();
+            Some((ns_of(doc), extract_metadata_field(doc, "name")?.to_string(), values))
+        })
+        .collect();
 
-    let err = if overall_success { None } else { Some(all_stderr.join("\n")) };
+    let workload_kinds = ["Deployment", "StatefulSet", "DaemonSet", "ReplicaSet", "Job", "CronJob", "Pod"];
+    let workloads: Vec<GraphWorkload> = docs
+        .iter()
+        .filter(|(_, doc)| workload_kinds.contains(&kind_of(doc).as_str()))
+        .filter_map(|(file, doc)| {
+            let namespace = ns_of(doc);
+            let referenced: Vec<&str> = doc
+                .lines()
+                .filter_map(|l| l.trim().trim_start_matches("- ").strip_prefix("name:"))
+                .map(|n| n.trim().trim_matches('"'))
+                .collect();
+            let mut values: Vec<String> = doc
+                .lines()
+                .filter_map(|l| l.trim().trim_start_matches("- ").strip_prefix("value:"))
+                .map(|v| v.trim().trim_matches('"').trim_matches('\'').to_string())
+                .collect();
+            for (cm_ns, cm_name, cm_values) in &config_maps {
+                if *cm_ns == namespace && referenced.contains(&cm_name.as_str()) {
+                    values.extend(cm_values.iter().cloned());
+                }
+            }
+            let pod_labels = if kind_of(doc) == "Pod" {
+                extract_block_map(doc, "metadata", "labels")
+            } else {
+                extract_block_map(doc, "template", "labels")
+            };
+            Some(GraphWorkload {
+                name: extract_metadata_field(doc, "name")?.to_string(),
+                namespace,
+                file_path: file.clone(),
+                pod_labels,
+                values,
+            })
+        })
+        .collect();
 
-    DeployImageResult {
-        success: overall_success,
-        deployment_name: name,
-        secret_name,
-        service_name,
-        namespace: ns,
-        stdout: all_stdout.join("\n"),
-        stderr: all_stderr.join("\n"),
-        error: err,
-        manifests: DeployImageManifests {
-            namespace: ns_manifest,
-            secret: secret_manifest,
-            deployment: deploy_manifest,
-            service: service_manifest,
-        },
+    let services: Vec<GraphService> = docs
+        .iter()
+        .filter(|(_, doc)| kind_of(doc) == "Service")
+        .filter_map(|(_, doc)| {
+            let target_ports = block_list_items(doc, "ports")
+                .iter()
+                .filter_map(|item| {
+                    let field = |key: &str| {
+                        item.lines()
+                            .find_map(|l| l.trim().strip_prefix(key).map(|v| v.trim().trim_matches('"').to_string()))
+                    };
+                    field("targetPort:").or_else(|| field("port:"))
+                })
+                .collect();
+            Some(GraphService {
+                name: extract_metadata_field(doc, "name")?.to_string(),
+                namespace: ns_of(doc),
+                selector: extract_block_map(doc, "spec", "selector")
+                    .into_iter()
+                    .filter(|(k, _)| k != "matchLabels")
+                    .collect(),
+                target_ports,
+            })
+        })
+        .collect();
+
+    let mut backends: Vec<IngressBackend> = Vec::new();
+    for (_, doc) in docs.iter().filter(|(_, doc)| kind_of(doc) == "Ingress") {
+        let Some(ingress) = extract_metadata_field(doc, "name") else { continue };
+        let mut service_indent: Option<usize> = None;
+        for line in doc.lines() {
+            let indent = line.len() - line.trim_start().len();
+            let t = line.trim();
+            match service_indent {
+                None if t == "service:" => service_indent = Some(indent),
+                Some(si) if indent <= si && !t.is_empty() => {
+                    service_indent = (t == "service:").then_some(indent);
+                }
+                Some(_) => {
+                    if let Some(name) = t.strip_prefix("name:") {
+                        let service = name.trim().trim_matches('"').to_string();
+                        let known = backends.iter().any(|b| b.ingress == ingress && b.service == service);
+                        if indent == service_indent.unwrap_or(0) + 2 && !known {
+                            backends.push(IngressBackend {
+                                namespace: ns_of(doc),
+                                ingress: ingress.to_string(),
+                                service,
+                            });
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
     }
+
+    ServiceGraph { workloads, services, backends }
 }
 
-/// Apply a YAML string via kubectl apply --server-side (stdin).
-fn kubectl_apply_manifest(yaml: &str, _namespace: &str) -> Result<String, String> {
-    use std::io::Write;
-    let mut child = Command::new("kubectl")
-        .args(["apply", "--server-side", "--field-manager=endfield", "--force-conflicts", "-f", "-"])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("kubectl not found: {}", e))?;
+/// True when `value` addresses Service `name` in `svc_ns` from `from_ns`:
+/// a bare host token in the same namespace, or `name.ns[.svc[.cluster.local]]`.
+fn value_addresses_service(value: &str, name: &str, svc_ns: &str, from_ns: &str) -> bool {
+    let qualified = format!("{}.{}", name, svc_ns);
+    value
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '.'))
+        .any(|host| {
+            (host == name && svc_ns == from_ns)
+                || host == qualified
+                || host.strip_prefix(&qualified).is_some_and(|rest| rest.starts_with(".svc"))
+        })
+}
 
-    if let Some(stdin) = child.stdin.as_mut() {
-        stdin.write_all(yaml.as_bytes())
-            .map_err(|e| format!("stdin write error: {}", e))?;
-    }
+fn labels_match(selector: &[(String, String)], labels: &[(String, String)]) -> bool {
+    selector.iter().all(|l| labels.contains(l))
+}
 
-    let output = child.wait_with_output()
-        .map_err(|e| format!("kubectl wait error: {}", e))?;
+/// Connections between project workloads, deduplicated.
+fn infer_connections(docs: &[(String, String)], ingress_namespace: &str) -> Vec<ServiceConnection> {
+    let ServiceGraph { workloads, services, backends } = service_graph(docs);
+    let targets_of = |svc: &GraphService| -> Vec<&GraphWorkload> {
+        if svc.selector.is_empty() {
+            return vec![];
+        }
+        workloads
+            .iter()
+            .filter(|w| w.namespace == svc.namespace && labels_match(&svc.selector, &w.pod_labels))
+            .collect()
+    };
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    let mut connections: Vec<ServiceConnection> = Vec::new();
+    let mut push = |c: ServiceConnection| {
+        if !connections.contains(&c) {
+            connections.push(c);
+        }
+    };
+    for svc in &services {
+        let targets = targets_of(svc);
+        for source in &workloads {
+            if !source
+                .values
+                .iter()
+                .any(|v| value_addresses_service(v, &svc.name, &svc.namespace, &source.namespace))
+            {
+                continue;
+            }
+            for target in targets.iter().filter(|t| t.name != source.name || t.namespace != source.namespace) {
+                push(ServiceConnection {
+                    from: source.name.clone(),
+                    from_namespace: source.namespace.clone(),
+                    to: target.name.clone(),
+                    to_namespace: target.namespace.clone(),
+                    service: svc.name.clone(),
+                    ports: svc.target_ports.clone(),
+                    via: "env".to_string(),
+                });
+            }
+        }
+        for backend in &backends {
+            if backend.service != svc.name || backend.namespace != svc.namespace {
+                continue;
+            }
+            for target in &targets {
+                push(ServiceConnection {
+                    from: format!("ingress/{}", backend.ingress),
+                    from_namespace: ingress_namespace.to_string(),
+                    to: target.name.clone(),
+                    to_namespace: target.namespace.clone(),
+                    service: svc.name.clone(),
+                    ports: svc.target_ports.clone(),
+                    via: "ingress".to_string(),
+                });
+            }
+        }
     }
+    connections
 }
 
-// ─── Ingress Nginx Types ──────────────────────────────────────────────────────
+fn render_match_labels(labels: &[(String, String)], indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let lines: String = labels
+        .iter()
+        .map(|(k, v)| format!("{}  {}: \"{}\"\n", pad, k, v))
+        .collect();
+    format!("{}matchLabels:\n{}", pad, lines)
+}
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct IngressNginxStatus {
-    pub ingress_class_name: String,
-    pub controller_service_name: String,
-    pub endpoint: Option<String>,
-    pub ready: bool,
+/// Ingress-only default deny for a namespace.
+fn generate_default_deny_policy(namespace: &str) -> String {
+    format!(
+        r#"apiVersion: networking.k8s.io/v1
+kind: NetworkPolicy
+metadata:
+  name: default-deny-ingress
+  namespace: {ns}
+  labels:
+    managed-by: endfield
+spec:
+  podSelector: {{}}
+  policyTypes:
+    - Ingress
+"#,
+        ns = namespace,
+    )
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct IngressRoute {
-    pub route_id: String,
-    pub field_id: String,
-    pub target_namespace: String,
-    pub target_service: String,
-    pub target_port_number: Option<u32>,
-    pub target_port_name: Option<String>,
-    pub host: Option<String>,
-    pub path: String,
-    pub path_type: String,
-    pub tls_secret: Option<String>,
-    pub tls_hosts: Option<Vec<String>>,
-    pub annotations: Option<Vec<(String, String)>>,
-    pub ingress_class_name: String,
-    pub ingress_name: String,
-    pub ingress_namespace: String,
+/// Allow policy admitting every inferred connection into `target`.
+/// Connections from a workload without pod labels are skipped with a
+/// warning — an empty podSelector would admit every pod in the namespace.
+/// `None` if no connection is left.
+fn generate_allow_policy(
+    target: &GraphWorkload,
+    incoming: &[&ServiceConnection],
+    workloads: &[GraphWorkload],
+    warnings: &mut Vec<String>,
+) -> Option<String> {
+    let mut rules = String::new();
+    for conn in incoming {
+        let peer = if conn.via == "ingress" {
+            format!(
+                "        - namespaceSelector:\n{}",
+                render_match_labels(&[("kubernetes.io/metadata.name".to_string(), conn.from_namespace.clone())], 12)
+            )
+        } else {
+            let Some(source) = workloads
+                .iter()
+                .find(|w| w.name == conn.from && w.namespace == conn.from_namespace)
+            else {
+                continue;
+            };
+            if source.pod_labels.is_empty() {
+                warnings.push(format!(
+                    "{}/{} has no pod labels — its connection to {}/{} is not allowed",
+                    source.namespace, source.name, target.namespace, target.name
+                ));
+                continue;
+            }
+            let mut peer = format!("        - podSelector:\n{}", render_match_labels(&source.pod_labels, 12));
+            if conn.from_namespace != target.namespace {
+                peer.push_str(&format!(
+                    "          namespaceSelector:\n{}",
+                    render_match_labels(&[("kubernetes.io/metadata.name".to_string(), conn.from_namespace.clone())], 12)
+                ));
+            }
+            peer
+        };
+        let ports = if conn.ports.is_empty() {
+            String::new()
+        } else {
+            let lines: String = conn
+                .ports
+                .iter()
+                .map(|p| format!("        - protocol: TCP\n          port: {}\n", p))
+                .collect();
+            format!("      ports:\n{}", lines)
+        };
+        let rule = format!(
+            "    # {} → {} via {}\n    - from:\n{}{}",
+            conn.from, conn.service, conn.via, peer, ports
+        );
+        if !rules.contains(&rule) {
+            rules.push_str(&rule);
+        }
+    }
+    if rules.is_empty() {
+        return None;
+    }
+    Some(format!(
+        r#"apiVersion: networking.k8s.io/v1
+kind: NetworkPolicy
+metadata:
+  name: allow-{name}
+  namespace: {ns}
+  labels:
+    managed-by: endfield
+spec:
+  podSelector:
+{selector}  policyTypes:
+    - Ingress
+  ingress:
+{rules}"#,
+        name = target.name,
+        ns = target.namespace,
+        selector = render_match_labels(&target.pod_labels, 4),
+        rules = rules,
+    ))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct IngressRouteResult {
-    pub route_id: String,
-    pub ingress_name: String,
-    pub namespace: String,
-    pub stdout: String,
-    pub stderr: String,
-    pub success: bool,
+/// Write `content` to a confined path unless a file is already there —
+/// generated policies may have been edited by hand.
+fn write_new_policy_file(roots: &ProjectRootState, path: &Path, content: &str, result: &mut NetworkPolicyResult) {
+    let path = match confine_subpath(roots, &path.to_string_lossy()) {
+        Ok(p) => p,
+        Err(e) => {
+            result.warnings.push(e.to_string());
+            return;
+        }
+    };
+    if path.exists() {
+        if fs::read_to_string(&path).ok().as_deref() != Some(content) {
+            result.warnings.push(format!(
+                "{} already exists — skipped; delete it to regenerate",
+                path.display()
+            ));
+        }
+        return;
+    }
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, content));
+    match written {
+        Ok(()) => result.generated_files.push(path.to_string_lossy().to_string()),
+        Err(e) => result.warnings.push(format!("Cannot write {}: {}", path.display(), e)),
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DiscoveredRoute {
-    pub route_id: String,
-    pub field_id: String,
-    pub ingress_name: String,
-    pub ingress_namespace: String,
-    pub host: Option<String>,
-    pub path: String,
-    pub path_type: String,
-    pub target_service: String,
-    pub target_namespace: String,
-    pub target_port_number: Option<u32>,
-    pub target_port_name: Option<String>,
-    pub ingress_class_name: String,
-    pub tls_secret: Option<String>,
-    pub address: Option<String>,
+/// Generate one default-deny per namespace (`network-policies/<ns>-default-deny.yaml`,
+/// so removing a single field never drops it) plus an allow policy per
+/// workload from the inferred service graph, written next to the workload's
+/// manifest (`apps/<id>/networkpolicy.yaml` for generated fields). Existing
+/// files are left alone. Does NOT deploy.
+#[tauri::command]
+fn generate_network_policies(
+    roots: tauri::State<ProjectRootState>,
+    ingress_namespace: Option<String>,
+) -> NetworkPolicyResult {
+    let mut result = NetworkPolicyResult {
+        generated_files: vec![],
+        connections: vec![],
+        warnings: vec![],
+        error: None,
+    };
+    let root = match project_root(&roots) {
+        Ok(r) => r,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    let ingress_ns = ingress_namespace.unwrap_or_else(|| "ingress-nginx".to_string());
+    let docs = project_yaml_docs(&root);
+    let connections = infer_connections(&docs, &ingress_ns);
+    let workloads = service_graph(&docs).workloads;
+
+    let mut namespaces: Vec<&str> = workloads
+        .iter()
+        .filter(|w| !w.pod_labels.is_empty())
+        .map(|w| w.namespace.as_str())
+        .collect();
+    namespaces.sort();
+    namespaces.dedup();
+    for ns in namespaces {
+        let path = root.join("network-policies").join(format!("{}-default-deny.yaml", ns));
+        write_new_policy_file(&roots, &path, &generate_default_deny_policy(ns), &mut result);
+    }
+
+    for target in &workloads {
+        if target.pod_labels.is_empty() {
+            result.warnings.push(format!(
+                "{}/{} has no pod labels — no policy generated",
+                target.namespace, target.name
+            ));
+            continue;
+        }
+        let incoming: Vec<&ServiceConnection> = connections
+            .iter()
+            .filter(|c| c.to == target.name && c.to_namespace == target.namespace)
+            .collect();
+        if incoming.is_empty() {
+            result.warnings.push(format!(
+                "{}/{} has no inbound connections detected — all ingress to it will be denied",
+                target.namespace, target.name
+            ));
+            continue;
+        }
+
+        let dir = Path::new(&target.file_path).parent().unwrap_or(&root);
+        let file_name = if dir.file_name().and_then(|n| n.to_str()) == Some(target.name.as_str()) {
+            "networkpolicy.yaml".to_string()
+        } else {
+            format!("{}-networkpolicy.yaml", target.name)
+        };
+        match generate_allow_policy(target, &incoming, &workloads, &mut result.warnings) {
+            Some(yaml) => write_new_policy_file(&roots, &dir.join(file_name), &yaml, &mut result),
+            None => result.warnings.push(format!(
+                "{}/{} has no allowable inbound connections — all ingress to it will be denied",
+                target.namespace, target.name
+            )),
+        }
+    }
+    result.connections = connections;
+    result
 }
 
-// ─── Ingress Nginx Commands ───────────────────────────────────────────────────
+/// A peer (`from` entry) of a NetworkPolicy ingress rule.
+struct PolicyPeer {
+    pod_selector: Option<Labels>,
+    namespace_selector: Option<Labels>,
+}
 
-#[tauri::command]
-fn detect_ingress_nginx(namespace: String, release_name: String) -> IngressNginxStatus {
-    let ingress_class = run_kubectl(&[
-        "get", "ingressclass",
-        "-l", &format!("app.kubernetes.io/instance={}", release_name),
-        "-o", "jsonpath={.items[0].metadata.name}",
-    ])
-    .unwrap_or_else(|_| "nginx".to_string());
-    let ingress_class = if ingress_class.is_empty() { "nginx".to_string() } else { ingress_class };
+struct PolicyRule {
+    /// None = any source
+    peers: Option<Vec<PolicyPeer>>,
+    /// Empty = any port
+    ports: Vec<String>,
+}
 
-    let svc_name = run_kubectl(&[
-        "get", "service", "-n", &namespace,
-        "-l", &format!("app.kubernetes.io/instance={}", release_name),
-        "-o", "jsonpath={.items[0].metadata.name}",
-    ])
-    .unwrap_or_default();
+struct ParsedPolicy {
+    name: String,
+    namespace: String,
+    pod_selector: Labels,
+    rules: Vec<PolicyRule>,
+}
 
-    let endpoint = if !svc_name.is_empty() {
-        let lb = run_kubectl(&[
-            "get", "service", &svc_name, "-n", &namespace,
-            "-o", "jsonpath={.status.loadBalancer.ingress[0].ip}",
-        ]).ok().filter(|s| !s.is_empty());
-        if lb.is_some() { lb } else {
-            run_kubectl(&[
-                "get", "service", &svc_name, "-n", &namespace,
-                "-o", "jsonpath={.status.loadBalancer.ingress[0].hostname}",
-            ]).ok().filter(|s| !s.is_empty())
+/// Ingress side of a NetworkPolicy; None for egress-only policies.
+fn parse_network_policy(doc: &str) -> Option<ParsedPolicy> {
+    if extract_yaml_field(doc, "kind")? != "NetworkPolicy" {
+        return None;
+    }
+    // policyTypes defaults to Ingress when omitted
+    let declared: String = doc
+        .lines()
+        .skip_while(|l| !l.trim().starts_with("policyTypes:"))
+        .take_while(|l| l.trim().starts_with("policyTypes:") || l.trim().starts_with("- "))
+        .collect();
+    if !declared.is_empty() && !declared.contains("Ingress") {
+        return None;
+    }
+    // spec.podSelector sits at indent 2; `{}` or no matchLabels selects all pods
+    let spec_selector = match doc.find("\n  podSelector:") {
+        Some(i) if !doc[i + 1..].lines().next().unwrap_or("").contains("{}") => {
+            extract_block_map(&doc[i + 1..], "podSelector", "matchLabels")
         }
-    } else { None };
+        _ => vec![],
+    };
+    let selector_of = |item: &str, key: &str| -> Option<Labels> {
+        item.lines()
+            .any(|l| l.trim().trim_start_matches("- ").starts_with(&format!("{}:", key)))
+            .then(|| extract_block_map(item, key, "matchLabels"))
+    };
+    let rules = block_list_items(doc, "ingress")
+        .iter()
+        .map(|rule| {
+            let has_from = rule.lines().any(|l| l.trim() == "from:");
+            PolicyRule {
+                peers: has_from.then(|| {
+                    block_list_items(rule, "from")
+                        .iter()
+                        .map(|peer| PolicyPeer {
+                            pod_selector: selector_of(peer, "podSelector"),
+                            namespace_selector: selector_of(peer, "namespaceSelector"),
+                        })
+                        .collect()
+                }),
+                ports: block_list_items(rule, "ports")
+                    .iter()
+                    .filter_map(|p| {
+                        p.lines().find_map(|l| {
+                            l.trim().strip_prefix("port:").map(|v| v.trim().trim_matches('"').to_string())
+                        })
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    Some(ParsedPolicy {
+        name: extract_metadata_field(doc, "name")?.to_string(),
+        namespace: extract_metadata_field(doc, "namespace").unwrap_or("default").to_string(),
+        pod_selector: spec_selector,
+        rules,
+    })
+}
 
-    IngressNginxStatus {
-        ingress_class_name: ingress_class,
-        controller_service_name: svc_name.clone(),
-        endpoint,
-        ready: !svc_name.is_empty(),
+fn rule_admits(rule: &PolicyRule, policy_ns: &str, from_ns: &str, from_labels: &[(String, String)], ports: &[String]) -> bool {
+    let ports_ok = rule.ports.is_empty()
+        || ports.is_empty()
+        || ports.iter().all(|p| rule.ports.contains(p));
+    if !ports_ok {
+        return false;
     }
+    let Some(peers) = &rule.peers else { return true };
+    let ns_labels = [("kubernetes.io/metadata.name".to_string(), from_ns.to_string())];
+    peers.iter().any(|peer| {
+        let ns_ok = match &peer.namespace_selector {
+            Some(sel) => labels_match(sel, &ns_labels),
+            None => from_ns == policy_ns,
+        };
+        let pod_ok = match &peer.pod_selector {
+            Some(sel) => labels_match(sel, from_labels),
+            None => peer.namespace_selector.is_some(),
+        };
+        ns_ok && pod_ok
+    })
 }
 
-fn generate_ingress_yaml(route: &IngressRoute) -> String {
-    let port_spec = if let Some(n) = route.target_port_number {
-        format!("number: {}", n)
-    } else if let Some(name) = &route.target_port_name {
-        format!("name: {}", name)
-    } else {
-        "number: 80".to_string()
-    };
-
-    let host_rules = if let Some(host) = &route.host {
-        format!(
-"  rules:\n    - host: {host}\n      http:\n        paths:\n          - path: {path}\n            pathType: {pt}\n            backend:\n              service:\n                name: {svc}\n                port:\n                  {port}\n",
-            host=host, path=route.path, pt=route.path_type,
-            svc=route.target_service, port=port_spec)
-    } else {
-        format!(
-"  rules:\n    - http:\n        paths:\n          - path: {path}\n            pathType: {pt}\n            backend:\n              service:\n                name: {svc}\n                port:\n                  {port}\n",
-            path=route.path, pt=route.path_type,
-            svc=route.target_service, port=port_spec)
+/// Check every inferred connection against the NetworkPolicies in the
+/// project and report the ones that would be blocked.
+#[tauri::command]
+fn check_network_policies(
+    roots: tauri::State<ProjectRootState>,
+    ingress_namespace: Option<String>,
+) -> Result<NetworkPolicyReport, String> {
+    let root = project_root(&roots)?;
+    let ingress_ns = ingress_namespace.unwrap_or_else(|| "ingress-nginx".to_string());
+    let docs = project_yaml_docs(&root);
+    let connections = infer_connections(&docs, &ingress_ns);
+    let workloads = service_graph(&docs).workloads;
+    let policies: Vec<ParsedPolicy> = docs.iter().filter_map(|(_, doc)| parse_network_policy(doc)).collect();
+    let labels_of = |name: &str, ns: &str| {
+        workloads
+            .iter()
+            .find(|w| w.name == name && w.namespace == ns)
+            .map(|w| w.pod_labels.clone())
+            .unwrap_or_default()
     };
 
-    let tls_block = match (&route.tls_secret, &route.tls_hosts) {
-        (Some(secret), Some(hosts)) if !hosts.is_empty() => {
-            let hl: String = hosts.iter().map(|h| format!("        - {}\n", h)).collect();
-            format!("  tls:\n    - hosts:\n{}      secretName: {}\n", hl, secret)
+    let mut blocked = Vec::new();
+    for conn in &connections {
+        let target_labels = labels_of(&conn.to, &conn.to_namespace);
+        let from_labels = labels_of(&conn.from, &conn.from_namespace);
+        let selecting: Vec<&ParsedPolicy> = policies
+            .iter()
+            .filter(|p| p.namespace == conn.to_namespace && labels_match(&p.pod_selector, &target_labels))
+            .collect();
+        if selecting.is_empty() {
+            continue;
         }
-        _ => String::new(),
-    };
-
-    let mut ann = format!(
-        "    app.kubernetes.io/managed-by: endfield\n    endfield.io/fieldId: {}\n    endfield.io/routeId: {}\n",
-        route.field_id, route.route_id
-    );
-    if let Some(anns) = &route.annotations {
-        for (k, v) in anns {
-            // Always quote values — prevents YAML from parsing "true"/"false"/numbers as non-strings
-            ann.push_str(&format!("    {}: \"{}\"\n", k, v.replace('"', "\\\"")));
+        let admitted = selecting.iter().any(|p| {
+            p.rules
+                .iter()
+                .any(|r| rule_admits(r, &p.namespace, &conn.from_namespace, &from_labels, &conn.ports))
+        });
+        if !admitted {
+            blocked.push(BlockedConnection {
+                connection: conn.clone(),
+                policies: selecting.iter().map(|p| p.name.clone()).collect(),
+            });
         }
     }
+    Ok(NetworkPolicyReport { connections, blocked })
+}
 
-    format!(
-"apiVersion: networking.k8s.io/v1\nkind: Ingress\nmetadata:\n  name: {name}\n  namespace: {ns}\n  labels:\n    app.kubernetes.io/managed-by: endfield\n    endfield.io/fieldId: {fid}\n    endfield.io/routeId: {rid}\n  annotations:\n{ann}spec:\n  ingressClassName: {class}\n{tls}{rules}",
-        name=route.ingress_name, ns=route.ingress_namespace,
-        fid=route.field_id, rid=route.route_id, ann=ann,
-        class=route.ingress_class_name, tls=tls_block, rules=host_rules)
+// ─── Service accounts and RBAC ────────────────────────────────────────────────
+
+const RBAC_VERBS: &[&str] = &[
+    "get", "list", "watch", "create", "update", "patch", "delete", "deletecollection",
+    "impersonate", "bind", "escalate", "use", "approve", "sign", "*",
+];
+
+/// One `rules:` entry. Rejects rules that grant nothing or use verbs the
+/// API server doesn't know; an empty apiGroups list means the core group.
+fn rbac_rule_yaml(rule: &RbacRule) -> Result<String, String> {
+    if rule.resources.is_empty() || rule.verbs.is_empty() {
+        return Err("RBAC rule needs at least one resource and one verb".to_string());
+    }
+    if let Some(verb) = rule.verbs.iter().find(|v| !RBAC_VERBS.contains(&v.as_str())) {
+        return Err(format!(
+            "Unknown RBAC verb '{}' (expected one of {})",
+            verb,
+            RBAC_VERBS.join(", ")
+        ));
+    }
+    let groups = if rule.api_groups.is_empty() { vec![String::new()] } else { rule.api_groups.clone() };
+    let mut out = format!(
+        "  - apiGroups: {}\n    resources: {}\n    verbs: {}\n",
+        yaml_flow_list(&groups),
+        yaml_flow_list(&rule.resources),
+        yaml_flow_list(&rule.verbs),
+    );
+    if !rule.resource_names.is_empty() {
+        out.push_str(&format!("    resourceNames: {}\n", yaml_flow_list(&rule.resource_names)));
+    }
+    Ok(out)
 }
 
-#[tauri::command]
-async fn apply_ingress_route(route: IngressRoute) -> IngressRouteResult {
-    tauri::async_runtime::spawn_blocking(move || {
-        let yaml = generate_ingress_yaml(&route);
-        let _ = ensure_namespace(&route.ingress_namespace);
-        match kubectl_apply_manifest(&yaml, &route.ingress_namespace) {
-            Ok(out) => IngressRouteResult {
-                route_id: route.route_id, ingress_name: route.ingress_name,
-                namespace: route.ingress_namespace, stdout: out,
-                stderr: String::new(), success: true,
-            },
-            Err(e) => IngressRouteResult {
-                route_id: route.route_id, ingress_name: route.ingress_name,
-                namespace: route.ingress_namespace, stdout: String::new(),
-                stderr: e, success: false,
-            },
+/// ServiceAccount named after the field, plus a Role/RoleBinding for the
+/// namespaced rules and a ClusterRole/ClusterRoleBinding for the cluster
+/// ones. The cluster role is prefixed with the namespace — its name is global.
+fn generate_rbac_yaml(cfg: &FieldConfig, rbac: &RbacConfig) -> Result<String, String> {
+    let labels = format!("  labels:\n    app: {}\n    managed-by: endfield\n", cfg.id);
+    let mut docs = vec![format!(
+        "apiVersion: v1\nkind: ServiceAccount\nmetadata:\n  name: {name}\n  namespace: {ns}\n{labels}automountServiceAccountToken: {automount}\n",
+        name = cfg.id,
+        ns = cfg.namespace,
+        labels = labels,
+        automount = rbac.automount_token,
+    )];
+    let subject = format!(
+        "subjects:\n  - kind: ServiceAccount\n    name: {}\n    namespace: {}\n",
+        cfg.id, cfg.namespace
+    );
+    let scopes = [
+        ("Role", "RoleBinding", cfg.id.clone(), Some(&cfg.namespace), &rbac.rules),
+        (
+            "ClusterRole",
+            "ClusterRoleBinding",
+            format!("{}-{}", cfg.namespace, cfg.id),
+            None,
+            &rbac.cluster_rules,
+        ),
+    ];
+    for (role_kind, binding_kind, role_name, ns, rules) in scopes {
+        if rules.is_empty() {
+            continue;
         }
-    }).await.unwrap_or_else(|e| IngressRouteResult {
-        route_id: String::new(), ingress_name: String::new(),
-        namespace: String::new(), stdout: String::new(),
-        stderr: format!("spawn error: {}", e), success: false,
-    })
+        let rules = rules.iter().map(rbac_rule_yaml).collect::<Result<String, String>>()?;
+        let ns_line = ns.map(|ns| format!("  namespace: {}\n", ns)).unwrap_or_default();
+        docs.push(format!(
+            "apiVersion: rbac.authorization.k8s.io/v1\nkind: {kind}\nmetadata:\n  name: {name}\n{ns}{labels}rules:\n{rules}",
+            kind = role_kind,
+            name = role_name,
+            ns = ns_line,
+            labels = labels,
+            rules = rules,
+        ));
+        docs.push(format!(
+            "apiVersion: rbac.authorization.k8s.io/v1\nkind: {kind}\nmetadata:\n  name: {name}\n{ns}{labels}roleRef:\n  apiGroup: rbac.authorization.k8s.io\n  kind: {role_kind}\n  name: {name}\n{subject}",
+            kind = binding_kind,
+            name = role_name,
+            ns = ns_line,
+            labels = labels,
+            role_kind = role_kind,
+            subject = subject,
+        ));
+    }
+    Ok(docs.join("---\n"))
 }
 
-#[tauri::command]
-fn get_ingress_route_yaml(route: IngressRoute) -> String {
-    generate_ingress_yaml(&route)
+/// Pod-level `serviceAccountName` when the field has its own ServiceAccount.
+fn service_account_block(cfg: &FieldConfig) -> String {
+    match cfg.rbac {
+        Some(_) => format!("      serviceAccountName: {}\n", cfg.id),
+        None => String::new(),
+    }
 }
 
-#[tauri::command]
-fn delete_ingress_route(ingress_name: String, namespace: String) -> Result<String, String> {
-    run_kubectl(&["delete", "ingress", &ingress_name, "-n", &namespace, "--ignore-not-found=true"])
+#[derive(Debug, Serialize)]
+pub struct ApiResourceInfo {
+    pub name: String,
+    /// "" for the core group
+    pub api_group: String,
+    pub kind: String,
+    pub namespaced: bool,
+    pub verbs: Vec<String>,
 }
 
+/// Resources the cluster serves, for picking RBAC rule resources and verbs.
+/// `api-resources` leaves columns blank (SHORTNAMES), so cells are cut at
+/// the header offsets instead of split on whitespace.
 #[tauri::command]
-fn discover_ingress_routes() -> Vec<DiscoveredRoute> {
-    let items_raw = match run_kubectl(&[
-        "get", "ingress", "--all-namespaces",
-        "-l", "app.kubernetes.io/managed-by=endfield",
-        "--no-headers", "-o",
-        "custom-columns=NAMESPACE:.metadata.namespace,NAME:.metadata.name,CLASS:.spec.ingressClassName",
-    ]) {
-        Ok(o) => o,
-        Err(_) => return vec![],
+fn list_api_resources() -> Result<Vec<ApiResourceInfo>, String> {
+    let raw = run_kubectl(&["api-resources", "-o", "wide"])?;
+    let mut lines = raw.lines();
+    let header = lines.next().unwrap_or_default();
+    let col = |name: &str| header.find(name);
+    let (Some(version_col), Some(namespaced_col), Some(kind_col), Some(verbs_col)) =
+        (col("APIVERSION"), col("NAMESPACED"), col("KIND"), col("VERBS"))
+    else {
+        return Err(format!("Unexpected kubectl api-resources output: {}", header));
+    };
+    let categories_col = col("CATEGORIES");
+    let cell = |line: &str, from: usize, to: Option<usize>| {
+        let to = to.unwrap_or(line.len()).min(line.len());
+        line.get(from..to).unwrap_or_default().trim().to_string()
     };
 
-    let mut routes = Vec::new();
-    for line in items_raw.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 2 { continue; }
-        let ns = parts[0];
-        let name = parts[1];
-        let class = parts.get(2).copied().unwrap_or("nginx");
+    Ok(lines
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| {
+            let version = cell(line, version_col, Some(namespaced_col));
+            ApiResourceInfo {
+                name: line.split_whitespace().next().unwrap_or_default().to_string(),
+                api_group: version.rsplit_once('/').map(|(g, _)| g.to_string()).unwrap_or_default(),
+                kind: cell(line, kind_col, Some(verbs_col)),
+                namespaced: cell(line, namespaced_col, Some(kind_col)) == "true",
+                verbs: cell(line, verbs_col, categories_col)
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect(),
+            }
+        })
+        .collect())
+}
 
-        let field_id = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.metadata.annotations.endfield\\.io/fieldId}"])
-            .unwrap_or_default();
-        let route_id = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.metadata.annotations.endfield\\.io/routeId}"])
-            .unwrap_or_default();
-        if field_id.is_empty() || route_id.is_empty() { continue; }
+#[derive(Debug, Deserialize)]
+pub struct PermissionCheck {
+    pub verb: String,
+    pub resource: String,
+    /// "" / None for the core group
+    #[serde(default)]
+    pub api_group: Option<String>,
+    /// A single object, e.g. one ConfigMap
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Defaults to the ServiceAccount's namespace
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
 
-        let path = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.rules[0].http.paths[0].path}"])
-            .unwrap_or_else(|_| "/".to_string());
-        let path_type = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.rules[0].http.paths[0].pathType}"])
-            .unwrap_or_else(|_| "Prefix".to_string());
-        let host = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.rules[0].host}"])
-            .ok().filter(|s| !s.is_empty());
-        let svc = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.rules[0].http.paths[0].backend.service.name}"])
-            .unwrap_or_default();
-        let port_num: Option<u32> = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.rules[0].http.paths[0].backend.service.port.number}"])
-            .ok().and_then(|s| s.parse().ok());
-        let port_name = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.rules[0].http.paths[0].backend.service.port.name}"])
-            .ok().filter(|s| !s.is_empty());
-        let tls_secret = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.spec.tls[0].secretName}"])
-            .ok().filter(|s| !s.is_empty());
-        let address = run_kubectl(&["get", "ingress", name, "-n", ns,
-            "-o", "jsonpath={.status.loadBalancer.ingress[0].ip}"])
-            .ok().filter(|s| !s.is_empty());
+#[derive(Debug, Serialize)]
+pub struct PermissionCheckResult {
+    pub verb: String,
+    pub resource: String,
+    pub namespace: String,
+    pub allowed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
 
-        routes.push(DiscoveredRoute {
-            route_id, field_id, ingress_name: name.to_string(),
-            ingress_namespace: ns.to_string(), host,
-            path: if path.is_empty() { "/".to_string() } else { path },
-            path_type: if path_type.is_empty() { "Prefix".to_string() } else { path_type },
-            target_service: svc, target_namespace: ns.to_string(),
-            target_port_number: port_num, target_port_name: port_name,
-            ingress_class_name: class.to_string(), tls_secret, address,
-        });
-    }
-    routes
+/// One row of `kubectl auth can-i --list`.
+#[derive(Debug, Serialize)]
+pub struct EffectiveRule {
+    pub resource: String,
+    pub non_resource_urls: Vec<String>,
+    pub resource_names: Vec<String>,
+    pub verbs: Vec<String>,
 }
 
-#[tauri::command]
-fn list_services_in_namespace(namespace: String) -> Vec<(String, Vec<String>)> {
-    let raw = run_kubectl(&[
-        "get", "services", "-n", &namespace, "--no-headers",
-        "-o", "custom-columns=NAME:.metadata.name,PORTS:.spec.ports[*].port",
-    ]).unwrap_or_default();
-    raw.lines().filter_map(|line| {
-        let mut parts = line.splitn(2, char::is_whitespace);
-        let name = parts.next()?.trim().to_string();
-        if name.is_empty() || name == "<none>" { return None; }
-        let ports_str = parts.next().unwrap_or("").trim();
-        let ports: Vec<String> = if ports_str == "<none>" { vec![] } else {
-            ports_str.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
-        };
-        Some((name, ports))
-    }).collect()
+#[derive(Debug, Serialize)]
+pub struct ServiceAccountPermissions {
+    pub service_account: String,
+    pub namespace: String,
+    /// Everything the ServiceAccount may do in its namespace
+    pub rules: Vec<EffectiveRule>,
+    pub checks: Vec<PermissionCheckResult>,
 }
 
-#[tauri::command]
-fn list_namespaces() -> Vec<String> {
-    run_kubectl(&["get", "namespaces", "--no-headers",
-        "-o", "custom-columns=NAME:.metadata.name"])
-        .unwrap_or_default()
-        .lines()
-        .map(|l| l.trim().to_string())
-        .filter(|l| !l.is_empty())
+/// Rows of `auth can-i --list`: an optional resource followed by three
+/// bracketed lists (non-resource URLs, resource names, verbs).
+fn parse_can_i_list(raw: &str) -> Vec<EffectiveRule> {
+    raw.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut resource = String::new();
+            let mut lists: Vec<Vec<String>> = Vec::new();
+            let mut rest = line.trim();
+            while !rest.is_empty() {
+                if let Some(inner) = rest.strip_prefix('[') {
+                    let end = inner.find(']')?;
+                    lists.push(inner[..end].split_whitespace().map(String::from).collect());
+                    rest = inner[end + 1..].trim_start();
+                } else {
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    resource = rest[..end].to_string();
+                    rest = rest[end..].trim_start();
+                }
+            }
+            let [non_resource_urls, resource_names, verbs]: [Vec<String>; 3] = lists.try_into().ok()?;
+            Some(EffectiveRule { resource, non_resource_urls, resource_names, verbs })
+        })
         .collect()
 }
 
+/// Effective permissions of a ServiceAccount, via impersonation: the full
+/// `auth can-i --list` for its namespace plus yes/no answers to `checks`.
+/// Needs impersonate rights for the current kubeconfig user.
+#[tauri::command]
+fn check_service_account_permissions(
+    namespace: String,
+    service_account: String,
+    checks: Vec<PermissionCheck>,
+) -> Result<ServiceAccountPermissions, String> {
+    let user = format!("system:serviceaccount:{}:{}", namespace, service_account);
+    let listed = run_kubectl(&["auth", "can-i", "--list", "--as", &user, "-n", &namespace])?;
+
+    let checks = checks
+        .into_iter()
+        .map(|check| {
+            let ns = check.namespace.clone().unwrap_or_else(|| namespace.clone());
+            let resource = match check.api_group.as_deref().filter(|g| !g.is_empty()) {
+                Some(group) => format!("{}.{}", check.resource, group),
+                None => check.resource.clone(),
+            };
+            let mut args = vec!["auth", "can-i", check.verb.as_str(), resource.as_str()];
+            if let Some(name) = check.name.as_deref().filter(|n| !n.is_empty()) {
+                args.push(name);
+            }
+            args.extend(["--as", user.as_str(), "-n", ns.as_str()]);
+            // "no" exits non-zero, so read the answer rather than the status
+            let (stdout, stderr, _) = run_kubectl_output(&args);
+            let answer = stdout.trim();
+            let allowed = answer == "yes" || answer.starts_with("yes ");
+            let reason = answer
+                .split_once(" - ")
+                .map(|(_, why)| why.to_string())
+                .or_else(|| (!stderr.trim().is_empty()).then(|| stderr.trim().to_string()));
+            PermissionCheckResult { verb: check.verb, resource, namespace: ns, allowed, reason }
+        })
+        .collect();
+
+    Ok(ServiceAccountPermissions {
+        service_account,
+        namespace,
+        rules: parse_can_i_list(&listed),
+        checks,
+    })
+}
+
 // ─── File Watcher ─────────────────────────────────────────────────────────────
 
 /// Payload emitted to the frontend when a YAML file changes.
@@ -3065,12 +11690,14 @@ pub struct WatcherState(pub Mutex<Option<RecommendedWatcher>>);
 fn watch_project(
     app: tauri::AppHandle,
     state: tauri::State<WatcherState>,
+    roots: tauri::State<ProjectRootState>,
     project_path: String,
 ) -> Result<(), String> {
     let watch_path = PathBuf::from(&project_path);
     if !watch_path.exists() {
         return Err(format!("Path does not exist: {}", project_path));
     }
+    register_project_root(&roots, &project_path)?;
 
     // Debounce state: last event time per path
     let debounce: Arc<Mutex<std::collections::HashMap<PathBuf, Instant>>> =
@@ -3150,6 +11777,9 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .manage(WatcherState(Mutex::new(None)))
+        .manage(ProjectRootState::default())
+        .manage(ConfirmationState::default())
+        .manage(EventWatchState(Mutex::new((0, None))))
         .invoke_handler(tauri::generate_handler![
             // Project / file IO
             open_folder_dialog,
@@ -3159,9 +11789,12 @@ fn main() {
             save_yaml_file,
             // Generation (new)
             generate_field,
+            generate_hpa,
             generate_infra,
+            import_compose,
             // Deploy / delete (new)
             deploy_resource,
+            deploy_resource_dry_run,
             remove_resource,
             diff_resource,
             get_field_logs,
@@ -3171,33 +11804,86 @@ fn main() {
             delete_field_files,
             kubectl_delete_by_label,
             apply_replicas,
+            apply_replicas_dry_run,
             kubectl_apply,
+            kubectl_apply_dry_run,
             kubectl_apply_async,
+            kubectl_apply_async_dry_run,
             get_pod_logs,
             get_events,
+            watch_events,
+            unwatch_events,
+            get_resource_metrics,
             // Helm
             helm_template,
             helm_template_async,
             helm_install,
             helm_install_async,
+            helm_install_dry_run,
+            helm_install_async_dry_run,
             helm_uninstall,
             helm_available,
+            export_helm_chart,
+            // Encrypted secrets
+            read_secret_file,
+            save_secret_file,
+            encrypt_secret_file,
             // Layout
             save_endfield_layout,
             load_endfield_layout,
+            // Project config / protected targets
+            load_project_config,
+            save_project_config,
+            plan_remove_resource,
+            plan_delete_field_files,
+            plan_delete_by_label,
+            plan_helm_uninstall,
+            plan_delete_ingress_route,
+            plan_rollout_action,
+            plan_trigger_cronjob,
+            // Audit log
+            query_audit_log,
             // Deploy Image
             deploy_image,
+            deploy_image_dry_run,
+            // Container registries
+            list_image_tags,
+            resolve_image_digest,
+            check_image_updates,
+            list_unpinned_images,
+            upsert_pull_secret,
+            list_pull_secrets,
+            attach_pull_secret,
             // File watcher
             watch_project,
             unwatch_project,
             // Ingress Nginx
             detect_ingress_nginx,
             apply_ingress_route,
+            apply_ingress_route_dry_run,
             get_ingress_route_yaml,
             delete_ingress_route,
             discover_ingress_routes,
             list_services_in_namespace,
             list_namespaces,
+            list_storage_classes,
+            // Batch jobs
+            trigger_cronjob,
+            list_job_runs,
+            get_job_run_logs,
+            // Rollouts
+            get_rollout_status,
+            watch_rollout,
+            get_rollout_history,
+            restart_rollout,
+            set_rollout_paused,
+            undo_rollout,
+            // Network policies
+            generate_network_policies,
+            check_network_policies,
+            // Service accounts / RBAC
+            list_api_resources,
+            check_service_account_permissions,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -8,19 +8,13 @@ import {
   generateInfra,
   deployResource,
   deployImage,
+  listStorageClasses,
   type YamlNode,
   type FieldConfig,
   type InfraConfig,
   type DeployImageRequest,
+  type StorageClassInfo,
 } from "../store/tauriStore";
-import {
-  generatePostgresRawManifests,
-  generatePostgresStatefulSet,
-  generatePostgresSecret,
-  generatePostgresService,
-  generatePostgresNamespace,
-  type PostgresConfig,
-} from "../store/postgresStore";
 import { genId } from "../layout/utils";
 
 // ─── Types ─────────────────────────────────────────────────────────
@@ -784,6 +778,11 @@ export function AddFieldModal({ onClose, namespace }: Props) {
   const [name, setName] = useState("");
   const [port, setPort] = useState(5432);
   const [envVars, setEnvVars] = useState<FieldEnvVar[]>([]);
+  const [storageSize, setStorageSize] = useState("10Gi");
+  const [storageClass, setStorageClass] = useState("");
+  const [storageClasses, setStorageClasses] = useState<StorageClassInfo[]>(
+    [],
+  );
   const [creating, setCreating] = useState(false);
   const [result, setResult] = useState<string | null>(null);
   const [hasHelm, setHasHelm] = useState(true);
@@ -826,6 +825,15 @@ export function AddFieldModal({ onClose, namespace }: Props) {
 
   const preset = FIELD_PRESETS[selPreset];
   const helmPreset = HELM_PRESETS[selHelm];
+  const stateful = isStatefulPreset(preset);
+
+  useEffect(() => {
+    if (!stateful) return;
+    listStorageClasses()
+      .then(setStorageClasses)
+      .catch(() => setStorageClasses([]));
+  }, [stateful]);
+
   const tt = getStyle(tab === "helm" ? helmPreset.typeId : preset.typeId);
 
   const pickPreset = (key: string) => {
@@ -834,6 +842,8 @@ export function AddFieldModal({ onClose, namespace }: Props) {
     setName(key);
     setPort(p.defaultPort);
     setEnvVars(p.envVars.map((e) => ({ ...e })));
+    setStorageSize(p.storageSize ?? "10Gi");
+    setStorageClass("");
     setStep("configure");
     setTimeout(() => nameRef.current?.focus(), 60);
   };
@@ -860,91 +870,6 @@ export function AddFieldModal({ onClose, namespace }: Props) {
       .toLowerCase()
       .replace(/[^a-z0-9-]/g, "-");
 
-    // ── Postgres: use dedicated generator for proper Secret + StatefulSet ──
-    if (selPreset === "postgres") {
-      try {
-        const pgPassword =
-          envVars.find((e) => e.key === "POSTGRES_PASSWORD")?.value ??
-          "changeme";
-        const pgUser =
-          envVars.find((e) => e.key === "POSTGRES_USER")?.value ?? "postgres";
-        const pgDb =
-          envVars.find((e) => e.key === "POSTGRES_DB")?.value ?? "appdb";
-        const pgNamespace = "apps";
-        const pgCfg: PostgresConfig = {
-          fieldId: n,
-          name: n,
-          namespace: pgNamespace,
-          postgresVersion: "16-alpine",
-          databaseName: pgDb,
-          username: pgUser,
-          password: pgPassword,
-          port,
-          storageSize: preset.storageSize ?? "10Gi",
-          storageClass: "",
-          deployMode: "raw",
-          chartVersion: "",
-          enableMetrics: false,
-        };
-        setResult("Generating postgres files...");
-        // generatePostgresRawManifests returns project-relative paths:
-        //   "namespace.yaml"              → save at projectPath/namespace.yaml
-        //   "databases/n/n-secret.yaml"   → save at projectPath/databases/n/...
-        const manifests = generatePostgresRawManifests(pgCfg);
-        const saves = Object.entries(manifests).map(([rel, yaml]) =>
-          saveYamlFile(`${projectPath}/${rel}`, yaml),
-        );
-        await Promise.all(saves);
-
-        // Deploy only the databases/n dir (not the namespace.yaml separately)
-        const fieldDir = `${projectPath}/databases/${n}`;
-
-        // Ensure namespace exists first
-        await deployResource(
-          "namespace",
-          "raw",
-          projectPath,
-          pgNamespace,
-          {},
-        ).catch(() => {});
-
-        addNode({
-          id: genId("node"),
-          label: n,
-          kind: "StatefulSet",
-          image: preset.image,
-          type_id: "database",
-          namespace: pgNamespace,
-          file_path: `${fieldDir}/${n}-statefulset.yaml`,
-          replicas: 1,
-          source: "raw",
-          x: 20 + Math.random() * 40,
-          y: 20 + Math.random() * 40,
-        });
-        setResult("Deploying postgres to cluster...");
-        const deployResult = await deployResource(
-          n,
-          "raw",
-          fieldDir,
-          pgNamespace,
-        );
-        if (!deployResult.success) {
-          setResult(
-            `Error: ${deployResult.stderr?.split("\n")[0] ?? "deploy failed"}`,
-          );
-          setCreating(false);
-          return;
-        }
-        setResult("OK: PostgreSQL deployed");
-      } catch (e) {
-        setResult(`Error: ${String(e)}`);
-      }
-      setCreating(false);
-      setTimeout(onClose, 900);
-      return;
-    }
-    // ── end postgres special case ──────────────────────────────────────────
-
     const fieldConfig: FieldConfig = {
       id: n,
       label: n,
@@ -954,6 +879,12 @@ export function AddFieldModal({ onClose, namespace }: Props) {
       port,
       env: envVars.map((e) => ({ key: e.key, value: e.value })),
       project_path: projectPath,
+      storage: stateful
+        ? {
+            size: storageSize.trim() || null,
+            storage_class: storageClass || null,
+          }
+        : undefined,
     };
     try {
       setResult("Generating files...");
@@ -973,7 +904,7 @@ export function AddFieldModal({ onClose, namespace }: Props) {
       addNode({
         id: genId("node"),
         label: n,
-        kind: isStatefulPreset(preset) ? "StatefulSet" : preset.kind,
+        kind: stateful ? "StatefulSet" : preset.kind,
         image: preset.image || `${n}:latest`,
         type_id: preset.typeId,
         namespace: genResult.namespace,
@@ -1599,6 +1530,35 @@ export function AddFieldModal({ onClose, namespace }: Props) {
                 }
               />
             </div>
+            {stateful && (
+              <div style={{ display: "flex", gap: 10 }}>
+                <div style={{ flex: 1 }}>
+                  <label style={LBL}>Storage size</label>
+                  <input
+                    value={storageSize}
+                    onChange={(e) => setStorageSize(e.target.value)}
+                    placeholder="10Gi"
+                    style={INP}
+                  />
+                </div>
+                <div style={{ flex: 1 }}>
+                  <label style={LBL}>Storage class</label>
+                  <select
+                    value={storageClass}
+                    onChange={(e) => setStorageClass(e.target.value)}
+                    style={{ ...INP, cursor: "pointer" }}
+                  >
+                    <option value="">cluster default</option>
+                    {storageClasses.map((sc) => (
+                      <option key={sc.name} value={sc.name}>
+                        {sc.name}
+                        {sc.is_default ? " (default)" : ""}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              </div>
+            )}
             {envVars.length > 0 && (
               <div>
                 <label style={LBL}>Environment</label>
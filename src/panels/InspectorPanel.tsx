@@ -193,6 +193,7 @@ function PodRow({
     name: string;
     namespace: string;
     phase: string;
+    status: string;
     ready: number;
     total: number;
     restarts: number;
@@ -217,7 +218,7 @@ function PodRow({
           borderRadius: "50%",
           flexShrink: 0,
           background:
-            pod.phase === "Running"
+            pod.status === "Running"
               ? "var(--status-ok)"
               : pod.phase === "Pending"
                 ? "var(--status-warn)"
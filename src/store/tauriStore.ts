@@ -46,13 +46,36 @@ export interface ScanResult {
   errors: string[];
 }
 
+export interface ContainerStatusInfo {
+  name: string;
+  init: boolean;
+  image: string;
+  image_id: string | null;
+  ready: boolean;
+  restarts: number;
+  state: "running" | "waiting" | "terminated";
+  reason: string | null;
+  message: string | null;
+  exit_code: number | null;
+  started_at: string | null;
+  last_termination_reason: string | null;
+  last_exit_code: number | null;
+}
+
 export interface PodInfo {
   name: string;
   namespace: string;
+  /** Pending | Running | Succeeded | Failed | Unknown */
   phase: string;
+  /** kubectl STATUS column, e.g. CrashLoopBackOff, Init:0/1, Terminating */
+  status: string;
   ready: number;
   total: number;
   restarts: number;
+  node: string | null;
+  pod_ip: string | null;
+  start_time: string | null;
+  containers: ContainerStatusInfo[];
 }
 
 export interface FieldStatus {
@@ -98,7 +121,163 @@ export interface EnvVar {
   value: string;
 }
 
-/** mirrors Rust FieldConfig */
+/** mirrors Rust ProbeConfig */
+export interface ProbeConfig {
+  /** "http" | "tcp" | "exec" | "none" ("none" disables the default probe) */
+  kind: "http" | "tcp" | "exec" | "none";
+  path?: string | null;
+  port?: number | null;
+  command?: string[];
+  initial_delay_seconds?: number | null;
+  period_seconds?: number | null;
+  timeout_seconds?: number | null;
+  failure_threshold?: number | null;
+}
+
+/** mirrors Rust SecurityContextConfig */
+export interface SecurityContextConfig {
+  run_as_non_root?: boolean | null;
+  run_as_user?: number | null;
+  run_as_group?: number | null;
+  fs_group?: number | null;
+  read_only_root_filesystem?: boolean | null;
+  allow_privilege_escalation?: boolean | null;
+  drop_capabilities?: string[];
+  add_capabilities?: string[];
+}
+
+/** mirrors Rust ContainerRuntime — unset fields fall back to type defaults */
+export interface ContainerRuntime {
+  liveness_probe?: ProbeConfig | null;
+  readiness_probe?: ProbeConfig | null;
+  startup_probe?: ProbeConfig | null;
+  security_context?: SecurityContextConfig | null;
+  command?: string[];
+  args?: string[];
+  termination_grace_period_seconds?: number | null;
+  /** native preStop sleep; 0 disables it */
+  pre_stop_sleep_seconds?: number | null;
+}
+
+/** mirrors Rust StorageConfig — volume claim template of stateful fields */
+export interface StorageConfig {
+  /** e.g. "10Gi" (default) */
+  size?: string | null;
+  /** null/empty → cluster default StorageClass */
+  storage_class?: string | null;
+  access_mode?:
+    | "ReadWriteOnce"
+    | "ReadWriteOncePod"
+    | "ReadWriteMany"
+    | "ReadOnlyMany"
+    | null;
+  mount_path?: string | null;
+}
+
+/** mirrors Rust VolumeConfig */
+export interface VolumeConfig {
+  name: string;
+  kind: "configMap" | "secret" | "emptyDir" | "pvc";
+  /** ConfigMap / Secret / PVC name (unused for emptyDir) */
+  source?: string | null;
+  mount_path: string;
+  sub_path?: string | null;
+  read_only?: boolean;
+  size_limit?: string | null;
+  medium?: string | null;
+}
+
+/** mirrors Rust CustomMetricTarget */
+export interface CustomMetricTarget {
+  kind: "pods" | "external";
+  name: string;
+  average_value: string;
+  selector?: Record<string, string>;
+}
+
+/** mirrors Rust AutoscalingConfig */
+export interface AutoscalingConfig {
+  min_replicas: number;
+  max_replicas: number;
+  cpu_utilization?: number | null;
+  memory_utilization?: number | null;
+  custom_metrics?: CustomMetricTarget[];
+  scale_down_stabilization_seconds?: number | null;
+}
+
+/** mirrors Rust AvailabilityConfig */
+export interface AvailabilityConfig {
+  pdb?: boolean;
+  pdb_min_available?: string | null;
+  pdb_max_unavailable?: string | null;
+  spread?: "zone" | "hostname" | null;
+  anti_affinity?: "preferred" | "required" | null;
+}
+
+/** mirrors Rust BatchConfig — Job / CronJob settings */
+export interface BatchConfig {
+  schedule?: string | null;
+  time_zone?: string | null;
+  concurrency_policy?: "Allow" | "Forbid" | "Replace" | null;
+  backoff_limit?: number | null;
+  ttl_seconds_after_finished?: number | null;
+  restart_policy?: "OnFailure" | "Never" | null;
+  active_deadline_seconds?: number | null;
+  successful_jobs_history_limit?: number | null;
+  failed_jobs_history_limit?: number | null;
+}
+
+/** mirrors Rust TolerationConfig */
+export interface TolerationConfig {
+  key?: string | null;
+  operator?: "Equal" | "Exists" | null;
+  value?: string | null;
+  effect?: "NoSchedule" | "PreferNoSchedule" | "NoExecute" | null;
+}
+
+/** mirrors Rust PlacementConfig */
+export interface PlacementConfig {
+  node_selector?: Record<string, string>;
+  tolerations?: TolerationConfig[];
+  tolerate_all?: boolean;
+  host_network?: boolean;
+}
+
+/** mirrors Rust ContainerMount */
+export interface ContainerMount {
+  name: string;
+  mount_path: string;
+  read_only?: boolean;
+}
+
+/** mirrors Rust ExtraContainer — init container or sidecar */
+export interface ExtraContainer {
+  name: string;
+  image: string;
+  command?: string[];
+  args?: string[];
+  env?: EnvVar[];
+  secret_env?: string[];
+  ports?: number[];
+  volume_mounts?: ContainerMount[];
+}
+
+/** mirrors Rust RbacRule */
+export interface RbacRule {
+  api_groups?: string[];
+  resources: string[];
+  verbs: string[];
+  resource_names?: string[];
+}
+
+/** mirrors Rust RbacConfig */
+export interface RbacConfig {
+  rules?: RbacRule[];
+  cluster_rules?: RbacRule[];
+  automount_token?: boolean;
+}
+
+/** mirrors Rust FieldConfig — everything after project_path is optional */
 export interface FieldConfig {
   id: string;
   label: string;
@@ -108,6 +287,36 @@ export interface FieldConfig {
   port: number;
   env: EnvVar[];
   project_path: string;
+  /** "sops" | "external-secrets" | "sealed-secrets"; null → project default */
+  secret_backend?: string | null;
+  runtime?: ContainerRuntime;
+  storage?: StorageConfig;
+  volumes?: VolumeConfig[];
+  autoscaling?: AutoscalingConfig | null;
+  availability?: AvailabilityConfig;
+  /** null → StatefulSet for stateful images, Deployment otherwise */
+  workload_kind?:
+    | "deployment"
+    | "statefulset"
+    | "daemonset"
+    | "job"
+    | "cronjob"
+    | null;
+  batch?: BatchConfig;
+  placement?: PlacementConfig;
+  init_containers?: ExtraContainer[];
+  sidecars?: ExtraContainer[];
+  rbac?: RbacConfig | null;
+}
+
+/** mirrors Rust StorageClassInfo */
+export interface StorageClassInfo {
+  name: string;
+  provisioner: string;
+  reclaim_policy: string;
+  volume_binding_mode: string;
+  allow_volume_expansion: boolean;
+  is_default: boolean;
 }
 
 /** mirrors Rust HelmInfraConfig */
@@ -161,6 +370,7 @@ export interface DeleteResult {
   file_errors: string[];
   kubectl_output: string | null;
   kubectl_error: string | null;
+  commands_run: string[];
 }
 
 export interface DiffResult {
@@ -202,12 +412,16 @@ function devFallback<T>(cmd: string, args?: Record<string, unknown>): T {
       return DEV_CLUSTER_STATUS as T;
     case "apply_replicas":
     case "scan_project_files":
+    case "get_events":
+    case "list_storage_classes":
       return [] as T;
     case "kubectl_apply":
       return "✓ Applied (dev)" as T;
     case "save_yaml_file":
     case "delete_field_files":
     case "save_endfield_layout":
+    case "watch_events":
+    case "unwatch_events":
       return undefined as T;
     case "load_endfield_layout":
       throw new Error("No .endfield file found");
@@ -662,8 +876,38 @@ export async function getPodLogs(
   return safeInvoke<string>("get_pod_logs", { namespace, podName, tail });
 }
 
-export async function getEvents(namespace: string): Promise<string> {
-  return safeInvoke<string>("get_events", { namespace });
+export interface ClusterEvent {
+  namespace: string;
+  type: "Normal" | "Warning";
+  reason: string;
+  object_kind: string;
+  object_name: string;
+  message: string;
+  count: number;
+  first_seen: string | null;
+  last_seen: string | null;
+  /** Field (workload / app label) the object belongs to, if known */
+  field: string | null;
+}
+
+export async function getEvents(
+  namespace: string,
+  field?: string,
+): Promise<ClusterEvent[]> {
+  return safeInvoke<ClusterEvent[]>("get_events", { namespace, field });
+}
+
+/**
+ * Stream new events as `cluster-event` (payload: ClusterEvent). The backend
+ * emits `cluster-event-watch-ended` when the watch closes on its own (not after
+ * unwatchEvents or a replacing watchEvents); call again to resume.
+ */
+export async function watchEvents(namespace: string, field?: string): Promise<void> {
+  return safeInvoke("watch_events", { namespace, field });
+}
+
+export async function unwatchEvents(): Promise<void> {
+  return safeInvoke("unwatch_events");
 }
 
 // ─── .endfield layout ────────────────────────────────────────────────────────
@@ -715,6 +959,11 @@ export async function generateField(
   return safeInvoke<GenerateResult>("generate_field", { config });
 }
 
+/** StorageClasses in the cluster, for `FieldConfig.storage.storage_class`. */
+export async function listStorageClasses(): Promise<StorageClassInfo[]> {
+  return safeInvoke<StorageClassInfo[]>("list_storage_classes");
+}
+
 /**
  * Generate Helm scaffold or validate Raw YAML structure for an Infra component.
  * For Helm: writes infra/<id>/namespace.yaml, helm/Chart.yaml, helm/values.yaml, rendered/.gitkeep